@@ -0,0 +1,180 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Backoff
+//!
+//! This module provides the exponential backoff used between retries.
+//!
+//! [`Backoff`] is an iterator of waits growing by a multiplier up to a cap, optionally
+//! jittered so that many clients failing at once do not retry in lockstep. It is used by
+//! the retrying wrappers of the crate and can be used standalone with a [`Timer`].
+//!
+//! [`Timer`]: crate::timer::Timer
+
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::Duration,
+};
+
+/// An endless iterator of exponentially growing, capped waits.
+///
+/// The first wait is `base` and every following one is the previous multiplied by the
+/// multiplier, 2 by default, until it reaches `max`. With a jitter `j` between 0 and 1,
+/// each wait is reduced by a random fraction of up to `j` of itself, so a jitter of 1
+/// spreads waits over the whole range from zero. Jitter does not affect the growth of the
+/// following waits. The iterator never ends; callers bound the number of attempts.
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    base: Duration,
+    max: Duration,
+    multiplier: f64,
+    jitter: f64,
+    next: Duration,
+    rng: u64,
+}
+
+impl Backoff {
+    /// Creates a new backoff without jitter and a multiplier of 2.
+    ///
+    /// # Arguments
+    ///
+    /// * `base` - The first wait.
+    /// * `max` - The maximum wait, raised to `base` if lower.
+    ///
+    /// # Returns
+    ///
+    /// A new `Backoff` instance.
+    pub fn new(base: Duration, max: Duration) -> Self {
+        Backoff {
+            base,
+            max: max.max(base),
+            multiplier: 2.0,
+            jitter: 0.0,
+            next: base,
+            rng: RandomState::new().build_hasher().finish() | 1,
+        }
+    }
+
+    /// Sets the factor each wait grows by.
+    ///
+    /// # Arguments
+    ///
+    /// * `multiplier` - The growth factor, raised to 1 if lower or not a number.
+    ///
+    /// # Returns
+    ///
+    /// The updated `Backoff`.
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = if multiplier >= 1.0 { multiplier } else { 1.0 };
+        self
+    }
+
+    /// Sets the maximum fraction of each wait removed at random.
+    ///
+    /// # Arguments
+    ///
+    /// * `jitter` - The jitter fraction, clamped between 0 and 1.
+    ///
+    /// # Returns
+    ///
+    /// The updated `Backoff`.
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = if jitter > 0.0 { jitter.min(1.0) } else { 0.0 };
+        self
+    }
+
+    /// Restarts the sequence from the base wait.
+    pub fn reset(&mut self) {
+        self.next = self.base;
+    }
+
+    /// Returns a random number in `[0, 1)` from an xorshift generator.
+    fn random(&mut self) -> f64 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        (self.rng >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+impl Iterator for Backoff {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        let wait = self.next;
+        self.next = Duration::try_from_secs_f64(wait.as_secs_f64() * self.multiplier)
+            .map_or(self.max, |next| next.min(self.max));
+
+        if self.jitter == 0.0 {
+            return Some(wait);
+        }
+        let factor = 1.0 - self.jitter * self.random();
+        Some(wait.mul_f64(factor))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(backoff: Backoff, n: usize) -> Vec<u128> {
+        backoff.take(n).map(|wait| wait.as_millis()).collect()
+    }
+
+    #[test]
+    fn doubles_up_to_the_cap() {
+        let backoff = Backoff::new(Duration::from_millis(100), Duration::from_millis(1000));
+
+        assert_eq!(millis(backoff, 7), [100, 200, 400, 800, 1000, 1000, 1000]);
+    }
+
+    #[test]
+    fn grows_by_the_multiplier() {
+        let backoff =
+            Backoff::new(Duration::from_millis(10), Duration::from_secs(1)).with_multiplier(3.0);
+
+        assert_eq!(millis(backoff, 5), [10, 30, 90, 270, 810]);
+    }
+
+    #[test]
+    fn multipliers_below_one_keep_the_wait_constant() {
+        for multiplier in [0.5, f64::NAN] {
+            let backoff = Backoff::new(Duration::from_millis(10), Duration::from_secs(1))
+                .with_multiplier(multiplier);
+
+            assert_eq!(millis(backoff, 3), [10, 10, 10]);
+        }
+    }
+
+    #[test]
+    fn cap_below_the_base_is_raised_to_it() {
+        let backoff = Backoff::new(Duration::from_millis(100), Duration::from_millis(10));
+
+        assert_eq!(millis(backoff, 3), [100, 100, 100]);
+    }
+
+    #[test]
+    fn reset_restarts_from_the_base() {
+        let mut backoff = Backoff::new(Duration::from_millis(100), Duration::from_secs(10));
+        backoff.by_ref().take(3).for_each(drop);
+
+        backoff.reset();
+
+        assert_eq!(backoff.next(), Some(Duration::from_millis(100)));
+    }
+
+    #[test]
+    fn jitter_only_shortens_waits_without_changing_the_growth() {
+        let plain = Backoff::new(Duration::from_millis(100), Duration::from_secs(10));
+        let jittered = plain.clone().with_jitter(0.5);
+
+        for (wait, full) in jittered.zip(plain).take(20) {
+            assert!(
+                wait <= full && wait >= full / 2,
+                "{wait:?} outside of {full:?}"
+            );
+        }
+    }
+}
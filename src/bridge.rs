@@ -0,0 +1,395 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Bridge
+//!
+//! This module provides a helper relaying messages from one broker to another.
+//!
+//! The [`Bridge`] consumes from a dispatcher, optionally transforms every message, and
+//! publishes the result through a publisher, e.g. to migrate traffic between brokers or to
+//! forward events across environments.
+
+use crate::{
+    dispatcher::{Dispatcher, DispatcherDefinition},
+    errors::MessagingError,
+    handler::{ConsumerHandler, ConsumerMessage},
+    publisher::{PublishMessage, Publisher},
+};
+use async_trait::async_trait;
+use opentelemetry::Context;
+use std::sync::Arc;
+
+type Transform = Arc<dyn Fn(ConsumerMessage) -> PublishMessage + Send + Sync>;
+
+/// Relays messages consumed by a dispatcher to a publisher.
+///
+/// Every message received on a subscribed definition is transformed and published. The
+/// message is only acknowledged once the publish succeeded: a failed publish fails the
+/// message, so the source dispatcher nacks or requeues it according to its own policy.
+///
+/// Without a transform, messages are published with `PublishMessage::from`, to a
+/// destination named after their source.
+pub struct Bridge<D> {
+    dispatcher: D,
+    publisher: Arc<dyn Publisher>,
+    definitions: Vec<DispatcherDefinition>,
+    transform: Transform,
+}
+
+impl<D> Bridge<D>
+where
+    D: Dispatcher,
+{
+    /// Creates a new bridge.
+    ///
+    /// # Arguments
+    ///
+    /// * `dispatcher` - The dispatcher to consume from.
+    /// * `publisher` - The publisher to relay messages to.
+    ///
+    /// # Returns
+    ///
+    /// A new `Bridge` instance without subscriptions.
+    pub fn new(dispatcher: D, publisher: Arc<dyn Publisher>) -> Self {
+        Bridge {
+            dispatcher,
+            publisher,
+            definitions: Vec::new(),
+            transform: Arc::new(|msg: ConsumerMessage| {
+                let to = msg.from.clone();
+                let mut relayed = PublishMessage::from(msg);
+                relayed.to = to;
+                relayed
+            }),
+        }
+    }
+
+    /// Adds a subscription whose messages are relayed.
+    ///
+    /// # Arguments
+    ///
+    /// * `definition` - The definition specifying what to consume.
+    ///
+    /// # Returns
+    ///
+    /// The updated `Bridge`.
+    pub fn subscribe(mut self, definition: DispatcherDefinition) -> Self {
+        self.definitions.push(definition);
+        self
+    }
+
+    /// Sets the transformation applied to every relayed message.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The function building the message to publish from the consumed one.
+    ///
+    /// # Returns
+    ///
+    /// The updated `Bridge`.
+    pub fn transform<F>(mut self, f: F) -> Self
+    where
+        F: Fn(ConsumerMessage) -> PublishMessage + Send + Sync + 'static,
+    {
+        self.transform = Arc::new(f);
+        self
+    }
+
+    /// Registers the subscriptions, freezes the dispatcher and relays messages until
+    /// consumption stops.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The OpenTelemetry context every relayed message is published with.
+    ///
+    /// # Returns
+    ///
+    /// The result of the dispatcher's `consume_blocking`.
+    pub async fn run(self, ctx: &Context) -> Result<(), MessagingError> {
+        let handler: Arc<dyn ConsumerHandler> = Arc::new(RelayHandler {
+            publisher: self.publisher,
+            transform: self.transform,
+            ctx: ctx.clone(),
+        });
+
+        let dispatcher = self
+            .definitions
+            .iter()
+            .fold(self.dispatcher, |dispatcher, definition| {
+                dispatcher.register(definition, handler.clone())
+            })
+            .freeze();
+
+        dispatcher.consume_blocking().await
+    }
+}
+
+struct RelayHandler {
+    publisher: Arc<dyn Publisher>,
+    transform: Transform,
+    ctx: Context,
+}
+
+#[async_trait]
+impl ConsumerHandler for RelayHandler {
+    async fn exec(&self, _ctx: &Context, msg: &ConsumerMessage) -> Result<(), MessagingError> {
+        let relayed = (self.transform)(msg.clone());
+        self.publisher.publish(&self.ctx, &relayed).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{CapturingDispatcher, TestPublisher, block_on, delivered, message};
+    #[cfg(feature = "test-util")]
+    use crate::{
+        handler::Acker, middleware::size_limit::SizeLimitedPublisher, test_support::TestHandler,
+        testing::in_memory::InMemoryBroker,
+    };
+    #[cfg(feature = "test-util")]
+    use std::sync::Mutex;
+
+    fn relay(
+        bridge: Bridge<CapturingDispatcher>,
+        dispatcher: &CapturingDispatcher,
+        data: &str,
+    ) -> Result<(), MessagingError> {
+        block_on(bridge.run(&Context::new())).unwrap();
+        let msg = delivered(&message("orders", data));
+        block_on(dispatcher.handler("orders").exec(&Context::new(), &msg))
+    }
+
+    #[test]
+    fn registers_every_subscription() {
+        let dispatcher = CapturingDispatcher::default();
+        let bridge = Bridge::new(dispatcher.clone(), Arc::new(TestPublisher::default()))
+            .subscribe(DispatcherDefinition::new("orders", None))
+            .subscribe(DispatcherDefinition::new("payments", Some("settled")));
+
+        block_on(bridge.run(&Context::new())).unwrap();
+
+        let names: Vec<_> = dispatcher
+            .registrations()
+            .into_iter()
+            .map(|definition| definition.to_string())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "sub(name=orders, type=*)",
+                "sub(name=payments, type=settled)"
+            ]
+        );
+    }
+
+    #[test]
+    fn relays_to_a_destination_named_after_the_source() {
+        let dispatcher = CapturingDispatcher::default();
+        let publisher = Arc::new(TestPublisher::default());
+        let bridge = Bridge::new(dispatcher.clone(), publisher.clone())
+            .subscribe(DispatcherDefinition::new("orders", None));
+
+        relay(bridge, &dispatcher, "created").unwrap();
+
+        let published = publisher.published();
+        assert_eq!(published.len(), 1);
+        assert_eq!(published[0].to, "orders");
+        assert_eq!(published[0].msg_type.as_deref(), Some("event"));
+        assert_eq!(publisher.published_data(), ["created"]);
+    }
+
+    #[test]
+    fn applies_the_transform() {
+        let dispatcher = CapturingDispatcher::default();
+        let publisher = Arc::new(TestPublisher::default());
+        let bridge = Bridge::new(dispatcher.clone(), publisher.clone())
+            .subscribe(DispatcherDefinition::new("orders", None))
+            .transform(|msg| {
+                let mut relayed = PublishMessage::from(msg);
+                relayed.to = "orders.mirror".to_owned();
+                relayed
+            });
+
+        relay(bridge, &dispatcher, "created").unwrap();
+
+        assert_eq!(publisher.published()[0].to, "orders.mirror");
+    }
+
+    #[test]
+    fn fails_the_message_when_the_publish_fails() {
+        let dispatcher = CapturingDispatcher::default();
+        let publisher = Arc::new(TestPublisher::default());
+        publisher.fail_with(Some(MessagingError::publisher("broker down")));
+        let bridge = Bridge::new(dispatcher.clone(), publisher.clone())
+            .subscribe(DispatcherDefinition::new("orders", None));
+
+        let result = relay(bridge, &dispatcher, "created");
+
+        assert_eq!(result, Err(MessagingError::publisher("broker down")));
+        assert!(publisher.published().is_empty());
+    }
+
+    /// An acker logging how messages were settled.
+    #[cfg(feature = "test-util")]
+    struct LogAcker(Arc<Mutex<Vec<String>>>);
+
+    #[cfg(feature = "test-util")]
+    #[async_trait]
+    impl Acker for LogAcker {
+        async fn ack(&self) -> Result<(), MessagingError> {
+            self.0.lock().unwrap().push("ack".to_owned());
+            Ok(())
+        }
+
+        async fn nack(&self, requeue: bool) -> Result<(), MessagingError> {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("nack requeue={requeue}"));
+            Ok(())
+        }
+    }
+
+    /// A handler settling every message through `exec_manual` and logging failures, the
+    /// way a broker with acknowledgements runs its handlers.
+    #[cfg(feature = "test-util")]
+    struct SettlingHandler {
+        inner: Arc<dyn ConsumerHandler>,
+        log: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[cfg(feature = "test-util")]
+    #[async_trait]
+    impl ConsumerHandler for SettlingHandler {
+        async fn exec(&self, ctx: &Context, msg: &ConsumerMessage) -> Result<(), MessagingError> {
+            let acker = Arc::new(LogAcker(self.log.clone()));
+            let result = self.inner.exec_manual(ctx, msg, acker).await;
+            if let Err(err) = &result {
+                self.log.lock().unwrap().push(format!("failed: {err}"));
+            }
+            result
+        }
+    }
+
+    /// An in-memory broker whose handlers settle messages like a broker with
+    /// acknowledgements.
+    #[cfg(feature = "test-util")]
+    struct Settling {
+        broker: InMemoryBroker,
+        log: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[cfg(feature = "test-util")]
+    impl Settling {
+        fn settling(&self, handler: Arc<dyn ConsumerHandler>) -> Arc<dyn ConsumerHandler> {
+            Arc::new(SettlingHandler {
+                inner: handler,
+                log: self.log.clone(),
+            })
+        }
+    }
+
+    #[cfg(feature = "test-util")]
+    #[async_trait]
+    impl Dispatcher for Settling {
+        fn register(
+            mut self,
+            definition: &DispatcherDefinition,
+            handler: Arc<dyn ConsumerHandler>,
+        ) -> Self {
+            self.register_ref(definition, handler);
+            self
+        }
+
+        fn register_ref(
+            &mut self,
+            definition: &DispatcherDefinition,
+            handler: Arc<dyn ConsumerHandler>,
+        ) -> &mut Self {
+            let handler = self.settling(handler);
+            self.broker.register_ref(definition, handler);
+            self
+        }
+
+        fn freeze(self) -> Self {
+            Settling {
+                broker: self.broker.freeze(),
+                log: self.log,
+            }
+        }
+
+        fn is_frozen(&self) -> bool {
+            self.broker.is_frozen()
+        }
+
+        async fn consume_blocking(&self) -> Result<(), MessagingError> {
+            self.broker.consume_blocking().await
+        }
+    }
+
+    #[cfg(feature = "test-util")]
+    fn bridged(target: Arc<dyn Publisher>, data: &[&str]) -> Vec<String> {
+        let source = InMemoryBroker::new();
+        for data in data {
+            block_on(source.publish(&Context::new(), &message("orders", data))).unwrap();
+        }
+        source.close();
+
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let dispatcher = Settling {
+            broker: source.clone(),
+            log: log.clone(),
+        };
+        let bridge = Bridge::new(dispatcher, target)
+            .subscribe(DispatcherDefinition::new("orders", None))
+            .transform(|msg| {
+                let mut relayed = PublishMessage::from(msg);
+                relayed.to = "orders.mirror".to_owned();
+                relayed.data = relayed.data.to_ascii_uppercase().into();
+                relayed
+            });
+
+        block_on(bridge.run(&Context::new())).unwrap();
+        assert_eq!(source.pending("orders"), 0);
+        log.lock().unwrap().clone()
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn relays_transformed_messages_between_in_memory_brokers() {
+        let target = InMemoryBroker::new();
+
+        let log = bridged(Arc::new(target.clone()), &["created", "paid"]);
+
+        assert_eq!(log, ["ack", "ack"]);
+        let target = target
+            .register(
+                &DispatcherDefinition::new("orders.mirror", None),
+                Arc::new(TestHandler::default()),
+            )
+            .freeze();
+        let data: Vec<_> = block_on(target.consume_n(2))
+            .unwrap()
+            .iter()
+            .map(|msg| msg.data_string().unwrap())
+            .collect();
+        assert_eq!(data, ["CREATED", "PAID"]);
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn failed_target_publishes_fail_and_requeue_the_source_message() {
+        let target = InMemoryBroker::new();
+        let limited = SizeLimitedPublisher::new(Arc::new(target.clone()), 4);
+
+        let log = bridged(Arc::new(limited), &["created"]);
+
+        assert_eq!(log.len(), 2);
+        assert_eq!(log[0], "nack requeue=true");
+        assert!(log[1].starts_with("failed: failure to publish message"));
+        assert!(!log.contains(&"ack".to_owned()));
+        assert_eq!(target.pending("orders.mirror"), 0);
+    }
+}
@@ -0,0 +1,388 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Codec
+//!
+//! This module defines how typed values are converted to and from message data.
+//!
+//! Types published or consumed through the typed helpers implement [`Encode`] and
+//! [`Decode`], typically by delegating to a serialization library such as `serde_json`.
+//! Implementations are provided for `String` and `Vec<u8>`.
+//!
+//! When the data format is chosen at runtime, e.g. from the `content-type` header, a
+//! [`CodecRegistry`] maps content types to [`Codec`] implementations instead.
+
+use crate::errors::MessagingError;
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    marker::PhantomData,
+    sync::Arc,
+};
+
+/// Defines how a value is encoded into message data.
+pub trait Encode {
+    /// The media type of the encoded data, stored in the `content-type` header.
+    const CONTENT_TYPE: &'static str;
+
+    /// Encodes the value.
+    ///
+    /// # Returns
+    ///
+    /// The encoded data, or `MessagingError::SerializingError` on failure.
+    fn encode(&self) -> Result<Vec<u8>, MessagingError>;
+}
+
+/// Defines how a value is decoded from message data.
+pub trait Decode: Sized {
+    /// Decodes a value.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The message data to decode.
+    ///
+    /// # Returns
+    ///
+    /// The decoded value, or `MessagingError::DeserializingError` on failure.
+    fn decode(data: &[u8]) -> Result<Self, MessagingError>;
+}
+
+impl Encode for String {
+    const CONTENT_TYPE: &'static str = "text/plain; charset=utf-8";
+
+    fn encode(&self) -> Result<Vec<u8>, MessagingError> {
+        Ok(self.as_bytes().to_vec())
+    }
+}
+
+impl Decode for String {
+    fn decode(data: &[u8]) -> Result<Self, MessagingError> {
+        Ok(String::from_utf8(data.to_vec())?)
+    }
+}
+
+impl Encode for Vec<u8> {
+    const CONTENT_TYPE: &'static str = "application/octet-stream";
+
+    fn encode(&self) -> Result<Vec<u8>, MessagingError> {
+        Ok(self.clone())
+    }
+}
+
+impl Decode for Vec<u8> {
+    fn decode(data: &[u8]) -> Result<Self, MessagingError> {
+        Ok(data.to_vec())
+    }
+}
+
+/// Defines how values of a type are converted to and from data of one content type.
+pub trait Codec<T>: Send + Sync {
+    /// Encodes a value.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to encode.
+    ///
+    /// # Returns
+    ///
+    /// The encoded data, or `MessagingError::SerializingError` on failure.
+    fn encode(&self, value: &T) -> Result<Vec<u8>, MessagingError>;
+
+    /// Decodes a value.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The data to decode.
+    ///
+    /// # Returns
+    ///
+    /// The decoded value, or `MessagingError::DeserializingError` on failure.
+    fn decode(&self, data: &[u8]) -> Result<T, MessagingError>;
+}
+
+/// A codec delegating to the `Encode` and `Decode` implementations of a type.
+struct TraitCodec<T>(PhantomData<fn() -> T>);
+
+impl<T> Codec<T> for TraitCodec<T>
+where
+    T: Encode + Decode,
+{
+    fn encode(&self, value: &T) -> Result<Vec<u8>, MessagingError> {
+        value.encode()
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<T, MessagingError> {
+        T::decode(data)
+    }
+}
+
+/// A table of codecs by content type and value type.
+///
+/// Content types are matched on their media type, ignoring case and parameters, so a
+/// codec registered for `application/json` also handles `application/json;
+/// charset=utf-8`. A new registry holds the codecs of `String` and `Vec<u8>` for their
+/// [`Encode::CONTENT_TYPE`]; other formats, such as JSON, protobuf or MessagePack, are
+/// registered by the application.
+#[derive(Clone)]
+pub struct CodecRegistry {
+    codecs: HashMap<(String, TypeId), Arc<dyn Any + Send + Sync>>,
+}
+
+impl Default for CodecRegistry {
+    fn default() -> Self {
+        CodecRegistry {
+            codecs: HashMap::new(),
+        }
+        .register_type::<String>()
+        .register_type::<Vec<u8>>()
+    }
+}
+
+impl CodecRegistry {
+    /// Creates a new registry with the built-in codecs.
+    ///
+    /// # Returns
+    ///
+    /// A new `CodecRegistry` instance.
+    pub fn new() -> Self {
+        CodecRegistry::default()
+    }
+
+    /// Registers a codec for a content type and value type, replacing any previous one.
+    ///
+    /// # Arguments
+    ///
+    /// * `content_type` - The content type handled by the codec, e.g. `application/json`.
+    /// * `codec` - The codec converting values of type `T`.
+    ///
+    /// # Returns
+    ///
+    /// The updated `CodecRegistry`.
+    pub fn register<T, C>(mut self, content_type: &str, codec: C) -> Self
+    where
+        T: 'static,
+        C: Codec<T> + 'static,
+    {
+        let codec: Arc<dyn Codec<T>> = Arc::new(codec);
+        self.codecs.insert(
+            (media_type(content_type), TypeId::of::<T>()),
+            Arc::new(codec),
+        );
+        self
+    }
+
+    /// Registers the `Encode` and `Decode` implementations of a type for its
+    /// `Encode::CONTENT_TYPE`.
+    ///
+    /// # Returns
+    ///
+    /// The updated `CodecRegistry`.
+    pub fn register_type<T>(self) -> Self
+    where
+        T: Encode + Decode + 'static,
+    {
+        self.register(T::CONTENT_TYPE, TraitCodec::<T>(PhantomData))
+    }
+
+    /// Encodes a value with the codec registered for a content type.
+    ///
+    /// # Arguments
+    ///
+    /// * `content_type` - The content type of the data to produce.
+    /// * `value` - The value to encode.
+    ///
+    /// # Returns
+    ///
+    /// The encoded data, or `MessagingError::SerializingError` if no codec is registered
+    /// for the content type and value type or encoding fails.
+    pub fn encode<T>(&self, content_type: &str, value: &T) -> Result<Vec<u8>, MessagingError>
+    where
+        T: 'static,
+    {
+        self.codec::<T>(content_type)
+            .ok_or_else(|| {
+                MessagingError::serializing(format!(
+                    "no codec registered for content type `{content_type}`"
+                ))
+            })?
+            .encode(value)
+    }
+
+    /// Decodes a value with the codec registered for a content type.
+    ///
+    /// # Arguments
+    ///
+    /// * `content_type` - The content type of the data.
+    /// * `data` - The data to decode.
+    ///
+    /// # Returns
+    ///
+    /// The decoded value, or `MessagingError::DeserializingError` if no codec is registered
+    /// for the content type and value type or decoding fails.
+    pub fn decode<T>(&self, content_type: &str, data: &[u8]) -> Result<T, MessagingError>
+    where
+        T: 'static,
+    {
+        self.codec::<T>(content_type)
+            .ok_or(MessagingError::DeserializingError)?
+            .decode(data)
+    }
+
+    fn codec<T>(&self, content_type: &str) -> Option<&Arc<dyn Codec<T>>>
+    where
+        T: 'static,
+    {
+        self.codecs
+            .get(&(media_type(content_type), TypeId::of::<T>()))?
+            .downcast_ref()
+    }
+}
+
+/// Returns the lowercase media type of a content type, without its parameters.
+fn media_type(content_type: &str) -> String {
+    content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{publisher::PublishMessage, test_support::delivered};
+
+    #[derive(Debug, PartialEq)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    /// Encodes points as `x,y` text.
+    struct CsvCodec;
+
+    impl Codec<Point> for CsvCodec {
+        fn encode(&self, value: &Point) -> Result<Vec<u8>, MessagingError> {
+            Ok(format!("{},{}", value.x, value.y).into_bytes())
+        }
+
+        fn decode(&self, data: &[u8]) -> Result<Point, MessagingError> {
+            let text = str::from_utf8(data)?;
+            let (x, y) = text
+                .split_once(',')
+                .ok_or(MessagingError::DeserializingError)?;
+            let parse = |v: &str| v.parse().map_err(|_| MessagingError::DeserializingError);
+            Ok(Point {
+                x: parse(x)?,
+                y: parse(y)?,
+            })
+        }
+    }
+
+    fn registry() -> CodecRegistry {
+        CodecRegistry::new().register("text/csv", CsvCodec)
+    }
+
+    #[test]
+    fn strings_round_trip() {
+        let data = "données".to_owned().encode().unwrap();
+
+        assert_eq!(String::decode(&data), Ok("données".to_owned()));
+        assert_eq!(
+            String::decode(&[0xff]),
+            Err(MessagingError::DeserializingError)
+        );
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let data = vec![0, 1, 255].encode().unwrap();
+
+        assert_eq!(Vec::<u8>::decode(&data), Ok(vec![0, 1, 255]));
+    }
+
+    #[test]
+    fn custom_codecs_round_trip() {
+        let registry = registry();
+        let point = Point { x: 3, y: -4 };
+
+        let data = registry.encode("text/csv", &point).unwrap();
+
+        assert_eq!(data, b"3,-4");
+        assert_eq!(registry.decode::<Point>("text/csv", &data), Ok(point));
+        assert_eq!(
+            registry.decode::<Point>("text/csv", b"3"),
+            Err(MessagingError::DeserializingError)
+        );
+    }
+
+    #[test]
+    fn matches_media_types_ignoring_case_and_parameters() {
+        let registry = registry();
+
+        assert_eq!(
+            registry.decode::<Point>("Text/CSV; charset=utf-8", b"1,2"),
+            Ok(Point { x: 1, y: 2 })
+        );
+    }
+
+    #[test]
+    fn fails_without_a_codec_for_the_content_type_and_type() {
+        let registry = registry();
+
+        assert_eq!(
+            registry.encode("application/json", &Point { x: 1, y: 2 }),
+            Err(MessagingError::serializing(
+                "no codec registered for content type `application/json`"
+            ))
+        );
+        assert_eq!(
+            registry.decode::<String>("text/csv", b"1,2"),
+            Err(MessagingError::DeserializingError)
+        );
+    }
+
+    #[test]
+    fn builtin_codecs_are_registered() {
+        let registry = CodecRegistry::new();
+
+        let data = registry
+            .encode(String::CONTENT_TYPE, &"text".to_owned())
+            .unwrap();
+
+        assert_eq!(
+            registry.decode::<String>(String::CONTENT_TYPE, &data),
+            Ok("text".to_owned())
+        );
+        assert_eq!(
+            registry.decode::<Vec<u8>>(Vec::<u8>::CONTENT_TYPE, &[0xff]),
+            Ok(vec![0xff])
+        );
+    }
+
+    #[test]
+    fn messages_round_trip_through_the_content_type_header() {
+        let registry = registry();
+        let point = Point { x: 5, y: 6 };
+
+        let msg = PublishMessage::encode("points", &point, "text/csv", &registry).unwrap();
+        let received = delivered(&msg);
+
+        assert_eq!(received.content_type(), Some("text/csv"));
+        assert_eq!(received.decode::<Point>(&registry), Ok(point));
+    }
+
+    #[test]
+    fn messages_without_a_content_type_fail_to_decode() {
+        let received = delivered(&PublishMessage::new(
+            None, "points", None, None, b"1,2", None,
+        ));
+
+        assert_eq!(
+            received.decode::<Point>(&registry()),
+            Err(MessagingError::DeserializingError)
+        );
+    }
+}
@@ -0,0 +1,157 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Destination
+//!
+//! This module defines a typed alternative to passing destinations as bare strings.
+//!
+//! A [`Destination`] states whether a message goes to a topic, a queue or an exchange, and
+//! renders to the `to` string (and, for exchanges, the routing key) of a `PublishMessage`.
+//! Since it converts into `String`, it can be used wherever a destination name is expected.
+
+use std::fmt;
+
+/// The destination of a published message.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Destination {
+    /// A topic, e.g. a Kafka topic or an MQTT topic filter.
+    Topic(String),
+
+    /// A queue, addressed directly.
+    Queue(String),
+
+    /// An exchange, with an optional routing key matched against its bindings.
+    Exchange {
+        /// The name of the exchange.
+        name: String,
+
+        /// The optional routing key of the message.
+        routing_key: Option<String>,
+    },
+}
+
+impl Destination {
+    /// Creates a topic destination.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the topic.
+    ///
+    /// # Returns
+    ///
+    /// A `Destination::Topic` value.
+    pub fn topic<T>(name: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Destination::Topic(name.into())
+    }
+
+    /// Creates a queue destination.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the queue.
+    ///
+    /// # Returns
+    ///
+    /// A `Destination::Queue` value.
+    pub fn queue<T>(name: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Destination::Queue(name.into())
+    }
+
+    /// Creates an exchange destination.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the exchange.
+    /// * `routing_key` - The optional routing key of the message.
+    ///
+    /// # Returns
+    ///
+    /// A `Destination::Exchange` value.
+    pub fn exchange<T>(name: T, routing_key: Option<T>) -> Self
+    where
+        T: Into<String>,
+    {
+        Destination::Exchange {
+            name: name.into(),
+            routing_key: routing_key.map(Into::into),
+        }
+    }
+
+    /// Returns the name of the topic, queue or exchange, used as the `to` of a message.
+    pub fn name(&self) -> &str {
+        match self {
+            Destination::Topic(name) | Destination::Queue(name) => name,
+            Destination::Exchange { name, .. } => name,
+        }
+    }
+
+    /// Returns the routing key of an exchange destination.
+    pub fn routing_key(&self) -> Option<&str> {
+        match self {
+            Destination::Exchange { routing_key, .. } => routing_key.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Destination {
+    /// Formats the destination as its `to` string, i.e. its name.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl From<Destination> for String {
+    /// Converts a destination into its `to` string.
+    ///
+    /// The routing key of an exchange destination is not part of the string; use
+    /// `PublishMessage::with_destination` to keep it.
+    fn from(destination: Destination) -> Self {
+        match destination {
+            Destination::Topic(name) | Destination::Queue(name) => name,
+            Destination::Exchange { name, .. } => name,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_each_variant_to_its_name() {
+        let cases = [
+            (Destination::topic("orders.created"), "orders.created"),
+            (Destination::queue("orders"), "orders"),
+            (
+                Destination::exchange("orders", Some("eu.created")),
+                "orders",
+            ),
+            (Destination::exchange("orders", None), "orders"),
+        ];
+
+        for (destination, to) in cases {
+            assert_eq!(destination.name(), to);
+            assert_eq!(destination.to_string(), to);
+            assert_eq!(String::from(destination), to);
+        }
+    }
+
+    #[test]
+    fn only_exchanges_have_a_routing_key() {
+        assert_eq!(
+            Destination::exchange("orders", Some("eu.created")).routing_key(),
+            Some("eu.created")
+        );
+        assert_eq!(Destination::exchange("orders", None).routing_key(), None);
+        assert_eq!(Destination::topic("orders").routing_key(), None);
+        assert_eq!(Destination::queue("orders").routing_key(), None);
+    }
+}
@@ -10,13 +10,112 @@
 //! based on the message type and subscription information. It manages the registration of
 //! handlers and the consumption of messages from the broker.
 
-use crate::{errors::MessagingError, handler::ConsumerHandler};
+use crate::{
+    errors::MessagingError,
+    handler::{ConsumerHandler, ConsumerMessage},
+    publisher::HeaderValues,
+};
 use async_trait::async_trait;
-use std::sync::Arc;
+use opentelemetry::Context;
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    num::NonZeroUsize,
+    sync::{Arc, Mutex, PoisonError},
+    thread::available_parallelism,
+    time::{Duration, Instant},
+};
 
 #[cfg(feature = "mocks")]
 use mockall::*;
 
+/// Defines how many messages a dispatcher may handle concurrently.
+///
+/// The limit is resolved once, when the dispatcher starts consuming, by calling
+/// [`Concurrency::resolve`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Concurrency {
+    /// A fixed number of concurrent messages.
+    Fixed(usize),
+
+    /// A number of concurrent messages relative to the available CPU cores,
+    /// e.g. `PerCore(2.0)` allows two messages per core.
+    PerCore(f32),
+
+    /// No limit on concurrent messages.
+    #[default]
+    Unbounded,
+}
+
+impl Concurrency {
+    /// Resolves the concurrency limit for the current machine.
+    ///
+    /// `PerCore` multiplies the ratio by `std::thread::available_parallelism()` (falling
+    /// back to one core when it cannot be determined) and rounds to the nearest integer.
+    /// Bounded limits never resolve below 1.
+    ///
+    /// # Returns
+    ///
+    /// The maximum number of concurrent messages, or `None` when unbounded.
+    pub fn resolve(&self) -> Option<usize> {
+        match *self {
+            Concurrency::Fixed(n) => Some(n.max(1)),
+            Concurrency::PerCore(ratio) => {
+                let cores = available_parallelism().map_or(1, NonZeroUsize::get);
+                Some(((cores as f32 * ratio).round() as usize).max(1))
+            }
+            Concurrency::Unbounded => None,
+        }
+    }
+}
+
+impl From<usize> for Concurrency {
+    /// Converts a number into a fixed concurrency limit.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - The maximum number of concurrent messages.
+    ///
+    /// # Returns
+    ///
+    /// A `Concurrency::Fixed` value.
+    fn from(n: usize) -> Self {
+        Concurrency::Fixed(n)
+    }
+}
+
+/// Defines when a consumed message is acknowledged to the broker.
+///
+/// This is a hint honored by broker implementations that support both modes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DeliveryMode {
+    /// The message is acknowledged before the handler runs. A message whose handler fails
+    /// or crashes is lost, but it is never delivered twice.
+    AtMostOnce,
+
+    /// The message is acknowledged after the handler succeeded. A message whose handler
+    /// fails is requeued, so it may be delivered more than once.
+    #[default]
+    AtLeastOnce,
+}
+
+/// Defines where consumption of a subscription should resume, for offset-based brokers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeekPosition {
+    /// The oldest message still retained by the broker.
+    Beginning,
+
+    /// Only messages published from now on.
+    End,
+
+    /// The message at the given offset.
+    Offset(u64),
+
+    /// The first message published at or after the given time, in milliseconds since the
+    /// Unix epoch.
+    Timestamp(i64),
+}
+
 /// Defines a subscription for message consumption.
 ///
 /// A dispatcher definition includes a name (typically a queue or topic name)
@@ -28,6 +127,33 @@ pub struct DispatcherDefinition {
 
     /// Optional message type for filtering messages.
     pub msg_type: Option<String>,
+
+    /// The maximum number of messages handled concurrently for this subscription.
+    pub max_concurrency: Concurrency,
+
+    /// Optional cap on how many messages may be requeued per second for this subscription.
+    ///
+    /// When exceeded, the dispatcher backs off before issuing further requeues so that a
+    /// few failing messages cannot starve healthy ones. The limit is checked when the
+    /// requeue is issued, so any redelivery delay applied by the backend comes on top of it.
+    pub max_requeues_per_second: Option<u32>,
+
+    /// When messages are acknowledged relative to handler execution.
+    pub delivery_mode: DeliveryMode,
+
+    /// Optional consumer group to join, e.g. the Kafka `group.id`.
+    ///
+    /// Dispatchers subscribing with the same group share the messages of the subscription
+    /// between them instead of each receiving all of them. When `None`, the backend
+    /// default applies. Brokers without consumer groups ignore it.
+    pub group: Option<String>,
+
+    /// Optional header values a message must carry to match this subscription, e.g.
+    /// `tenant=acme`.
+    ///
+    /// Brokers generally cannot filter on headers, so dispatcher implementations apply it
+    /// after delivery with [`DispatcherDefinition::matches_headers`].
+    pub header_filter: Option<HashMap<String, HeaderValues>>,
 }
 
 impl DispatcherDefinition {
@@ -45,20 +171,471 @@ impl DispatcherDefinition {
     where
         T: Into<String>,
     {
-        let msg_type = if msg_type.is_some() {
-            let v = msg_type.unwrap();
-            Some(v.into())
-        } else {
-            None
-        };
-
         DispatcherDefinition {
             name: name.into(),
-            msg_type,
+            msg_type: msg_type.map(Into::into),
+            max_concurrency: Concurrency::default(),
+            max_requeues_per_second: None,
+            delivery_mode: DeliveryMode::default(),
+            group: None,
+            header_filter: None,
+        }
+    }
+
+    /// Sets the maximum number of messages handled concurrently.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_concurrency` - The concurrency limit, e.g. `Concurrency::PerCore(2.0)` or `8`.
+    ///
+    /// # Returns
+    ///
+    /// The updated `DispatcherDefinition`.
+    pub fn with_max_concurrency<C>(mut self, max_concurrency: C) -> Self
+    where
+        C: Into<Concurrency>,
+    {
+        self.max_concurrency = max_concurrency.into();
+        self
+    }
+
+    /// Sets the maximum number of requeues per second.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_requeues_per_second` - The requeue rate limit.
+    ///
+    /// # Returns
+    ///
+    /// The updated `DispatcherDefinition`.
+    pub fn with_max_requeues_per_second(mut self, max_requeues_per_second: u32) -> Self {
+        self.max_requeues_per_second = Some(max_requeues_per_second);
+        self
+    }
+
+    /// Sets the delivery mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `delivery_mode` - When messages are acknowledged relative to handler execution.
+    ///
+    /// # Returns
+    ///
+    /// The updated `DispatcherDefinition`.
+    pub fn with_delivery_mode(mut self, delivery_mode: DeliveryMode) -> Self {
+        self.delivery_mode = delivery_mode;
+        self
+    }
+
+    /// Sets the consumer group to join.
+    ///
+    /// # Arguments
+    ///
+    /// * `group` - The consumer group identifier.
+    ///
+    /// # Returns
+    ///
+    /// The updated `DispatcherDefinition`.
+    pub fn with_group<T>(mut self, group: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.group = Some(group.into());
+        self
+    }
+
+    /// Adds a header value to the header filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The header key.
+    /// * `value` - The value the header must have.
+    ///
+    /// # Returns
+    ///
+    /// The updated `DispatcherDefinition`.
+    pub fn with_header_filter<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: Into<String>,
+        V: Into<HeaderValues>,
+    {
+        self.header_filter
+            .get_or_insert_with(Default::default)
+            .insert(key.into(), value.into());
+        self
+    }
+
+    /// Checks a message against the header filter.
+    ///
+    /// Filter values are compared with the message headers by their string
+    /// representation, since consumer headers carry no type information.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The received message.
+    ///
+    /// # Returns
+    ///
+    /// `true` if there is no filter or the message carries every filtered header with the
+    /// filtered value.
+    pub fn matches_headers(&self, msg: &ConsumerMessage) -> bool {
+        self.header_filter.iter().flatten().all(|(key, value)| {
+            msg.header(key)
+                .is_some_and(|actual| actual == String::from(value.clone()))
+        })
+    }
+}
+
+impl fmt::Display for DispatcherDefinition {
+    /// Formats the definition as a concise summary for logs, e.g.
+    /// `sub(name=orders, type=orders.created)`, or `sub(name=orders, type=*)` when no
+    /// message type is set.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "sub(name={}, type={})",
+            self.name,
+            self.msg_type.as_deref().unwrap_or("*")
+        )
+    }
+}
+
+/// A reusable table mapping subscriptions to their handlers.
+///
+/// Broker implementations can keep their registrations in a `HandlerRegistry` and focus
+/// on transport. A message matches a definition when its source equals the definition
+/// name and, if the definition has a `msg_type`, when the message types are equal.
+/// Definitions with a matching `msg_type` take precedence over definitions without one;
+/// among equally specific definitions, the first registered wins. Messages matching no
+/// definition go to the fallback handler, if one is registered.
+#[derive(Clone, Default)]
+pub struct HandlerRegistry {
+    entries: Vec<(DispatcherDefinition, Arc<dyn ConsumerHandler>)>,
+    fallback: Option<Arc<dyn ConsumerHandler>>,
+}
+
+impl HandlerRegistry {
+    /// Creates a new, empty registry.
+    ///
+    /// # Returns
+    ///
+    /// A new `HandlerRegistry` instance.
+    pub fn new() -> Self {
+        HandlerRegistry::default()
+    }
+
+    /// Registers a handler for a definition.
+    ///
+    /// # Arguments
+    ///
+    /// * `definition` - The definition specifying what to subscribe to.
+    /// * `handler` - The handler to process messages matching the definition.
+    pub fn register(
+        &mut self,
+        definition: &DispatcherDefinition,
+        handler: Arc<dyn ConsumerHandler>,
+    ) {
+        self.entries.push((definition.clone(), handler));
+    }
+
+    /// Registers the handler for messages matching no definition, replacing any previous
+    /// fallback.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - The handler to process unmatched messages.
+    pub fn register_fallback(&mut self, handler: Arc<dyn ConsumerHandler>) {
+        self.fallback = Some(handler);
+    }
+
+    /// Finds the handler for a message.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The source of the message.
+    /// * `msg_type` - The type of the message.
+    ///
+    /// # Returns
+    ///
+    /// The matching handler, the fallback handler if no definition matches, or `None` if
+    /// there is no fallback either.
+    pub fn resolve(&self, from: &str, msg_type: &str) -> Option<Arc<dyn ConsumerHandler>> {
+        self.find(from, msg_type, |_| true)
+    }
+
+    /// Finds the handler for a message, also skipping definitions whose header filter the
+    /// message does not match.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The received message.
+    ///
+    /// # Returns
+    ///
+    /// The matching handler, the fallback handler if no definition matches, or `None` if
+    /// there is no fallback either.
+    pub fn resolve_message(&self, msg: &ConsumerMessage) -> Option<Arc<dyn ConsumerHandler>> {
+        self.find(&msg.from, &msg.msg_type, |definition| {
+            definition.matches_headers(msg)
+        })
+    }
+
+    fn find<F>(&self, from: &str, msg_type: &str, accept: F) -> Option<Arc<dyn ConsumerHandler>>
+    where
+        F: Fn(&DispatcherDefinition) -> bool,
+    {
+        let mut candidates = self
+            .entries
+            .iter()
+            .filter(|(definition, _)| definition.name == from && accept(definition));
+
+        candidates
+            .clone()
+            .find(|(definition, _)| definition.msg_type.as_deref() == Some(msg_type))
+            .or_else(|| candidates.find(|(definition, _)| definition.msg_type.is_none()))
+            .map(|(_, handler)| handler)
+            .or(self.fallback.as_ref())
+            .cloned()
+    }
+
+    /// Resolves the handler for a message with [`HandlerRegistry::resolve_message`] and
+    /// executes it.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The OpenTelemetry context for tracing and monitoring.
+    /// * `msg` - The received message to process.
+    ///
+    /// # Returns
+    ///
+    /// The result of the handler, or `MessagingError::UnregisteredHandler` if no
+    /// definition matches and there is no fallback.
+    pub async fn dispatch(
+        &self,
+        ctx: &Context,
+        msg: &ConsumerMessage,
+    ) -> Result<(), MessagingError> {
+        self.resolve_message(msg)
+            .ok_or(MessagingError::UnregisteredHandler)?
+            .exec(ctx, msg)
+            .await
+    }
+
+    /// Returns the registered definitions in registration order.
+    pub fn definitions(&self) -> impl Iterator<Item = &DispatcherDefinition> {
+        self.entries.iter().map(|(definition, _)| definition)
+    }
+
+    /// Returns the number of registered handlers.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no handler is registered.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Collects handler registrations and options independently of a concrete dispatcher.
+///
+/// Code configuring subscriptions, e.g. from application configuration, can build a
+/// `DispatcherBuilder` without knowing which dispatcher implementation it will run on, and
+/// hand the registrations over with [`DispatcherBuilder::build_into`].
+#[derive(Clone, Default)]
+pub struct DispatcherBuilder {
+    entries: Vec<(DispatcherDefinition, Arc<dyn ConsumerHandler>)>,
+    fallback: Option<Arc<dyn ConsumerHandler>>,
+    prefetch: Option<u16>,
+    delivery_mode: Option<DeliveryMode>,
+}
+
+impl DispatcherBuilder {
+    /// Creates a new builder without registrations or options.
+    ///
+    /// # Returns
+    ///
+    /// A new `DispatcherBuilder` instance.
+    pub fn new() -> Self {
+        DispatcherBuilder::default()
+    }
+
+    /// Adds a handler for a definition.
+    ///
+    /// # Arguments
+    ///
+    /// * `definition` - The dispatcher definition specifying what to subscribe to.
+    /// * `handler` - The handler to process messages matching the definition.
+    ///
+    /// # Returns
+    ///
+    /// The updated `DispatcherBuilder`.
+    pub fn register(
+        mut self,
+        definition: DispatcherDefinition,
+        handler: Arc<dyn ConsumerHandler>,
+    ) -> Self {
+        self.entries.push((definition, handler));
+        self
+    }
+
+    /// Sets the handler for messages matching no definition, replacing any previous one.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - The handler to process unmatched messages.
+    ///
+    /// # Returns
+    ///
+    /// The updated `DispatcherBuilder`.
+    pub fn with_fallback(mut self, handler: Arc<dyn ConsumerHandler>) -> Self {
+        self.fallback = Some(handler);
+        self
+    }
+
+    /// Sets the prefetch passed to `Dispatcher::with_prefetch`.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefetch` - The maximum number of unacknowledged messages.
+    ///
+    /// # Returns
+    ///
+    /// The updated `DispatcherBuilder`.
+    pub fn with_prefetch(mut self, prefetch: u16) -> Self {
+        self.prefetch = Some(prefetch);
+        self
+    }
+
+    /// Sets the delivery mode of every definition, overriding their own.
+    ///
+    /// # Arguments
+    ///
+    /// * `delivery_mode` - The delivery mode applied to all definitions.
+    ///
+    /// # Returns
+    ///
+    /// The updated `DispatcherBuilder`.
+    pub fn with_delivery_mode(mut self, delivery_mode: DeliveryMode) -> Self {
+        self.delivery_mode = Some(delivery_mode);
+        self
+    }
+
+    /// Returns the collected definitions in registration order.
+    pub fn definitions(&self) -> impl Iterator<Item = &DispatcherDefinition> {
+        self.entries.iter().map(|(definition, _)| definition)
+    }
+
+    /// Applies the options and registrations to a dispatcher.
+    ///
+    /// The prefetch is set first, then the handlers are registered in the order they were
+    /// added, and the fallback last. The dispatcher is frozen afterwards, so it is ready to
+    /// consume.
+    ///
+    /// # Arguments
+    ///
+    /// * `dispatcher` - The dispatcher to configure.
+    ///
+    /// # Returns
+    ///
+    /// The configured dispatcher.
+    pub fn build_into<D>(self, dispatcher: D) -> D
+    where
+        D: Dispatcher,
+    {
+        let mut dispatcher = match self.prefetch {
+            Some(prefetch) => dispatcher.with_prefetch(prefetch),
+            None => dispatcher,
+        };
+
+        for (mut definition, handler) in self.entries {
+            if let Some(delivery_mode) = self.delivery_mode {
+                definition.delivery_mode = delivery_mode;
+            }
+            dispatcher = dispatcher.register(&definition, handler);
+        }
+
+        match self.fallback {
+            Some(handler) => dispatcher.register_fallback(handler),
+            None => dispatcher,
+        }
+        .freeze()
+    }
+}
+
+/// Tracks requeues over a sliding one-second window and enforces a maximum rate.
+///
+/// Dispatcher implementations can keep one limiter per definition with a
+/// `max_requeues_per_second` and consult it before requeueing a message.
+#[derive(Debug)]
+pub struct RequeueLimiter {
+    max_per_second: u32,
+    requeues: Mutex<VecDeque<Instant>>,
+}
+
+impl RequeueLimiter {
+    const WINDOW: Duration = Duration::from_secs(1);
+
+    /// Creates a new requeue limiter.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_per_second` - The maximum number of requeues allowed per second.
+    ///
+    /// # Returns
+    ///
+    /// A new `RequeueLimiter` instance.
+    pub fn new(max_per_second: u32) -> Self {
+        RequeueLimiter {
+            max_per_second,
+            requeues: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Records a requeue if the rate allows it.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the requeue may proceed, or `Err` with how long to back off before
+    /// trying again.
+    pub fn try_requeue(&self) -> Result<(), Duration> {
+        let now = Instant::now();
+        let mut requeues = self.requeues.lock().unwrap_or_else(PoisonError::into_inner);
+        Self::expire(&mut requeues, now);
+
+        if requeues.len() >= self.max_per_second as usize {
+            let oldest = requeues.front().copied().unwrap_or(now);
+            return Err(Self::WINDOW.saturating_sub(now.duration_since(oldest)));
+        }
+
+        requeues.push_back(now);
+        Ok(())
+    }
+
+    /// Returns the number of requeues recorded during the last second.
+    pub fn rate(&self) -> u32 {
+        let mut requeues = self.requeues.lock().unwrap_or_else(PoisonError::into_inner);
+        Self::expire(&mut requeues, Instant::now());
+        requeues.len() as u32
+    }
+
+    fn expire(requeues: &mut VecDeque<Instant>, now: Instant) {
+        while requeues
+            .front()
+            .is_some_and(|at| now.duration_since(*at) >= Self::WINDOW)
+        {
+            requeues.pop_front();
         }
     }
 }
 
+/// Runtime statistics reported by a dispatcher.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DispatcherStats {
+    /// The number of messages requeued during the last second.
+    pub requeue_rate: u32,
+}
+
 /// Defines the interface for message dispatching.
 ///
 /// Implementations of this trait are responsible for consuming messages
@@ -76,16 +653,866 @@ pub trait Dispatcher: Send + Sync {
     /// # Returns
     ///
     /// Self reference for method chaining.
-    fn register(self, definition: &DispatcherDefinition, handler: Arc<dyn ConsumerHandler>)
-    -> Self;
+    fn register(self, definition: &DispatcherDefinition, handler: Arc<dyn ConsumerHandler>) -> Self
+    where
+        Self: Sized;
 
-    /// Starts consuming messages in a blocking manner.
+    /// Registers a handler for a specific dispatcher definition in place.
     ///
-    /// This method will block the current thread/task and continuously process
-    /// incoming messages until an error occurs or the dispatcher is stopped.
+    /// This is the borrowing counterpart of `register`, convenient when registering
+    /// handlers in a loop over configuration. Wrappers delegate it to the wrapped
+    /// dispatcher; dispatchers implementing `Default` can implement it by temporarily
+    /// taking the dispatcher with `mem::take` and calling `register`.
+    ///
+    /// # Arguments
+    ///
+    /// * `definition` - The dispatcher definition specifying what to subscribe to.
+    /// * `handler` - The handler to process messages matching the definition.
     ///
     /// # Returns
     ///
-    /// A `Result` indicating success or containing an error if consumption fails.
-    async fn consume_blocking(&self) -> Result<(), MessagingError>;
+    /// A mutable reference to the dispatcher for method chaining.
+    fn register_ref(
+        &mut self,
+        definition: &DispatcherDefinition,
+        handler: Arc<dyn ConsumerHandler>,
+    ) -> &mut Self
+    where
+        Self: Sized;
+
+    /// Sets how many unacknowledged messages may be in flight at once.
+    ///
+    /// Implementations should cap their outstanding deliveries to `prefetch`, e.g. through
+    /// the AMQP `basic.qos` prefetch count, so that a slow consumer cannot buffer an
+    /// unbounded number of messages in memory. The default implementation ignores the
+    /// value and keeps the implementation's own default, which may be unbounded.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefetch` - The maximum number of unacknowledged messages.
+    ///
+    /// # Returns
+    ///
+    /// Self reference for method chaining.
+    fn with_prefetch(self, _prefetch: u16) -> Self
+    where
+        Self: Sized,
+    {
+        self
+    }
+
+    /// Registers a catch-all handler for messages matching no registered definition.
+    ///
+    /// Instead of failing unknown message types with `MessagingError::UnregisteredHandler`,
+    /// implementations should deliver them to the fallback, e.g. to log or dead-letter
+    /// them. The default implementation ignores the handler.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - The handler to process unmatched messages.
+    ///
+    /// # Returns
+    ///
+    /// Self reference for method chaining.
+    fn register_fallback(self, _handler: Arc<dyn ConsumerHandler>) -> Self
+    where
+        Self: Sized,
+    {
+        self
+    }
+
+    /// Marks the registration of handlers as complete.
+    ///
+    /// Dispatchers supporting the barrier refuse to consume until they were frozen, so that
+    /// consumption cannot begin with only part of the handlers registered, e.g. when
+    /// registrations are assembled from configuration. Registering another handler after
+    /// `freeze` reopens the registration phase. The default implementation does nothing.
+    ///
+    /// # Returns
+    ///
+    /// Self reference for method chaining.
+    fn freeze(self) -> Self
+    where
+        Self: Sized,
+    {
+        self
+    }
+
+    /// Returns whether the registration of handlers was completed with `freeze`.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the dispatcher may consume. The default implementation always returns
+    /// `true`, for dispatchers without a registration barrier.
+    fn is_frozen(&self) -> bool {
+        true
+    }
+
+    /// Starts consuming messages in a blocking manner.
+    ///
+    /// This method will block the current thread/task and continuously process
+    /// incoming messages until an error occurs or the dispatcher is stopped.
+    ///
+    /// Dispatchers supporting the registration barrier fail with
+    /// `MessagingError::InternalError` if they were not frozen with `freeze` since the last
+    /// registration.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or containing an error if consumption fails.
+    async fn consume_blocking(&self) -> Result<(), MessagingError>;
+
+    /// Eagerly establishes the connection to the messaging broker.
+    ///
+    /// Calling it at startup lets an application fail fast on a bad configuration instead
+    /// of when consumption starts. The default implementation does nothing.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or containing an error if connecting fails.
+    async fn connect(&self) -> Result<(), MessagingError> {
+        Ok(())
+    }
+
+    /// Cleanly closes the connection to the messaging broker.
+    ///
+    /// Implementations should stop taking new deliveries and let in-flight handlers finish
+    /// before closing, so that shutting down drains gracefully. The default implementation
+    /// does nothing.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or containing an error if disconnecting fails.
+    async fn disconnect(&self) -> Result<(), MessagingError> {
+        Ok(())
+    }
+
+    /// Checks whether the dispatcher is connected and able to consume.
+    ///
+    /// Intended for readiness and liveness probes. Implementations can ping the broker and
+    /// should report a lost connection with `MessagingError::ConnectionError`. The default
+    /// implementation always reports the dispatcher as healthy.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the dispatcher is healthy, or the error describing why it is not.
+    async fn health_check(&self) -> Result<(), MessagingError> {
+        Ok(())
+    }
+
+    /// Stops taking new deliveries and waits for in-flight messages to finish.
+    ///
+    /// Unlike a hard shutdown, a drain lets every handler already running, and every
+    /// message already buffered locally, complete before consumption stops, e.g. before a
+    /// deploy. Messages still on the broker stay there. Implementations should make
+    /// `consume_blocking` return once drained. The default implementation delegates to
+    /// `disconnect` and ignores the timeout.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - The maximum time to wait for in-flight messages.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success, or `MessagingError::TimeoutError` if in-flight messages
+    /// did not finish in time.
+    async fn drain(&self, _timeout: Duration) -> Result<(), MessagingError> {
+        self.disconnect().await
+    }
+
+    /// Consumes a fixed number of messages without invoking the registered handlers.
+    ///
+    /// Intended for scripts, command-line tools and tests. Messages are taken from the
+    /// registered subscriptions and returned instead of being dispatched; implementations
+    /// acknowledge them as they are taken. The default implementation fails with
+    /// `MessagingError::InternalError`, since the backend does not support it.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - The number of messages to consume.
+    ///
+    /// # Returns
+    ///
+    /// The consumed messages in arrival order, fewer than `n` if consumption stopped first,
+    /// or an error if consuming fails.
+    async fn consume_n(&self, _n: usize) -> Result<Vec<ConsumerMessage>, MessagingError> {
+        Err(MessagingError::InternalError)
+    }
+
+    /// Lists the destinations known to the backend that can be subscribed to.
+    ///
+    /// Intended for admin tooling and discovery UIs. Backends with a management API can
+    /// list their queues or topics. The default implementation fails with
+    /// `MessagingError::InternalError`, since the backend does not support it.
+    ///
+    /// # Returns
+    ///
+    /// The names of the destinations, or an error if listing fails.
+    async fn list_destinations(&self) -> Result<Vec<String>, MessagingError> {
+        Err(MessagingError::InternalError)
+    }
+
+    /// Moves the consumption position of a subscription, e.g. to reprocess messages.
+    ///
+    /// Only meaningful for brokers that retain messages by offset, such as Kafka. The
+    /// default implementation fails with a `MessagingError::ConsumerError` with the code
+    /// `unsupported`, since the backend is not seekable.
+    ///
+    /// # Arguments
+    ///
+    /// * `definition` - The subscription to move.
+    /// * `position` - Where consumption should resume.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or containing an error if seeking fails.
+    async fn seek(
+        &self,
+        _definition: &DispatcherDefinition,
+        _position: SeekPosition,
+    ) -> Result<(), MessagingError> {
+        Err(MessagingError::consumer_with_code(
+            "seeking is not supported",
+            "unsupported",
+        ))
+    }
+
+    /// Returns the current runtime statistics of the dispatcher.
+    ///
+    /// # Returns
+    ///
+    /// The dispatcher statistics. The default implementation reports all zeroes.
+    fn stats(&self) -> DispatcherStats {
+        DispatcherStats::default()
+    }
+
+    /// Returns the definitions the dispatcher is subscribed to.
+    ///
+    /// # Returns
+    ///
+    /// The registered definitions. The default implementation returns an empty list.
+    fn definitions(&self) -> Vec<DispatcherDefinition> {
+        Vec::new()
+    }
+
+    /// Returns the number of registered handlers.
+    ///
+    /// # Returns
+    ///
+    /// The handler count. The default implementation returns zero.
+    fn handler_count(&self) -> usize {
+        0
+    }
+
+    /// Temporarily stops delivering messages to handlers without disconnecting.
+    ///
+    /// While paused, `consume_blocking` keeps running but does not invoke any handler.
+    /// Whether incoming messages are buffered locally or left on the broker is
+    /// implementation-defined. The default implementation does nothing.
+    async fn pause(&self) {}
+
+    /// Resumes delivering messages to handlers after a `pause`.
+    ///
+    /// The default implementation does nothing.
+    async fn resume(&self) {}
+
+    /// Returns whether the dispatcher is currently paused.
+    ///
+    /// # Returns
+    ///
+    /// `true` if paused. The default implementation always returns `false`.
+    fn is_paused(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        handler::fn_handler,
+        middleware::{channel::ChannelDispatcher, reconnect::ReconnectingDispatcher},
+        test_support::{CapturingDispatcher, RecordingTimer, block_on},
+    };
+
+    fn handler() -> Arc<dyn ConsumerHandler> {
+        fn_handler(|_, _| async { Ok(()) })
+    }
+
+    fn resolves_to(
+        resolved: Option<Arc<dyn ConsumerHandler>>,
+        expected: &Arc<dyn ConsumerHandler>,
+    ) -> bool {
+        resolved.is_some_and(|resolved| Arc::ptr_eq(&resolved, expected))
+    }
+
+    #[test]
+    fn resolves_fixed_concurrency_to_at_least_one() {
+        assert_eq!(Concurrency::Fixed(4).resolve(), Some(4));
+        assert_eq!(Concurrency::Fixed(0).resolve(), Some(1));
+        assert_eq!(Concurrency::from(8), Concurrency::Fixed(8));
+    }
+
+    #[test]
+    fn resolves_per_core_concurrency_from_the_available_cores() {
+        let cores = available_parallelism().map_or(1, NonZeroUsize::get);
+
+        assert_eq!(Concurrency::PerCore(2.0).resolve(), Some(cores * 2));
+        assert_eq!(Concurrency::PerCore(0.0).resolve(), Some(1));
+    }
+
+    #[test]
+    fn resolves_unbounded_concurrency_to_no_limit() {
+        assert_eq!(Concurrency::Unbounded.resolve(), None);
+        assert_eq!(Concurrency::default(), Concurrency::Unbounded);
+    }
+
+    #[test]
+    fn requeue_limiter_allows_requeues_up_to_the_rate() {
+        let limiter = RequeueLimiter::new(2);
+
+        assert_eq!(limiter.try_requeue(), Ok(()));
+        assert_eq!(limiter.try_requeue(), Ok(()));
+        assert_eq!(limiter.rate(), 2);
+
+        let backoff = limiter.try_requeue().unwrap_err();
+        assert!(backoff > Duration::ZERO && backoff <= RequeueLimiter::WINDOW);
+        assert_eq!(limiter.rate(), 2);
+    }
+
+    #[test]
+    fn requeue_limiter_with_zero_rate_rejects_every_requeue() {
+        assert!(RequeueLimiter::new(0).try_requeue().is_err());
+    }
+
+    #[test]
+    fn definitions_have_no_requeue_limit_by_default() {
+        let definition = DispatcherDefinition::new("orders", None);
+        assert_eq!(definition.max_requeues_per_second, None);
+
+        let definition = definition.with_max_requeues_per_second(5);
+        assert_eq!(definition.max_requeues_per_second, Some(5));
+    }
+
+    #[test]
+    fn registry_resolves_exact_matches() {
+        let created = handler();
+        let cancelled = handler();
+        let mut registry = HandlerRegistry::new();
+        registry.register(
+            &DispatcherDefinition::new("orders", Some("created")),
+            created.clone(),
+        );
+        registry.register(
+            &DispatcherDefinition::new("orders", Some("cancelled")),
+            cancelled.clone(),
+        );
+
+        assert!(resolves_to(registry.resolve("orders", "created"), &created));
+        assert!(resolves_to(
+            registry.resolve("orders", "cancelled"),
+            &cancelled
+        ));
+    }
+
+    #[test]
+    fn registry_prefers_msg_type_matches_over_catch_alls() {
+        let any = handler();
+        let created = handler();
+        let mut registry = HandlerRegistry::new();
+        registry.register(&DispatcherDefinition::new("orders", None), any.clone());
+        registry.register(
+            &DispatcherDefinition::new("orders", Some("created")),
+            created.clone(),
+        );
+
+        assert!(resolves_to(registry.resolve("orders", "created"), &created));
+        assert!(resolves_to(registry.resolve("orders", "cancelled"), &any));
+    }
+
+    #[test]
+    fn registry_resolves_to_the_first_of_equally_specific_registrations() {
+        let first = handler();
+        let mut registry = HandlerRegistry::new();
+        registry.register(&DispatcherDefinition::new("orders", None), first.clone());
+        registry.register(&DispatcherDefinition::new("orders", None), handler());
+
+        assert!(resolves_to(registry.resolve("orders", "created"), &first));
+    }
+
+    #[test]
+    fn registry_misses_unregistered_sources_and_types() {
+        let mut registry = HandlerRegistry::new();
+        assert!(registry.is_empty());
+        registry.register(
+            &DispatcherDefinition::new("orders", Some("created")),
+            handler(),
+        );
+
+        assert!(registry.resolve("orders", "cancelled").is_none());
+        assert!(registry.resolve("invoices", "created").is_none());
+
+        let msg = ConsumerMessage::new("invoices", "created", b"data", None);
+        assert_eq!(
+            block_on(registry.dispatch(&Context::new(), &msg)),
+            Err(MessagingError::UnregisteredHandler)
+        );
+    }
+
+    #[test]
+    fn registry_sends_misses_to_the_fallback() {
+        let fallback = handler();
+        let mut registry = HandlerRegistry::new();
+        registry.register(
+            &DispatcherDefinition::new("orders", Some("created")),
+            handler(),
+        );
+        registry.register_fallback(fallback.clone());
+
+        assert!(resolves_to(
+            registry.resolve("invoices", "created"),
+            &fallback
+        ));
+        assert_eq!(registry.len(), 1);
+    }
+
+    #[test]
+    fn default_introspection_reports_no_handlers() {
+        let dispatcher = CapturingDispatcher::default()
+            .register(&DispatcherDefinition::new("orders", None), handler());
+
+        assert_eq!(dispatcher.handler_count(), 0);
+        assert!(dispatcher.definitions().is_empty());
+        assert_eq!(dispatcher.stats(), DispatcherStats::default());
+    }
+
+    #[test]
+    fn default_pause_is_a_no_op() {
+        let dispatcher = CapturingDispatcher::default();
+
+        block_on(dispatcher.pause());
+
+        assert!(!dispatcher.is_paused());
+    }
+
+    #[test]
+    fn displays_definitions_with_and_without_msg_type() {
+        let typed = DispatcherDefinition::new("orders", Some("orders.created"));
+        let untyped = DispatcherDefinition::new("orders", None);
+
+        assert_eq!(typed.to_string(), "sub(name=orders, type=orders.created)");
+        assert_eq!(untyped.to_string(), "sub(name=orders, type=*)");
+    }
+
+    fn tagged(headers: &[(&str, &str)]) -> ConsumerMessage {
+        let headers = headers
+            .iter()
+            .map(|(k, v)| ((*k).to_owned(), (*v).to_owned()))
+            .collect();
+        ConsumerMessage::new("orders", "created", b"data", Some(headers))
+    }
+
+    #[test]
+    fn definitions_without_header_filter_match_every_message() {
+        let definition = DispatcherDefinition::new("orders", None);
+
+        assert!(definition.matches_headers(&tagged(&[])));
+        assert!(definition.matches_headers(&ConsumerMessage::new("orders", "created", b"", None)));
+    }
+
+    #[test]
+    fn header_filter_requires_every_header_with_its_value() {
+        let definition = DispatcherDefinition::new("orders", None)
+            .with_header_filter("tenant", "acme")
+            .with_header_filter("version", HeaderValues::LongUint(2));
+
+        assert!(definition.matches_headers(&tagged(&[("tenant", "acme"), ("version", "2")])));
+        assert!(definition.matches_headers(&tagged(&[
+            ("tenant", "acme"),
+            ("version", "2"),
+            ("region", "eu"),
+        ])));
+        assert!(!definition.matches_headers(&tagged(&[("tenant", "acme")])));
+        assert!(!definition.matches_headers(&tagged(&[("tenant", "acme"), ("version", "3")])));
+        assert!(!definition.matches_headers(&ConsumerMessage::new("orders", "created", b"", None)));
+    }
+
+    #[test]
+    fn registry_skips_definitions_whose_header_filter_does_not_match() {
+        let (acme, others) = (handler(), handler());
+        let mut registry = HandlerRegistry::new();
+        registry.register(
+            &DispatcherDefinition::new("orders", None).with_header_filter("tenant", "acme"),
+            acme.clone(),
+        );
+        registry.register(&DispatcherDefinition::new("orders", None), others.clone());
+
+        assert!(resolves_to(
+            registry.resolve_message(&tagged(&[("tenant", "acme")])),
+            &acme
+        ));
+        assert!(resolves_to(
+            registry.resolve_message(&tagged(&[("tenant", "globex")])),
+            &others
+        ));
+    }
+
+    #[test]
+    fn definitions_have_no_group_by_default() {
+        assert_eq!(DispatcherDefinition::new("orders", None).group, None);
+    }
+
+    #[test]
+    fn group_round_trips_through_the_builder() {
+        let definition = DispatcherDefinition::new("orders", Some("created"))
+            .with_group("billing")
+            .with_delivery_mode(DeliveryMode::AtMostOnce);
+
+        let cloned = definition.clone();
+
+        assert_eq!(cloned.group.as_deref(), Some("billing"));
+        assert_eq!(cloned.name, "orders");
+        assert_eq!(cloned.delivery_mode, DeliveryMode::AtMostOnce);
+    }
+
+    #[test]
+    fn delivery_mode_defaults_to_at_least_once() {
+        let definition = DispatcherDefinition::new("orders", None);
+
+        assert_eq!(definition.delivery_mode, DeliveryMode::AtLeastOnce);
+        assert_eq!(DeliveryMode::default(), DeliveryMode::AtLeastOnce);
+    }
+
+    #[test]
+    fn delivery_mode_round_trips_through_the_builder() {
+        let definition =
+            DispatcherDefinition::new("orders", None).with_delivery_mode(DeliveryMode::AtMostOnce);
+
+        assert_eq!(definition.delivery_mode, DeliveryMode::AtMostOnce);
+    }
+
+    #[test]
+    fn dispatcher_builder_registers_every_pair_in_order() {
+        let handlers = [handler(), handler(), handler()];
+        let definitions = [
+            DispatcherDefinition::new("orders", None),
+            DispatcherDefinition::new("orders", Some("cancelled")),
+            DispatcherDefinition::new("invoices", None),
+        ];
+        let builder = definitions.iter().zip(&handlers).fold(
+            DispatcherBuilder::new(),
+            |builder, (definition, handler)| builder.register(definition.clone(), handler.clone()),
+        );
+        let collected: Vec<_> = builder.definitions().map(ToString::to_string).collect();
+
+        let dispatcher = CapturingDispatcher::default();
+        builder.build_into(dispatcher.clone());
+
+        let expected: Vec<_> = definitions.iter().map(ToString::to_string).collect();
+        let registered: Vec<_> = dispatcher
+            .registrations()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(collected, expected);
+        assert_eq!(registered, expected);
+        assert!(resolves_to(
+            Some(dispatcher.handler("invoices")),
+            &handlers[2]
+        ));
+    }
+
+    #[test]
+    fn dispatcher_builder_overrides_the_delivery_mode_of_every_definition() {
+        let dispatcher = CapturingDispatcher::default();
+        DispatcherBuilder::new()
+            .register(DispatcherDefinition::new("orders", None), handler())
+            .register(
+                DispatcherDefinition::new("invoices", None)
+                    .with_delivery_mode(DeliveryMode::AtLeastOnce),
+                handler(),
+            )
+            .with_delivery_mode(DeliveryMode::AtMostOnce)
+            .build_into(dispatcher.clone());
+
+        let modes: Vec<_> = dispatcher
+            .registrations()
+            .iter()
+            .map(|definition| definition.delivery_mode)
+            .collect();
+        assert_eq!(modes, [DeliveryMode::AtMostOnce; 2]);
+    }
+
+    #[cfg(feature = "mocks")]
+    #[test]
+    fn build_into_freezes_after_every_registration() {
+        let calls = Arc::new(Mutex::new(Vec::<String>::new()));
+
+        let register_calls = calls.clone();
+        let mut dispatcher = MockDispatcher::new();
+        dispatcher
+            .expect_register()
+            .times(1)
+            .returning(move |definition, _| {
+                register_calls
+                    .lock()
+                    .unwrap()
+                    .push(format!("register {}", definition.name));
+
+                let fallback_calls = register_calls.clone();
+                let mut registered = MockDispatcher::new();
+                registered
+                    .expect_register_fallback()
+                    .times(1)
+                    .returning(move |_| {
+                        fallback_calls.lock().unwrap().push("fallback".to_owned());
+
+                        let freeze_calls = fallback_calls.clone();
+                        let mut with_fallback = MockDispatcher::new();
+                        with_fallback.expect_freeze().times(1).returning(move || {
+                            freeze_calls.lock().unwrap().push("freeze".to_owned());
+                            MockDispatcher::new()
+                        });
+                        with_fallback
+                    });
+                registered
+            });
+
+        let handler = handler();
+        DispatcherBuilder::new()
+            .register(DispatcherDefinition::new("orders", None), handler.clone())
+            .with_fallback(handler)
+            .build_into(dispatcher);
+
+        assert_eq!(
+            *calls.lock().unwrap(),
+            ["register orders", "fallback", "freeze"]
+        );
+    }
+
+    #[cfg(feature = "mocks")]
+    #[test]
+    fn dispatcher_builder_passes_the_prefetch_to_the_dispatcher() {
+        let configured = Arc::new(Mutex::new(None));
+
+        let recorded = configured.clone();
+        let mut dispatcher = MockDispatcher::new();
+        dispatcher
+            .expect_with_prefetch()
+            .times(1)
+            .returning(move |prefetch| {
+                *recorded.lock().unwrap() = Some(prefetch);
+                let mut configured = MockDispatcher::new();
+                configured.expect_freeze().returning(MockDispatcher::new);
+                configured
+            });
+
+        DispatcherBuilder::new()
+            .with_prefetch(32)
+            .build_into(dispatcher);
+
+        assert_eq!(*configured.lock().unwrap(), Some(32));
+    }
+
+    #[test]
+    fn default_prefetch_leaves_the_dispatcher_unchanged() {
+        let dispatcher = CapturingDispatcher::default()
+            .register(&DispatcherDefinition::new("orders", None), handler())
+            .with_prefetch(32);
+
+        assert_eq!(dispatcher.registrations().len(), 1);
+    }
+
+    #[test]
+    fn default_lifecycle_succeeds() {
+        let dispatcher = CapturingDispatcher::default();
+
+        assert_eq!(block_on(dispatcher.connect()), Ok(()));
+        assert_eq!(block_on(dispatcher.disconnect()), Ok(()));
+    }
+
+    fn register_in_place<D: Dispatcher>(dispatcher: &mut D) {
+        for name in ["orders", "payments"] {
+            dispatcher.register_ref(&DispatcherDefinition::new(name, None), handler());
+        }
+        dispatcher
+            .register_ref(&DispatcherDefinition::new("invoices", None), handler())
+            .register_ref(
+                &DispatcherDefinition::new("invoices", Some("paid")),
+                handler(),
+            );
+    }
+
+    fn registered(dispatcher: &CapturingDispatcher) -> Vec<String> {
+        dispatcher
+            .registrations()
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    const REGISTERED_IN_PLACE: [&str; 4] = [
+        "sub(name=orders, type=*)",
+        "sub(name=payments, type=*)",
+        "sub(name=invoices, type=*)",
+        "sub(name=invoices, type=paid)",
+    ];
+
+    #[test]
+    fn register_ref_registers_in_place() {
+        let mut dispatcher = CapturingDispatcher::default();
+
+        register_in_place(&mut dispatcher);
+
+        assert_eq!(registered(&dispatcher), REGISTERED_IN_PLACE);
+    }
+
+    #[test]
+    fn register_ref_reaches_through_wrappers_without_default() {
+        let inner = CapturingDispatcher::default();
+        let (channel, _receiver) =
+            ChannelDispatcher::new(inner.clone(), &DispatcherDefinition::new("audit", None), 1);
+        let mut dispatcher =
+            ReconnectingDispatcher::new(channel, Arc::new(RecordingTimer::default()));
+
+        register_in_place(&mut dispatcher);
+
+        assert_eq!(registered(&inner)[1..], REGISTERED_IN_PLACE);
+    }
+
+    #[test]
+    fn default_consume_n_is_unsupported() {
+        assert_eq!(
+            block_on(CapturingDispatcher::default().consume_n(1)),
+            Err(MessagingError::InternalError)
+        );
+    }
+
+    #[test]
+    fn default_list_destinations_is_unsupported() {
+        assert_eq!(
+            block_on(CapturingDispatcher::default().list_destinations()),
+            Err(MessagingError::InternalError)
+        );
+    }
+
+    #[cfg(feature = "mocks")]
+    #[test]
+    fn mock_can_list_destinations() {
+        let mut dispatcher = MockDispatcher::new();
+        dispatcher
+            .expect_list_destinations()
+            .returning(|| Ok(vec!["orders".to_owned(), "payments".to_owned()]));
+
+        assert_eq!(
+            block_on(dispatcher.list_destinations()),
+            Ok(vec!["orders".to_owned(), "payments".to_owned()])
+        );
+    }
+
+    #[test]
+    fn default_drain_succeeds_regardless_of_the_timeout() {
+        let dispatcher = CapturingDispatcher::default();
+
+        assert_eq!(block_on(dispatcher.drain(Duration::ZERO)), Ok(()));
+    }
+
+    #[cfg(feature = "mocks")]
+    #[test]
+    fn mock_can_override_the_lifecycle() {
+        let mut dispatcher = MockDispatcher::new();
+        dispatcher
+            .expect_connect()
+            .returning(|| Err(MessagingError::ConnectionError));
+        dispatcher.expect_disconnect().returning(|| Ok(()));
+
+        assert_eq!(
+            block_on(dispatcher.connect()),
+            Err(MessagingError::ConnectionError)
+        );
+        assert_eq!(block_on(dispatcher.disconnect()), Ok(()));
+    }
+
+    #[test]
+    fn default_health_check_reports_healthy() {
+        assert_eq!(
+            block_on(CapturingDispatcher::default().health_check()),
+            Ok(())
+        );
+    }
+
+    #[cfg(feature = "mocks")]
+    #[test]
+    fn mock_can_report_an_unhealthy_dispatcher() {
+        let mut dispatcher = MockDispatcher::new();
+        dispatcher
+            .expect_health_check()
+            .returning(|| Err(MessagingError::ConnectionError));
+
+        assert_eq!(
+            block_on(dispatcher.health_check()),
+            Err(MessagingError::ConnectionError)
+        );
+    }
+
+    #[test]
+    fn default_seek_is_unsupported() {
+        let dispatcher = CapturingDispatcher::default();
+        let definition = DispatcherDefinition::new("orders", None);
+
+        let err = block_on(dispatcher.seek(&definition, SeekPosition::Beginning)).unwrap_err();
+
+        assert_eq!(err.code(), Some("unsupported"));
+    }
+
+    #[cfg(feature = "mocks")]
+    #[test]
+    fn mock_can_accept_a_seek() {
+        let mut dispatcher = MockDispatcher::new();
+        dispatcher
+            .expect_seek()
+            .withf(|definition, position| {
+                definition.name == "orders" && *position == SeekPosition::Offset(42)
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let definition = DispatcherDefinition::new("orders", None);
+
+        assert_eq!(
+            block_on(dispatcher.seek(&definition, SeekPosition::Offset(42))),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn default_freeze_is_a_no_op() {
+        struct Unbarriered;
+
+        #[async_trait]
+        impl Dispatcher for Unbarriered {
+            fn register(
+                self,
+                _definition: &DispatcherDefinition,
+                _handler: Arc<dyn ConsumerHandler>,
+            ) -> Self {
+                self
+            }
+
+            fn register_ref(
+                &mut self,
+                _definition: &DispatcherDefinition,
+                _handler: Arc<dyn ConsumerHandler>,
+            ) -> &mut Self {
+                self
+            }
+
+            async fn consume_blocking(&self) -> Result<(), MessagingError> {
+                Ok(())
+            }
+        }
+
+        assert!(Unbarriered.is_frozen());
+        assert!(Unbarriered.freeze().is_frozen());
+    }
 }
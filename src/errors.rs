@@ -8,15 +8,17 @@
 //!
 //! The `MessagingError` enum represents all possible error conditions that can arise
 //! when working with the messaging abstractions, including connection issues,
-//! serialization problems, and handler failures.
+//! serialization problems, and handler failures. [`ErrorCategory`] groups the variants
+//! into coarse classes for alerting and for mapping errors to API responses.
 
+use std::{io, str::Utf8Error, string::FromUtf8Error, time::Duration};
 use thiserror::Error;
 
 /// Represents errors that can occur in messaging operations.
 ///
 /// This enum contains variants for all possible error conditions that may arise
 /// when interacting with messaging systems, from connection failures to serialization issues.
-#[derive(Error, Debug, PartialEq, Eq)]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum MessagingError {
     /// An unexpected internal error occurred.
     #[error("internal error")]
@@ -35,8 +37,14 @@ pub enum MessagingError {
     CreatingConsumerError,
 
     /// Failed to serialize message data.
-    #[error("serializing error")]
-    SerializingError,
+    ///
+    /// The optional detail describes what could not be serialized, e.g. which header key
+    /// broke which rule. It is appended to the displayed error.
+    #[error("serializing error{}", detail_suffix(detail))]
+    SerializingError {
+        /// An optional description of the failure.
+        detail: Option<String>,
+    },
 
     /// Failed to deserialize message data.
     #[error("deserializing error")]
@@ -46,11 +54,384 @@ pub enum MessagingError {
     #[error("error to handle message")]
     HandlerError,
 
-    /// Failed to consume a message, with an optional error message.
-    #[error("failure to consume message `{0}`")]
-    ConsumerError(String),
+    /// Failed to consume a message.
+    ///
+    /// The optional code is a machine-readable, broker-specific identifier of the failure,
+    /// e.g. `channel-closed`, letting callers react to it without parsing the message. It
+    /// is not part of the displayed error.
+    #[error("failure to consume message `{message}`")]
+    ConsumerError {
+        /// A description of the failure.
+        message: String,
+
+        /// An optional machine-readable code identifying the failure.
+        code: Option<String>,
+    },
 
     /// Failed to publish a message.
-    #[error("failure to publish message")]
-    PublisherError,
+    ///
+    /// The optional detail describes why the message was not published, e.g. that it
+    /// exceeds a size limit. It is appended to the displayed error.
+    #[error("failure to publish message{}", detail_suffix(detail))]
+    PublisherError {
+        /// An optional description of the failure.
+        detail: Option<String>,
+    },
+
+    /// An operation did not complete within its time limit.
+    #[error("operation timed out")]
+    TimeoutError,
+
+    /// The message cannot be handled now and should be redelivered after the delay.
+    ///
+    /// Handlers return it to ask for a delayed redelivery instead of an immediate requeue;
+    /// see `ConsumerHandler::exec_manual` and `Acker::nack_after`.
+    #[error("retry after {0:?}")]
+    RetryAfter(Duration),
+}
+
+/// A coarse classification of a [`MessagingError`], as returned by
+/// [`MessagingError::category`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCategory {
+    /// The operation may succeed when retried, e.g. after the broker is reachable again.
+    /// Comparable to an HTTP 503.
+    Transient,
+
+    /// The operation fails the same way when retried, e.g. because the message is
+    /// malformed. Comparable to an HTTP 400 or 422.
+    Permanent,
+
+    /// The application is set up incorrectly and needs a change to succeed.
+    Config,
+
+    /// A bug or unexpected state in the messaging code. Comparable to an HTTP 500.
+    Internal,
+}
+
+impl MessagingError {
+    /// Classifies the error into a coarse [`ErrorCategory`].
+    ///
+    /// The variants map as follows:
+    ///
+    /// * `InternalError` - `Internal`.
+    /// * `UnregisteredHandler` - `Config`, as a handler is missing for a subscribed message.
+    /// * `CreatingConsumerError` - `Config`, as it usually means a missing destination or
+    ///   missing permissions.
+    /// * `SerializingError` and `DeserializingError` - `Permanent`, as the same data fails
+    ///   again.
+    /// * `ConnectionError`, `PublisherError`, `ConsumerError` and `TimeoutError` -
+    ///   `Transient`.
+    /// * `HandlerError` - `Transient`, matching the broker redelivering failed messages.
+    /// * `RetryAfter` - `Transient`.
+    ///
+    /// # Returns
+    ///
+    /// The category of the error.
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            MessagingError::InternalError => ErrorCategory::Internal,
+            MessagingError::UnregisteredHandler | MessagingError::CreatingConsumerError => {
+                ErrorCategory::Config
+            }
+            MessagingError::SerializingError { .. } | MessagingError::DeserializingError => {
+                ErrorCategory::Permanent
+            }
+            MessagingError::ConnectionError
+            | MessagingError::HandlerError
+            | MessagingError::ConsumerError { .. }
+            | MessagingError::PublisherError { .. }
+            | MessagingError::TimeoutError
+            | MessagingError::RetryAfter(_) => ErrorCategory::Transient,
+        }
+    }
+
+    /// Returns whether retrying the failed operation may succeed.
+    ///
+    /// An error is retryable when its [`category`](MessagingError::category) is
+    /// `ErrorCategory::Transient`, e.g. `TimeoutError` or `ConnectionError`. Permanent,
+    /// configuration and internal errors fail the same way when retried.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the error is transient.
+    pub fn is_retryable(&self) -> bool {
+        self.category() == ErrorCategory::Transient
+    }
+
+    /// Creates a `MessagingError::ConsumerError` without a code.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - A description of the failure.
+    ///
+    /// # Returns
+    ///
+    /// A new `MessagingError::ConsumerError`.
+    pub fn consumer<T>(message: T) -> Self
+    where
+        T: Into<String>,
+    {
+        MessagingError::ConsumerError {
+            message: message.into(),
+            code: None,
+        }
+    }
+
+    /// Creates a `MessagingError::ConsumerError` with a code.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - A description of the failure.
+    /// * `code` - A machine-readable code identifying the failure.
+    ///
+    /// # Returns
+    ///
+    /// A new `MessagingError::ConsumerError`.
+    pub fn consumer_with_code<T>(message: T, code: T) -> Self
+    where
+        T: Into<String>,
+    {
+        MessagingError::ConsumerError {
+            message: message.into(),
+            code: Some(code.into()),
+        }
+    }
+
+    /// Creates a `MessagingError::SerializingError` with a detail.
+    ///
+    /// # Arguments
+    ///
+    /// * `detail` - A description of the failure.
+    ///
+    /// # Returns
+    ///
+    /// A new `MessagingError::SerializingError`.
+    pub fn serializing<T>(detail: T) -> Self
+    where
+        T: Into<String>,
+    {
+        MessagingError::SerializingError {
+            detail: Some(detail.into()),
+        }
+    }
+
+    /// Creates a `MessagingError::PublisherError` with a detail.
+    ///
+    /// # Arguments
+    ///
+    /// * `detail` - A description of the failure.
+    ///
+    /// # Returns
+    ///
+    /// A new `MessagingError::PublisherError`.
+    pub fn publisher<T>(detail: T) -> Self
+    where
+        T: Into<String>,
+    {
+        MessagingError::PublisherError {
+            detail: Some(detail.into()),
+        }
+    }
+
+    /// Returns the detail of a `MessagingError::SerializingError` or
+    /// `MessagingError::PublisherError`.
+    ///
+    /// # Returns
+    ///
+    /// The detail, or `None` for other variants and errors without a detail.
+    pub fn detail(&self) -> Option<&str> {
+        match self {
+            MessagingError::SerializingError { detail }
+            | MessagingError::PublisherError { detail } => detail.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Returns the machine-readable code of a `MessagingError::ConsumerError`.
+    ///
+    /// # Returns
+    ///
+    /// The code, or `None` for other variants and consumer errors without a code.
+    pub fn code(&self) -> Option<&str> {
+        match self {
+            MessagingError::ConsumerError { code, .. } => code.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// Formats an optional detail as a suffix of a displayed error.
+fn detail_suffix(detail: &Option<String>) -> String {
+    detail
+        .as_deref()
+        .map_or_else(String::new, |detail| format!(": {detail}"))
+}
+
+impl From<io::Error> for MessagingError {
+    /// Converts an I/O error into a `MessagingError::ConnectionError`.
+    ///
+    /// Broker clients mostly surface I/O errors for socket failures, so they are treated
+    /// as connection failures. The original error is not retained, keeping the enum `Eq`.
+    fn from(_: io::Error) -> Self {
+        MessagingError::ConnectionError
+    }
+}
+
+impl From<Utf8Error> for MessagingError {
+    /// Converts an invalid UTF-8 error into a `MessagingError::DeserializingError`.
+    fn from(_: Utf8Error) -> Self {
+        MessagingError::DeserializingError
+    }
+}
+
+impl From<FromUtf8Error> for MessagingError {
+    /// Converts an invalid UTF-8 error into a `MessagingError::DeserializingError`.
+    fn from(_: FromUtf8Error) -> Self {
+        MessagingError::DeserializingError
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn displays_timeouts() {
+        assert_eq!(
+            MessagingError::TimeoutError.to_string(),
+            "operation timed out"
+        );
+    }
+
+    #[test]
+    fn displays_and_keeps_the_retry_delay() {
+        let err = MessagingError::RetryAfter(Duration::from_millis(1500));
+
+        assert_eq!(err.to_string(), "retry after 1.5s");
+        assert_eq!(err, MessagingError::RetryAfter(Duration::from_millis(1500)));
+        assert_ne!(err, MessagingError::RetryAfter(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn classifies_timeouts_as_retryable() {
+        assert_eq!(
+            MessagingError::TimeoutError.category(),
+            ErrorCategory::Transient
+        );
+        assert!(MessagingError::TimeoutError.is_retryable());
+    }
+
+    #[test]
+    fn classifies_every_variant() {
+        let cases = [
+            (MessagingError::InternalError, ErrorCategory::Internal),
+            (MessagingError::UnregisteredHandler, ErrorCategory::Config),
+            (MessagingError::ConnectionError, ErrorCategory::Transient),
+            (MessagingError::CreatingConsumerError, ErrorCategory::Config),
+            (
+                MessagingError::SerializingError { detail: None },
+                ErrorCategory::Permanent,
+            ),
+            (MessagingError::DeserializingError, ErrorCategory::Permanent),
+            (MessagingError::HandlerError, ErrorCategory::Transient),
+            (MessagingError::consumer("closed"), ErrorCategory::Transient),
+            (
+                MessagingError::PublisherError { detail: None },
+                ErrorCategory::Transient,
+            ),
+            (MessagingError::TimeoutError, ErrorCategory::Transient),
+            (
+                MessagingError::RetryAfter(Duration::from_secs(1)),
+                ErrorCategory::Transient,
+            ),
+        ];
+
+        for (err, category) in cases {
+            assert_eq!(err.category(), category, "{err:?}");
+            assert_eq!(
+                err.is_retryable(),
+                category == ErrorCategory::Transient,
+                "{err:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn exposes_codes_and_details() {
+        let err = MessagingError::consumer_with_code("channel closed", "channel-closed");
+        assert_eq!(err.code(), Some("channel-closed"));
+        assert_eq!(
+            err.to_string(),
+            "failure to consume message `channel closed`"
+        );
+        assert_eq!(MessagingError::consumer("closed").code(), None);
+
+        let err = MessagingError::publisher("too large");
+        assert_eq!(err.detail(), Some("too large"));
+        assert_eq!(err.to_string(), "failure to publish message: too large");
+        assert_eq!(MessagingError::HandlerError.detail(), None);
+    }
+
+    #[test]
+    fn displays_consumer_errors_without_the_code() {
+        let err = MessagingError::consumer_with_code("partition revoked", "revoked");
+
+        assert_eq!(
+            err.to_string(),
+            "failure to consume message `partition revoked`"
+        );
+    }
+
+    #[test]
+    fn compares_consumer_errors_by_message_and_code() {
+        let err = MessagingError::consumer_with_code("closed", "channel-closed");
+
+        assert_eq!(
+            err,
+            MessagingError::consumer_with_code("closed", "channel-closed")
+        );
+        assert_ne!(err, MessagingError::consumer_with_code("closed", "other"));
+        assert_ne!(err, MessagingError::consumer("closed"));
+        assert_ne!(
+            MessagingError::consumer("closed"),
+            MessagingError::consumer("reset")
+        );
+    }
+
+    #[test]
+    fn converts_io_errors_to_connection_errors() {
+        let io = io::Error::new(io::ErrorKind::BrokenPipe, "closed");
+
+        assert_eq!(MessagingError::from(io), MessagingError::ConnectionError);
+    }
+
+    #[test]
+    fn converts_utf8_errors_to_deserializing_errors() {
+        let utf8 = String::from_utf8(vec![0xff]).unwrap_err().utf8_error();
+
+        assert_eq!(
+            MessagingError::from(utf8),
+            MessagingError::DeserializingError
+        );
+    }
+
+    #[test]
+    fn converts_from_utf8_errors_to_deserializing_errors() {
+        let utf8 = String::from_utf8(vec![0xff]).unwrap_err();
+
+        assert_eq!(
+            MessagingError::from(utf8),
+            MessagingError::DeserializingError
+        );
+    }
+
+    #[test]
+    fn conversions_apply_with_the_question_mark_operator() {
+        fn read() -> Result<(), MessagingError> {
+            Err(io::Error::from(io::ErrorKind::ConnectionReset))?
+        }
+
+        assert_eq!(read(), Err(MessagingError::ConnectionError));
+    }
 }
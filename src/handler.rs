@@ -9,10 +9,24 @@
 //! The `ConsumerHandler` trait defines how incoming messages should be processed, while
 //! the `ConsumerMessage` struct represents a received message with its metadata.
 
-use crate::errors::MessagingError;
+use crate::{
+    codec::CodecRegistry,
+    errors::MessagingError,
+    headers,
+    publisher::{HeaderValues, PublishMessage},
+    timer::Timer,
+};
 use async_trait::async_trait;
 use opentelemetry::Context;
-use std::collections::HashMap;
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    fmt,
+    future::Future,
+    marker::PhantomData,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 #[cfg(feature = "mocks")]
 use mockall::*;
@@ -21,7 +35,7 @@ use mockall::*;
 ///
 /// This struct contains the message content along with metadata such as the source,
 /// message type, and headers.
-#[derive(Clone, Default)]
+#[derive(Clone, Default, PartialEq)]
 pub struct ConsumerMessage {
     /// The source of the message (e.g., queue or exchange name).
     pub from: String,
@@ -34,6 +48,135 @@ pub struct ConsumerMessage {
 
     /// Optional headers associated with the message.
     pub headers: Option<HashMap<String, String>>,
+
+    /// Optional unique identifier of the message, assigned by the producer or the broker.
+    pub message_id: Option<String>,
+
+    /// Whether the broker flagged the message as delivered before, e.g. the AMQP
+    /// `redelivered` flag.
+    pub redelivered: bool,
+
+    /// Optional number of times the message has been delivered, including this delivery,
+    /// as reported natively by the broker.
+    pub delivery_count: Option<u32>,
+
+    /// Optional group the message belongs to, e.g. the Azure Service Bus session id.
+    ///
+    /// Messages of a group are expected to be delivered in order to a single consumer at a
+    /// time. Dispatchers supporting sessions use it; others ignore it.
+    pub group_id: Option<String>,
+
+    /// Arbitrary typed values attached to the message by middleware for downstream
+    /// handlers, e.g. the authenticated user. Empty when the message is received.
+    pub extensions: Extensions,
+}
+
+impl fmt::Debug for ConsumerMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConsumerMessage")
+            .field("from", &self.from)
+            .field("msg_type", &self.msg_type)
+            .field("data", &DataPreview(&self.data))
+            .field("headers", &self.headers)
+            .field("message_id", &self.message_id)
+            .field("redelivered", &self.redelivered)
+            .field("delivery_count", &self.delivery_count)
+            .field("group_id", &self.group_id)
+            .field("extensions", &self.extensions)
+            .finish()
+    }
+}
+
+/// A map of typed values attached to a message, holding at most one value per type.
+///
+/// Values are shared, so cloning a message or its extensions does not clone the values.
+/// Extensions do not take part in comparisons: two maps are always equal, so comparing
+/// messages only compares their broker data.
+#[derive(Clone, Default)]
+pub struct Extensions {
+    map: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
+}
+
+impl Extensions {
+    /// Inserts a value, replacing any previous value of the same type.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to attach.
+    pub fn insert<T>(&mut self, value: T)
+    where
+        T: Send + Sync + 'static,
+    {
+        self.map.insert(TypeId::of::<T>(), Arc::new(value));
+    }
+
+    /// Returns the value of a type, if one was inserted.
+    pub fn get<T>(&self) -> Option<&T>
+    where
+        T: Send + Sync + 'static,
+    {
+        self.map.get(&TypeId::of::<T>())?.downcast_ref()
+    }
+
+    /// Removes the value of a type.
+    ///
+    /// # Returns
+    ///
+    /// `true` if a value of that type was present.
+    pub fn remove<T>(&mut self) -> bool
+    where
+        T: Send + Sync + 'static,
+    {
+        self.map.remove(&TypeId::of::<T>()).is_some()
+    }
+
+    /// Returns the number of attached values.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if no value is attached.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+
+impl PartialEq for Extensions {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl fmt::Debug for Extensions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} values", self.map.len())
+    }
+}
+
+/// Formats message data as its length and a short hex preview, so large payloads do not
+/// flood debug output.
+pub(crate) struct DataPreview<'a>(pub(crate) &'a [u8]);
+
+impl DataPreview<'_> {
+    const PREVIEW_LEN: usize = 16;
+}
+
+impl fmt::Debug for DataPreview<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} bytes", self.0.len())?;
+        if self.0.is_empty() {
+            return Ok(());
+        }
+
+        f.write_str(" 0x")?;
+        for byte in self.0.iter().take(Self::PREVIEW_LEN) {
+            write!(f, "{byte:02x}")?;
+        }
+        if self.0.len() > Self::PREVIEW_LEN {
+            f.write_str("…")?;
+        }
+        Ok(())
+    }
 }
 
 impl ConsumerMessage {
@@ -63,8 +206,223 @@ impl ConsumerMessage {
             msg_type: msg_type.into(),
             data: data.into(),
             headers,
+            message_id: None,
+            redelivered: false,
+            delivery_count: None,
+            group_id: None,
+            extensions: Extensions::default(),
         }
     }
+
+    /// Returns the message data as UTF-8 text.
+    ///
+    /// # Returns
+    ///
+    /// The data as a string slice, or `MessagingError::DeserializingError` if it is not
+    /// valid UTF-8.
+    pub fn data_str(&self) -> Result<&str, MessagingError> {
+        Ok(str::from_utf8(&self.data)?)
+    }
+
+    /// Returns an owned copy of the message data as UTF-8 text.
+    ///
+    /// # Returns
+    ///
+    /// The data as a string, or `MessagingError::DeserializingError` if it is not valid
+    /// UTF-8.
+    pub fn data_string(&self) -> Result<String, MessagingError> {
+        self.data_str().map(str::to_owned)
+    }
+
+    /// Decodes the message data with the registry codec for its `content-type` header.
+    ///
+    /// # Arguments
+    ///
+    /// * `registry` - The registry to pick the codec from.
+    ///
+    /// # Returns
+    ///
+    /// The decoded value, or `MessagingError::DeserializingError` if the message has no
+    /// content type, no codec is registered for it or decoding fails.
+    pub fn decode<T>(&self, registry: &CodecRegistry) -> Result<T, MessagingError>
+    where
+        T: 'static,
+    {
+        let content_type = self
+            .content_type()
+            .ok_or(MessagingError::DeserializingError)?;
+        registry.decode(content_type, &self.data)
+    }
+
+    /// Returns the W3C trace context carried in the `traceparent` header.
+    pub fn trace_parent(&self) -> Option<&str> {
+        self.header(headers::TRACE_PARENT)
+    }
+
+    /// Returns the message id, preferring the `message_id` field over the
+    /// `message-id` header.
+    pub fn message_id(&self) -> Option<&str> {
+        self.message_id
+            .as_deref()
+            .or_else(|| self.header(headers::MESSAGE_ID))
+    }
+
+    /// Returns the media type of the message data carried in the `content-type` header.
+    pub fn content_type(&self) -> Option<&str> {
+        self.header(headers::CONTENT_TYPE)
+    }
+
+    /// Returns the encoding of the message data carried in the `content-encoding` header.
+    pub fn content_encoding(&self) -> Option<&str> {
+        self.header(headers::CONTENT_ENCODING)
+    }
+
+    /// Returns the correlation id carried in the `correlation-id` header.
+    pub fn correlation_id(&self) -> Option<&str> {
+        self.header(headers::CORRELATION_ID)
+    }
+
+    /// Returns the destination for replies carried in the `reply-to` header.
+    pub fn reply_to(&self) -> Option<&str> {
+        self.header(headers::REPLY_TO)
+    }
+
+    /// Builds the reply to a request sent by an [`RpcClient`](crate::rpc::RpcClient) or a
+    /// similar requester.
+    ///
+    /// The reply is addressed to the `reply-to` header of the message and carries its
+    /// `correlation-id` header, if any, so the requester can match it to the request.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The raw binary data of the reply.
+    ///
+    /// # Returns
+    ///
+    /// The reply to publish, or `MessagingError::ConsumerError` if the message has no
+    /// `reply-to` header.
+    pub fn reply(&self, data: &[u8]) -> Result<PublishMessage, MessagingError> {
+        let reply_to = self
+            .reply_to()
+            .ok_or_else(|| MessagingError::consumer("message has no reply-to header"))?;
+
+        let mut reply = PublishMessage::new(None, reply_to, None, None, data, None);
+        if let Some(correlation_id) = self.correlation_id() {
+            reply.set_header(
+                headers::CORRELATION_ID,
+                HeaderValues::ShortString(correlation_id.to_owned()),
+            );
+        }
+
+        Ok(reply)
+    }
+
+    /// Returns the delivery count, preferring the `delivery_count` field over the
+    /// `x-delivery-count` header.
+    ///
+    /// A header value that is not a valid unsigned integer is treated as absent.
+    pub fn delivery_count(&self) -> Option<u32> {
+        self.delivery_count.or_else(|| {
+            self.header(headers::DELIVERY_COUNT)
+                .and_then(|v| v.parse().ok())
+        })
+    }
+
+    /// Returns the schema version carried in the `schema-version` header.
+    ///
+    /// A header value that is not a valid unsigned integer is treated as absent.
+    pub fn schema_version(&self) -> Option<u32> {
+        self.header(headers::SCHEMA_VERSION)
+            .and_then(|v| v.parse().ok())
+    }
+
+    /// Returns the time elapsed since the message was sent, read from the `x-sent-at` header.
+    ///
+    /// The send time is stamped with the clock of the publishing host and compared with the
+    /// clock of the consuming host, so the age is only as accurate as the synchronization of
+    /// both clocks. A sender clock ahead of the receiver clock yields a zero age rather than
+    /// a negative one.
+    ///
+    /// # Returns
+    ///
+    /// The age of the message, or `None` if the header is absent or not a valid number of
+    /// milliseconds.
+    pub fn age(&self) -> Option<Duration> {
+        let millis = self.header(headers::SENT_AT)?.parse().ok()?;
+        let sent_at = UNIX_EPOCH.checked_add(Duration::from_millis(millis))?;
+        Some(
+            SystemTime::now()
+                .duration_since(sent_at)
+                .unwrap_or_default(),
+        )
+    }
+
+    /// Returns the value of a header.
+    ///
+    /// Keys are matched exactly, so the lookup is case-sensitive.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The header key.
+    ///
+    /// # Returns
+    ///
+    /// The header value, or `None` if the message has no such header.
+    pub fn header(&self, key: &str) -> Option<&str> {
+        self.headers.as_ref()?.get(key).map(String::as_str)
+    }
+
+    /// Returns the value of a header, or a default when it is absent.
+    ///
+    /// Keys are matched exactly, as in [`ConsumerMessage::header`].
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The header key.
+    /// * `default` - The value returned when the message has no such header.
+    ///
+    /// # Returns
+    ///
+    /// The header value, or `default`.
+    pub fn header_or<'a>(&'a self, key: &str, default: &'a str) -> &'a str {
+        self.header(key).unwrap_or(default)
+    }
+
+    /// Returns the headers of the message, initializing them if the message has none.
+    pub fn ensure_headers(&mut self) -> &mut HashMap<String, String> {
+        self.headers.get_or_insert_with(Default::default)
+    }
+
+    /// Sets a header, initializing the headers if the message has none.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The header key.
+    /// * `value` - The header value.
+    ///
+    /// # Returns
+    ///
+    /// The previous value of the header, if any.
+    pub fn set_header<K, V>(&mut self, key: K, value: V) -> Option<String>
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.ensure_headers().insert(key.into(), value.into())
+    }
+
+    /// Removes a header.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The header key.
+    ///
+    /// # Returns
+    ///
+    /// The removed value, or `None` if the message had no such header.
+    pub fn remove_header(&mut self, key: &str) -> Option<String> {
+        self.headers.as_mut()?.remove(key)
+    }
 }
 
 /// Defines the interface for handling consumed messages.
@@ -85,4 +443,651 @@ pub trait ConsumerHandler: Send + Sync {
     ///
     /// A `Result` indicating success or containing an error if handling fails.
     async fn exec(&self, ctx: &Context, msg: &ConsumerMessage) -> Result<(), MessagingError>;
+
+    /// Executes the handler logic for a received message, settling it explicitly.
+    ///
+    /// Dispatchers with manual acknowledgement call this method instead of `exec` and
+    /// leave the disposition of the message to the handler, e.g. to acknowledge it early
+    /// and continue long-running work afterwards. The returned error is only reported; it
+    /// does not settle the message. The default implementation calls `exec`, then
+    /// acknowledges the message on success, settles it with `Acker::nack_after` on
+    /// `MessagingError::RetryAfter` and rejects it with requeue on any other failure.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The OpenTelemetry context for tracing and monitoring.
+    /// * `msg` - The received message to process.
+    /// * `acker` - The acknowledger settling the message with the broker. It is shared, so
+    ///   the handler can keep it to settle the message after returning.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or containing an error if handling or settling fails.
+    async fn exec_manual(
+        &self,
+        ctx: &Context,
+        msg: &ConsumerMessage,
+        acker: Arc<dyn Acker>,
+    ) -> Result<(), MessagingError> {
+        match self.exec(ctx, msg).await {
+            Ok(()) => acker.ack().await,
+            Err(MessagingError::RetryAfter(delay)) => {
+                acker.nack_after(delay).await?;
+                Err(MessagingError::RetryAfter(delay))
+            }
+            Err(err) => {
+                acker.nack(true).await?;
+                Err(err)
+            }
+        }
+    }
+}
+
+/// Defines the interface for settling a consumed message with the broker.
+///
+/// Implementations are provided by dispatchers supporting manual acknowledgement, one per
+/// delivered message. A message should be settled exactly once; settling it again is up
+/// to the implementation to ignore or reject.
+#[cfg_attr(feature = "mocks", automock)]
+#[async_trait]
+pub trait Acker: Send + Sync {
+    /// Acknowledges the message, removing it from the broker.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or containing an error if acknowledging fails.
+    async fn ack(&self) -> Result<(), MessagingError>;
+
+    /// Rejects the message.
+    ///
+    /// # Arguments
+    ///
+    /// * `requeue` - Whether the broker should redeliver the message, rather than discard
+    ///   or dead-letter it.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or containing an error if rejecting fails.
+    async fn nack(&self, requeue: bool) -> Result<(), MessagingError>;
+
+    /// Rejects the message, asking the broker to redeliver it after a delay.
+    ///
+    /// Implementations should map it to the delayed redelivery of the broker, e.g. a
+    /// scheduled enqueue time or a delay queue. The default implementation requeues the
+    /// message immediately; wrap the acker in a [`DelayedRequeueAcker`] to wait locally
+    /// before requeueing instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `delay` - How long the broker should wait before redelivering the message.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or containing an error if rejecting fails.
+    async fn nack_after(&self, _delay: Duration) -> Result<(), MessagingError> {
+        self.nack(true).await
+    }
+}
+
+/// An acker falling back to sleeping before requeueing for brokers without delayed
+/// redelivery.
+///
+/// `nack_after` waits for the delay on the timer, then rejects the message with requeue.
+/// The message stays unacknowledged while waiting, so it counts against the prefetch
+/// limit and the broker redelivers it anyway if the connection drops.
+pub struct DelayedRequeueAcker {
+    inner: Arc<dyn Acker>,
+    timer: Arc<dyn Timer>,
+}
+
+impl DelayedRequeueAcker {
+    /// Creates a new delayed requeue acker.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The acker settling the message with the broker.
+    /// * `timer` - The timer used to wait before requeueing.
+    ///
+    /// # Returns
+    ///
+    /// A new `DelayedRequeueAcker` instance.
+    pub fn new(inner: Arc<dyn Acker>, timer: Arc<dyn Timer>) -> Self {
+        DelayedRequeueAcker { inner, timer }
+    }
+}
+
+#[async_trait]
+impl Acker for DelayedRequeueAcker {
+    async fn ack(&self) -> Result<(), MessagingError> {
+        self.inner.ack().await
+    }
+
+    async fn nack(&self, requeue: bool) -> Result<(), MessagingError> {
+        self.inner.nack(requeue).await
+    }
+
+    async fn nack_after(&self, delay: Duration) -> Result<(), MessagingError> {
+        self.timer.sleep(delay).await;
+        self.inner.nack(true).await
+    }
+}
+
+/// Defines the interface for handling consumed messages in batches.
+///
+/// Sinks such as databases or search indexes are often far more efficient when writing
+/// many messages at once. The batch succeeds or fails as a whole: an error applies to
+/// every message of the batch.
+#[cfg_attr(feature = "mocks", automock)]
+#[async_trait]
+pub trait BatchConsumerHandler: Send + Sync {
+    /// Executes the handler logic for a batch of received messages.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The OpenTelemetry context for tracing and monitoring.
+    /// * `msgs` - The received messages to process, in arrival order.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or containing an error if handling fails.
+    async fn exec_batch(
+        &self,
+        ctx: &Context,
+        msgs: &[ConsumerMessage],
+    ) -> Result<(), MessagingError>;
+}
+
+struct FnHandler<F, Fut> {
+    f: F,
+    _marker: PhantomData<fn() -> Fut>,
+}
+
+#[async_trait]
+impl<F, Fut> ConsumerHandler for FnHandler<F, Fut>
+where
+    F: Fn(&Context, &ConsumerMessage) -> Fut + Send + Sync,
+    Fut: Future<Output = Result<(), MessagingError>> + Send,
+{
+    async fn exec(&self, ctx: &Context, msg: &ConsumerMessage) -> Result<(), MessagingError> {
+        (self.f)(ctx, msg).await
+    }
+}
+
+/// Creates a handler from a closure.
+///
+/// The future returned by the closure cannot borrow the context or the message, so the
+/// closure should clone whatever it needs before building the future.
+///
+/// # Arguments
+///
+/// * `f` - The closure invoked for every message.
+///
+/// # Returns
+///
+/// The closure as a `ConsumerHandler`, ready to be registered with a dispatcher.
+pub fn fn_handler<F, Fut>(f: F) -> Arc<dyn ConsumerHandler>
+where
+    F: Fn(&Context, &ConsumerMessage) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<(), MessagingError>> + Send + 'static,
+{
+    Arc::new(FnHandler {
+        f,
+        _marker: PhantomData,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{RecordingTimer, TestHandler, block_on};
+    use std::sync::Mutex;
+
+    fn with_headers(headers: &[(&str, &str)]) -> ConsumerMessage {
+        let headers = headers
+            .iter()
+            .map(|(k, v)| ((*k).to_owned(), (*v).to_owned()))
+            .collect();
+        ConsumerMessage::new("orders", "created", b"data", Some(headers))
+    }
+
+    #[test]
+    fn reads_system_headers() {
+        let msg = with_headers(&[
+            (headers::TRACE_PARENT, "00-trace-span-01"),
+            (headers::MESSAGE_ID, "42"),
+            (headers::CONTENT_TYPE, "application/json"),
+            (headers::CONTENT_ENCODING, "gzip"),
+        ]);
+
+        assert_eq!(msg.trace_parent(), Some("00-trace-span-01"));
+        assert_eq!(msg.message_id(), Some("42"));
+        assert_eq!(msg.content_type(), Some("application/json"));
+        assert_eq!(msg.content_encoding(), Some("gzip"));
+    }
+
+    #[test]
+    fn system_headers_are_absent_without_headers() {
+        let msg = ConsumerMessage::new("orders", "created", b"data", None);
+
+        assert_eq!(msg.trace_parent(), None);
+        assert_eq!(msg.message_id(), None);
+        assert_eq!(msg.content_type(), None);
+        assert_eq!(msg.content_encoding(), None);
+    }
+
+    #[test]
+    fn message_id_field_takes_precedence_over_the_header() {
+        let mut msg = with_headers(&[(headers::MESSAGE_ID, "header")]);
+        msg.message_id = Some("field".to_owned());
+
+        assert_eq!(msg.message_id(), Some("field"));
+    }
+
+    #[test]
+    fn fn_handler_runs_the_closure() {
+        let handler = fn_handler(|_, msg: &ConsumerMessage| {
+            let from = msg.from.clone();
+            async move {
+                if from == "orders" {
+                    Ok(())
+                } else {
+                    Err(MessagingError::HandlerError)
+                }
+            }
+        });
+        let ctx = Context::new();
+
+        let orders = ConsumerMessage::new("orders", "created", b"data", None);
+        let invoices = ConsumerMessage::new("invoices", "created", b"data", None);
+        assert_eq!(block_on(handler.exec(&ctx, &orders)), Ok(()));
+        assert_eq!(
+            block_on(handler.exec(&ctx, &invoices)),
+            Err(MessagingError::HandlerError)
+        );
+    }
+
+    #[test]
+    fn messages_compare_by_value() {
+        let msg = ConsumerMessage::new("orders", "created", b"data", None);
+        let mut other = msg.clone();
+        assert_eq!(msg, other);
+
+        other.redelivered = true;
+        assert_ne!(msg, other);
+    }
+
+    #[test]
+    fn debug_previews_the_data() {
+        let short = ConsumerMessage::new("orders", "created", b"\x01\xab", None);
+        let long = ConsumerMessage::new("orders", "created", &[0; 20], None);
+        let empty = ConsumerMessage::new("orders", "created", b"", None);
+
+        assert!(format!("{short:?}").contains("data: 2 bytes 0x01ab,"));
+        assert!(format!("{long:?}").contains(&format!("data: 20 bytes 0x{}…,", "00".repeat(16))));
+        assert!(format!("{empty:?}").contains("data: 0 bytes,"));
+    }
+
+    #[test]
+    fn header_returns_present_values_only() {
+        let msg = with_headers(&[("tenant", "acme")]);
+
+        assert_eq!(msg.header("tenant"), Some("acme"));
+        assert_eq!(msg.header("Tenant"), None);
+        assert_eq!(msg.header("region"), None);
+        assert_eq!(
+            ConsumerMessage::new("orders", "created", b"data", None).header("tenant"),
+            None
+        );
+    }
+
+    #[test]
+    fn header_or_falls_back_to_the_default() {
+        let msg = with_headers(&[("tenant", "acme")]);
+
+        assert_eq!(msg.header_or("tenant", "default"), "acme");
+        assert_eq!(msg.header_or("region", "eu"), "eu");
+    }
+
+    #[test]
+    fn reads_utf8_data_as_text() {
+        let msg = ConsumerMessage::new("orders", "created", "héllo".as_bytes(), None);
+
+        assert_eq!(msg.data_str(), Ok("héllo"));
+        assert_eq!(msg.data_string(), Ok("héllo".to_owned()));
+    }
+
+    #[test]
+    fn rejects_invalid_utf8_data() {
+        let msg = ConsumerMessage::new("orders", "created", b"\xff\xfe", None);
+
+        assert_eq!(msg.data_str(), Err(MessagingError::DeserializingError));
+        assert_eq!(msg.data_string(), Err(MessagingError::DeserializingError));
+    }
+
+    #[test]
+    fn delivery_attempt_fields_default_to_a_first_delivery() {
+        let msg = ConsumerMessage::new("orders", "created", b"data", None);
+
+        assert!(!msg.redelivered);
+        assert_eq!(msg.delivery_count, None);
+        assert_eq!(msg.delivery_count(), None);
+    }
+
+    #[test]
+    fn delivery_attempt_fields_survive_clone() {
+        let mut msg = ConsumerMessage::new("orders", "created", b"data", None);
+        msg.redelivered = true;
+        msg.delivery_count = Some(3);
+
+        let cloned = msg.clone();
+
+        assert!(cloned.redelivered);
+        assert_eq!(cloned.delivery_count(), Some(3));
+    }
+
+    #[test]
+    fn delivery_count_falls_back_to_a_valid_header() {
+        let mut msg = with_headers(&[(headers::DELIVERY_COUNT, "2")]);
+        assert_eq!(msg.delivery_count(), Some(2));
+
+        msg.delivery_count = Some(5);
+        assert_eq!(msg.delivery_count(), Some(5));
+
+        let invalid = with_headers(&[(headers::DELIVERY_COUNT, "two")]);
+        assert_eq!(invalid.delivery_count(), None);
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct User(&'static str);
+
+    #[test]
+    fn extensions_store_one_value_per_type() {
+        let mut msg = ConsumerMessage::new("orders", "created", b"data", None);
+        assert!(msg.extensions.is_empty());
+        assert_eq!(msg.extensions.get::<User>(), None);
+
+        msg.extensions.insert(User("alice"));
+        msg.extensions.insert(7u32);
+        msg.extensions.insert(User("bob"));
+
+        assert_eq!(msg.extensions.get::<User>(), Some(&User("bob")));
+        assert_eq!(msg.extensions.get::<u32>(), Some(&7));
+        assert_eq!(msg.extensions.len(), 2);
+    }
+
+    #[test]
+    fn removes_extensions_by_type() {
+        let mut extensions = Extensions::default();
+        extensions.insert(User("alice"));
+
+        assert!(extensions.remove::<User>());
+        assert!(!extensions.remove::<User>());
+        assert!(extensions.is_empty());
+    }
+
+    #[test]
+    fn clones_share_extensions_that_do_not_affect_equality() {
+        let mut msg = ConsumerMessage::new("orders", "created", b"data", None);
+        let plain = msg.clone();
+        msg.extensions.insert(User("alice"));
+
+        let cloned = msg.clone();
+
+        assert!(std::ptr::eq(
+            cloned.extensions.get::<User>().unwrap(),
+            msg.extensions.get::<User>().unwrap()
+        ));
+        assert_eq!(msg, plain);
+        assert_eq!(format!("{:?}", msg.extensions), "1 values");
+    }
+
+    fn sent_at(time: SystemTime) -> ConsumerMessage {
+        let millis = time.duration_since(UNIX_EPOCH).unwrap().as_millis();
+        with_headers(&[(headers::SENT_AT, &millis.to_string())])
+    }
+
+    #[test]
+    fn computes_the_age_from_the_send_time() {
+        let age = sent_at(SystemTime::now() - Duration::from_secs(5))
+            .age()
+            .unwrap();
+
+        assert!(age >= Duration::from_secs(5) && age < Duration::from_secs(6));
+    }
+
+    #[test]
+    fn messages_sent_in_the_future_have_a_zero_age() {
+        let msg = sent_at(SystemTime::now() + Duration::from_secs(60));
+
+        assert_eq!(msg.age(), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn age_is_unknown_without_a_valid_send_time() {
+        assert_eq!(with_headers(&[]).age(), None);
+        assert_eq!(with_headers(&[(headers::SENT_AT, "yesterday")]).age(), None);
+    }
+
+    #[test]
+    fn set_header_initializes_missing_headers() {
+        let mut msg = ConsumerMessage::new("orders", "created", b"data", None);
+
+        assert_eq!(msg.set_header("tenant", "acme"), None);
+        assert_eq!(msg.set_header("tenant", "globex"), Some("acme".to_owned()));
+        assert_eq!(msg.header("tenant"), Some("globex"));
+    }
+
+    #[test]
+    fn remove_header_without_headers_leaves_them_absent() {
+        let mut msg = ConsumerMessage::new("orders", "created", b"data", None);
+
+        assert_eq!(msg.remove_header("tenant"), None);
+        assert_eq!(msg.headers, None);
+
+        msg.ensure_headers();
+        assert_eq!(msg.headers, Some(HashMap::new()));
+    }
+
+    /// An acker recording how the message was settled.
+    #[derive(Default)]
+    struct RecordingAcker {
+        calls: Mutex<Vec<String>>,
+    }
+
+    impl RecordingAcker {
+        fn calls(&self) -> Vec<String> {
+            self.calls.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait]
+    impl Acker for RecordingAcker {
+        async fn ack(&self) -> Result<(), MessagingError> {
+            self.calls.lock().unwrap().push("ack".to_owned());
+            Ok(())
+        }
+
+        async fn nack(&self, requeue: bool) -> Result<(), MessagingError> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("nack requeue={requeue}"));
+            Ok(())
+        }
+    }
+
+    fn exec_manual(
+        handler: TestHandler,
+        acker: &Arc<RecordingAcker>,
+    ) -> Result<(), MessagingError> {
+        let msg = ConsumerMessage::new("orders", "created", b"data", None);
+        block_on(handler.exec_manual(&Context::new(), &msg, acker.clone()))
+    }
+
+    #[test]
+    fn default_manual_exec_acknowledges_successes() {
+        let acker = Arc::new(RecordingAcker::default());
+
+        assert_eq!(exec_manual(TestHandler::default(), &acker), Ok(()));
+        assert_eq!(acker.calls(), ["ack"]);
+    }
+
+    #[test]
+    fn default_manual_exec_requeues_failures() {
+        let acker = Arc::new(RecordingAcker::default());
+        let handler = TestHandler::failing(MessagingError::HandlerError);
+
+        assert_eq!(
+            exec_manual(handler, &acker),
+            Err(MessagingError::HandlerError)
+        );
+        assert_eq!(acker.calls(), ["nack requeue=true"]);
+    }
+
+    #[test]
+    fn default_nack_after_requeues_immediately() {
+        let acker = RecordingAcker::default();
+
+        assert_eq!(block_on(acker.nack_after(Duration::from_secs(5))), Ok(()));
+        assert_eq!(acker.calls(), ["nack requeue=true"]);
+    }
+
+    #[test]
+    fn delayed_requeue_acker_waits_before_requeueing() {
+        let inner = Arc::new(RecordingAcker::default());
+        let timer = Arc::new(RecordingTimer::default());
+        let acker = DelayedRequeueAcker::new(inner.clone(), timer.clone());
+
+        block_on(acker.nack_after(Duration::from_secs(5))).unwrap();
+        block_on(acker.ack()).unwrap();
+        block_on(acker.nack(false)).unwrap();
+
+        assert_eq!(timer.sleeps(), [Duration::from_secs(5)]);
+        assert_eq!(
+            inner.calls(),
+            ["nack requeue=true", "ack", "nack requeue=false"]
+        );
+    }
+
+    #[test]
+    fn default_manual_exec_passes_the_retry_delay_to_the_acker() {
+        let inner = Arc::new(RecordingAcker::default());
+        let timer = Arc::new(RecordingTimer::default());
+        let acker = DelayedRequeueAcker::new(inner.clone(), timer.clone());
+        let handler = TestHandler::failing(MessagingError::RetryAfter(Duration::from_secs(30)));
+
+        let msg = ConsumerMessage::new("orders", "created", b"data", None);
+        let result = block_on(handler.exec_manual(&Context::new(), &msg, Arc::new(acker)));
+
+        assert_eq!(
+            result,
+            Err(MessagingError::RetryAfter(Duration::from_secs(30)))
+        );
+        assert_eq!(timer.sleeps(), [Duration::from_secs(30)]);
+        assert_eq!(inner.calls(), ["nack requeue=true"]);
+    }
+
+    #[cfg(feature = "mocks")]
+    #[test]
+    fn mock_acker_receives_the_retry_delay() {
+        let mut acker = MockAcker::new();
+        acker.expect_nack().never();
+        acker
+            .expect_nack_after()
+            .with(predicate::eq(Duration::from_millis(250)))
+            .times(1)
+            .returning(|_| Ok(()));
+        let handler = TestHandler::failing(MessagingError::RetryAfter(Duration::from_millis(250)));
+
+        let msg = ConsumerMessage::new("orders", "created", b"data", None);
+        let result = block_on(handler.exec_manual(&Context::new(), &msg, Arc::new(acker)));
+
+        assert_eq!(
+            result,
+            Err(MessagingError::RetryAfter(Duration::from_millis(250)))
+        );
+    }
+
+    #[cfg(feature = "mocks")]
+    #[test]
+    fn mock_acker_observes_the_settlement() {
+        let mut acker = MockAcker::new();
+        acker.expect_ack().never();
+        acker
+            .expect_nack()
+            .with(predicate::eq(true))
+            .times(1)
+            .returning(|_| Ok(()));
+        let handler = TestHandler::failing(MessagingError::HandlerError);
+
+        let msg = ConsumerMessage::new("orders", "created", b"data", None);
+        let result = block_on(handler.exec_manual(&Context::new(), &msg, Arc::new(acker)));
+
+        assert_eq!(result, Err(MessagingError::HandlerError));
+    }
+
+    #[cfg(feature = "mocks")]
+    #[test]
+    fn mock_acker_errors_are_returned() {
+        let mut acker = MockAcker::new();
+        acker
+            .expect_ack()
+            .times(1)
+            .returning(|| Err(MessagingError::ConnectionError));
+
+        let msg = ConsumerMessage::new("orders", "created", b"data", None);
+        let result =
+            block_on(TestHandler::default().exec_manual(&Context::new(), &msg, Arc::new(acker)));
+
+        assert_eq!(result, Err(MessagingError::ConnectionError));
+    }
+
+    #[test]
+    fn reply_is_addressed_to_reply_to_with_the_correlation_id() {
+        let msg = with_headers(&[
+            (headers::REPLY_TO, "replies"),
+            (headers::CORRELATION_ID, "c-1"),
+        ]);
+
+        let reply = msg.reply(b"pong").unwrap();
+
+        assert_eq!(reply.to, "replies");
+        assert_eq!(&*reply.data, b"pong");
+        assert_eq!(
+            reply.headers,
+            Some(HashMap::from([(
+                headers::CORRELATION_ID.to_owned(),
+                HeaderValues::ShortString("c-1".to_owned())
+            )]))
+        );
+    }
+
+    #[test]
+    fn reply_without_correlation_id_has_no_headers() {
+        let reply = with_headers(&[(headers::REPLY_TO, "replies")])
+            .reply(b"pong")
+            .unwrap();
+
+        assert_eq!(reply.headers, None);
+    }
+
+    #[test]
+    fn reply_requires_reply_to() {
+        let msg = with_headers(&[(headers::CORRELATION_ID, "c-1")]);
+
+        assert_eq!(
+            msg.reply(b"pong"),
+            Err(MessagingError::consumer("message has no reply-to header"))
+        );
+    }
+
+    #[test]
+    fn group_id_defaults_to_none_and_survives_clone() {
+        let mut msg = ConsumerMessage::new("orders", "created", b"data", None);
+        assert_eq!(msg.group_id, None);
+
+        msg.group_id = Some("session-1".to_owned());
+
+        assert_eq!(msg.clone().group_id.as_deref(), Some("session-1"));
+    }
 }
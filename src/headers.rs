@@ -0,0 +1,454 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Headers
+//!
+//! This module defines the names of the reserved message headers used across the crate.
+//!
+//! Broker implementations and middleware should use these constants instead of raw
+//! strings, so that every component agrees on where well-known metadata is stored. The
+//! module also provides validation of header maps against common broker restrictions and
+//! a [`Headers`] builder for header maps.
+
+use crate::{errors::MessagingError, publisher::HeaderValues};
+use std::collections::HashMap;
+
+/// The W3C trace context `traceparent` header.
+pub const TRACE_PARENT: &str = "traceparent";
+
+/// The unique identifier of the message.
+pub const MESSAGE_ID: &str = "message-id";
+
+/// The media type of the message data, e.g. `application/json`.
+pub const CONTENT_TYPE: &str = "content-type";
+
+/// The encoding applied to the message data, e.g. `gzip`.
+pub const CONTENT_ENCODING: &str = "content-encoding";
+
+/// The delay in milliseconds before a message is delivered, used by delayed-message
+/// exchanges.
+pub const DELAY: &str = "x-delay";
+
+/// The hex-encoded nonce used to encrypt the message data.
+pub const ENCRYPTION_NONCE: &str = "x-encryption-nonce";
+
+/// The identifier correlating a message with a related message, e.g. a request.
+pub const CORRELATION_ID: &str = "correlation-id";
+
+/// The destination replies to the message should be published to.
+pub const REPLY_TO: &str = "reply-to";
+
+/// The number of times the message has been delivered.
+pub const DELIVERY_COUNT: &str = "x-delivery-count";
+
+/// The version of the schema the message data conforms to.
+pub const SCHEMA_VERSION: &str = "schema-version";
+
+/// The time the message was sent at, in milliseconds since the Unix epoch.
+pub const SENT_AT: &str = "x-sent-at";
+
+/// The identifier shared by all chunks of a message split by a `ChunkingPublisher`.
+pub const CHUNK_ID: &str = "x-chunk-id";
+
+/// The zero-based position of a chunk within its message.
+pub const CHUNK_INDEX: &str = "x-chunk-index";
+
+/// The number of chunks a message was split into.
+pub const CHUNK_COUNT: &str = "x-chunk-count";
+
+/// The maximum length of a header key in bytes, matching the AMQP field name limit.
+pub const MAX_KEY_LENGTH: usize = 128;
+
+/// The default maximum number of headers on a message.
+pub const DEFAULT_MAX_HEADER_COUNT: usize = 128;
+
+/// The default maximum estimated size of all headers of a message in bytes.
+///
+/// 64 KiB matches the property size limit of Azure Service Bus and stays well within the
+/// default 128 KiB frame size of RabbitMQ.
+pub const DEFAULT_MAX_HEADER_BYTES: usize = 64 * 1024;
+
+/// Limits on the header table of a message, enforced by [`validate_headers_with`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeaderLimits {
+    /// The maximum number of headers.
+    pub max_count: usize,
+
+    /// The maximum estimated size of all headers in bytes, as computed by
+    /// [`estimated_size`].
+    pub max_bytes: usize,
+}
+
+impl Default for HeaderLimits {
+    /// Returns the [`DEFAULT_MAX_HEADER_COUNT`] and [`DEFAULT_MAX_HEADER_BYTES`] limits.
+    fn default() -> Self {
+        HeaderLimits {
+            max_count: DEFAULT_MAX_HEADER_COUNT,
+            max_bytes: DEFAULT_MAX_HEADER_BYTES,
+        }
+    }
+}
+
+/// Estimates the serialized size of a header map.
+///
+/// Every header counts the length of its key and value: strings and byte arrays count
+/// their length in bytes and integers their width. Protocol framing is ignored, so the
+/// estimate is below the actual wire size.
+///
+/// # Arguments
+///
+/// * `headers` - The headers to measure.
+///
+/// # Returns
+///
+/// The estimated size in bytes.
+pub fn estimated_size(headers: &HashMap<String, HeaderValues>) -> usize {
+    headers
+        .iter()
+        .map(|(key, value)| {
+            key.len()
+                + match value {
+                    HeaderValues::ShortString(v) | HeaderValues::LongString(v) => v.len(),
+                    HeaderValues::Bytes(v) => v.len(),
+                    HeaderValues::Int(_) | HeaderValues::Uint(_) => 1,
+                    HeaderValues::LongInt(_) | HeaderValues::LongUint(_) => 4,
+                    HeaderValues::LongLongInt(_) | HeaderValues::LongLongUint(_) => 8,
+                }
+        })
+        .sum()
+}
+
+/// Validates a header map against common broker restrictions, using the default
+/// [`HeaderLimits`].
+///
+/// See [`validate_headers_with`] for the checks performed.
+///
+/// # Arguments
+///
+/// * `headers` - The headers to validate.
+///
+/// # Returns
+///
+/// `Ok(())` if the headers are valid, or a `MessagingError::SerializingError` whose detail
+/// describes the violation.
+pub fn validate_headers(headers: &HashMap<String, HeaderValues>) -> Result<(), MessagingError> {
+    validate_headers_with(headers, &HeaderLimits::default())
+}
+
+/// Validates a header map against common broker restrictions.
+///
+/// A key is valid when it is not empty, is at most [`MAX_KEY_LENGTH`] bytes long and
+/// contains no control characters. The map is valid when every key is valid and it stays
+/// within the given limits. Keys are checked in sorted order, and the detail of the error
+/// names the first invalid key and the rule it breaks, or the exceeded limit with the
+/// actual count or size.
+///
+/// # Arguments
+///
+/// * `headers` - The headers to validate.
+/// * `limits` - The limits on the header count and size.
+///
+/// # Returns
+///
+/// `Ok(())` if the headers are valid, or a `MessagingError::SerializingError` whose detail
+/// describes the violation.
+pub fn validate_headers_with(
+    headers: &HashMap<String, HeaderValues>,
+    limits: &HeaderLimits,
+) -> Result<(), MessagingError> {
+    let mut keys: Vec<&String> = headers.keys().collect();
+    keys.sort();
+    for key in keys {
+        validate_key(key)?;
+    }
+
+    if headers.len() > limits.max_count {
+        return Err(MessagingError::serializing(format!(
+            "{} headers are more than the maximum of {}",
+            headers.len(),
+            limits.max_count
+        )));
+    }
+
+    let size = estimated_size(headers);
+    if size > limits.max_bytes {
+        return Err(MessagingError::serializing(format!(
+            "headers have an estimated size of {size} bytes, more than the maximum of {} bytes",
+            limits.max_bytes
+        )));
+    }
+
+    Ok(())
+}
+
+/// Checks a header key against the rules of [`validate_headers_with`].
+fn validate_key(key: &str) -> Result<(), MessagingError> {
+    if key.is_empty() {
+        return Err(MessagingError::serializing("header key is empty"));
+    }
+    if key.len() > MAX_KEY_LENGTH {
+        return Err(MessagingError::serializing(format!(
+            "header key `{key}` is {} bytes long, more than the maximum of {MAX_KEY_LENGTH}",
+            key.len()
+        )));
+    }
+    if key.chars().any(char::is_control) {
+        return Err(MessagingError::serializing(format!(
+            "header key {key:?} contains a control character"
+        )));
+    }
+    Ok(())
+}
+
+/// A builder for the header map of a `PublishMessage`.
+///
+/// Use [`Headers::into_option`] to pass the result directly to `PublishMessage::new`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Headers {
+    map: HashMap<String, HeaderValues>,
+}
+
+impl Headers {
+    /// Creates a new, empty header builder.
+    ///
+    /// # Returns
+    ///
+    /// A new `Headers` instance.
+    pub fn new() -> Self {
+        Headers::default()
+    }
+
+    /// Adds a header value, replacing any previous value for the key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The header key.
+    /// * `value` - The header value, or a value converting into one, e.g. `42u32`.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the header added.
+    pub fn value<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: Into<String>,
+        V: Into<HeaderValues>,
+    {
+        self.map.insert(key.into(), value.into());
+        self
+    }
+
+    /// Adds a string header, stored as `HeaderValues::LongString`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The header key.
+    /// * `value` - The header value.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the header added.
+    pub fn str<K, V>(self, key: K, value: V) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.value(key, HeaderValues::LongString(value.into()))
+    }
+
+    /// Adds an integer header, stored as `HeaderValues::LongLongInt`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The header key.
+    /// * `value` - The header value.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the header added.
+    pub fn int<K>(self, key: K, value: i64) -> Self
+    where
+        K: Into<String>,
+    {
+        self.value(key, HeaderValues::LongLongInt(value))
+    }
+
+    /// Builds the header map.
+    ///
+    /// # Returns
+    ///
+    /// The headers added to the builder.
+    pub fn build(self) -> HashMap<String, HeaderValues> {
+        self.map
+    }
+
+    /// Builds the header map in the form expected by `PublishMessage::new`.
+    ///
+    /// # Returns
+    ///
+    /// The headers added to the builder, or `None` if no header was added.
+    pub fn into_option(self) -> Option<HashMap<String, HeaderValues>> {
+        (!self.map.is_empty()).then_some(self.map)
+    }
+}
+
+impl From<Headers> for HashMap<String, HeaderValues> {
+    fn from(headers: Headers) -> Self {
+        headers.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(keys: &[&str]) -> HashMap<String, HeaderValues> {
+        keys.iter()
+            .map(|key| ((*key).to_owned(), HeaderValues::from("value")))
+            .collect()
+    }
+
+    fn detail(headers: &HashMap<String, HeaderValues>) -> String {
+        validate_headers(headers)
+            .unwrap_err()
+            .detail()
+            .unwrap()
+            .to_owned()
+    }
+
+    #[test]
+    fn accepts_valid_keys() {
+        let longest = "k".repeat(MAX_KEY_LENGTH);
+        assert_eq!(validate_headers(&headers(&["x-tenant", &longest])), Ok(()));
+        assert_eq!(validate_headers(&HashMap::new()), Ok(()));
+    }
+
+    #[test]
+    fn rejects_empty_keys() {
+        assert_eq!(detail(&headers(&["x-tenant", ""])), "header key is empty");
+    }
+
+    #[test]
+    fn rejects_oversized_keys() {
+        let oversized = "k".repeat(MAX_KEY_LENGTH + 1);
+
+        assert_eq!(
+            detail(&headers(&[&oversized])),
+            format!("header key `{oversized}` is 129 bytes long, more than the maximum of 128")
+        );
+    }
+
+    #[test]
+    fn rejects_control_characters() {
+        assert_eq!(
+            detail(&headers(&["x-tenant", "x-\nid"])),
+            r#"header key "x-\nid" contains a control character"#
+        );
+    }
+
+    #[test]
+    fn displays_the_detail() {
+        let err = validate_headers(&headers(&[""])).unwrap_err();
+
+        assert_eq!(err.to_string(), "serializing error: header key is empty");
+        assert_eq!(
+            MessagingError::SerializingError { detail: None }.to_string(),
+            "serializing error"
+        );
+    }
+
+    #[test]
+    fn rejects_maps_over_the_count_limit() {
+        let limits = HeaderLimits {
+            max_count: 2,
+            ..HeaderLimits::default()
+        };
+
+        assert_eq!(
+            validate_headers_with(&headers(&["a", "b"]), &limits),
+            Ok(())
+        );
+        assert_eq!(
+            validate_headers_with(&headers(&["a", "b", "c"]), &limits),
+            Err(MessagingError::serializing(
+                "3 headers are more than the maximum of 2"
+            ))
+        );
+    }
+
+    #[test]
+    fn rejects_maps_over_the_byte_limit() {
+        let limits = HeaderLimits {
+            max_bytes: 10,
+            ..HeaderLimits::default()
+        };
+
+        assert_eq!(estimated_size(&headers(&["a"])), 6);
+        assert_eq!(validate_headers_with(&headers(&["a"]), &limits), Ok(()));
+        assert_eq!(
+            validate_headers_with(&headers(&["a", "b"]), &limits),
+            Err(MessagingError::serializing(
+                "headers have an estimated size of 12 bytes, more than the maximum of 10 bytes"
+            ))
+        );
+    }
+
+    #[test]
+    fn estimates_byte_arrays_by_their_length() {
+        let headers = HashMap::from([("token".to_owned(), HeaderValues::Bytes(vec![0xff; 16]))]);
+
+        assert_eq!(estimated_size(&headers), 21);
+    }
+
+    #[test]
+    fn default_limits_match_common_brokers() {
+        let many: Vec<String> = (0..=DEFAULT_MAX_HEADER_COUNT)
+            .map(|n| n.to_string())
+            .collect();
+        let many: Vec<&str> = many.iter().map(String::as_str).collect();
+
+        assert!(validate_headers(&headers(&many[1..])).is_ok());
+        assert!(validate_headers(&headers(&many)).is_err());
+    }
+
+    #[test]
+    fn builds_typed_header_maps() {
+        let map = Headers::new()
+            .str("tenant", "acme")
+            .int("attempt", -2)
+            .value("version", 3u32)
+            .build();
+
+        assert_eq!(
+            map,
+            HashMap::from([
+                (
+                    "tenant".to_owned(),
+                    HeaderValues::LongString("acme".to_owned())
+                ),
+                ("attempt".to_owned(), HeaderValues::LongLongInt(-2)),
+                ("version".to_owned(), HeaderValues::LongUint(3)),
+            ])
+        );
+    }
+
+    #[test]
+    fn later_values_replace_earlier_ones() {
+        let map: HashMap<_, _> = Headers::new().int("attempt", 1).int("attempt", 2).into();
+
+        assert_eq!(map["attempt"], HeaderValues::LongLongInt(2));
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn into_option_is_none_without_headers() {
+        assert_eq!(Headers::new().into_option(), None);
+        assert_eq!(
+            Headers::new()
+                .str("tenant", "acme")
+                .into_option()
+                .map(|map| map.len()),
+            Some(1)
+        );
+    }
+}
@@ -16,9 +16,41 @@
 //! - [`dispatcher`]: Message consumption and handler registration.
 //! - [`publisher`]: Message publishing capabilities.
 //! - [`handler`]: Consumer handler traits and message structures.
+//! - [`codec`]: Encoding of typed values into message data.
+//! - [`typed`]: Typed publishing and handling helpers.
+//! - [`destination`]: Typed message destinations.
+//! - [`headers`]: Names of the reserved message headers.
+//! - [`partition`]: Deterministic partition selection from message keys.
+//! - [`propagation`]: Propagation of OpenTelemetry baggage through message headers.
+//! - [`middleware`]: Composable wrappers around consumer handlers and publishers.
+//! - [`noop`]: Null implementations for deployments with messaging disabled.
+//! - [`rpc`]: Request-response calls over a publisher and a dispatcher.
+//! - [`bridge`]: Relaying of messages from a dispatcher to a publisher.
 //! - [`errors`]: Error types specific to messaging operations.
+//! - [`backoff`]: Exponential backoff between retries.
+//! - [`spawn`]: Runtime-independent task spawning used by background helpers.
+//! - [`timer`]: Runtime-independent timer abstraction used by time-based wrappers.
+//! - `testing`: In-process implementations for tests (requires the `test-util` feature, or
+//!   `mocks` for the `TestDispatcher` only).
 
+pub mod backoff;
+pub mod bridge;
+pub mod codec;
+pub mod destination;
 pub mod dispatcher;
 pub mod errors;
 pub mod handler;
+pub mod headers;
+pub mod middleware;
+pub mod noop;
+pub mod partition;
+pub mod propagation;
 pub mod publisher;
+pub mod rpc;
+pub mod spawn;
+#[cfg(test)]
+mod test_support;
+#[cfg(any(feature = "test-util", feature = "mocks"))]
+pub mod testing;
+pub mod timer;
+pub mod typed;
@@ -0,0 +1,314 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Batch
+//!
+//! This module provides an adapter that groups consumed messages into batches.
+//!
+//! The [`BatchingHandler`] is registered like any other handler. It accumulates the
+//! messages delivered to it and hands them to a [`BatchConsumerHandler`] once a batch is
+//! full or its time window elapsed.
+//!
+//! Batching is an adapter rather than a dispatcher option, so that it works unchanged with
+//! every dispatcher implementation: the dispatcher only needs to deliver messages
+//! concurrently for batches to fill up.
+
+use crate::{
+    errors::MessagingError,
+    handler::{BatchConsumerHandler, ConsumerHandler, ConsumerMessage},
+    timer::{self, Timer},
+};
+use async_trait::async_trait;
+use opentelemetry::Context;
+use std::{
+    future::poll_fn,
+    mem,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    task::{Poll, Waker},
+    time::Duration,
+};
+
+/// A consumer handler that accumulates messages and flushes them to a batch handler.
+///
+/// A batch is flushed as soon as it holds `max_size` messages, or `max_wait` after its
+/// first message arrived, whichever comes first. Each `exec` call completes once the batch
+/// containing its message has been handled, and returns the result of the whole batch:
+/// if the batch fails, every message in it fails and is dispositioned accordingly by the
+/// dispatcher.
+///
+/// Batches can only grow beyond one message when the dispatcher delivers messages
+/// concurrently, e.g. with a `max_concurrency` of at least `max_size`.
+///
+/// The batch is handled by the `exec` call that filled it or, once the time window
+/// elapsed, by the first call to notice. If that call is cancelled before the batch handler
+/// completes, every other message of the batch fails with a `MessagingError::ConsumerError`
+/// with the code `cancelled` instead of waiting forever.
+pub struct BatchingHandler {
+    inner: Arc<dyn BatchConsumerHandler>,
+    max_size: usize,
+    max_wait: Duration,
+    timer: Arc<dyn Timer>,
+    pending: Mutex<PendingBatch>,
+}
+
+#[derive(Default)]
+struct PendingBatch {
+    msgs: Vec<ConsumerMessage>,
+    outcome: Arc<BatchOutcome>,
+}
+
+#[derive(Default)]
+struct BatchOutcome {
+    state: Mutex<OutcomeState>,
+}
+
+#[derive(Default)]
+struct OutcomeState {
+    result: Option<Result<(), MessagingError>>,
+    waiters: Vec<Waker>,
+}
+
+/// Fails the outcome of a batch if its flush is dropped before completing.
+struct CancelGuard<'a> {
+    outcome: Option<&'a BatchOutcome>,
+}
+
+impl Drop for CancelGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(outcome) = self.outcome {
+            outcome.complete(Err(MessagingError::consumer_with_code(
+                "batch flush was cancelled",
+                "cancelled",
+            )));
+        }
+    }
+}
+
+impl BatchOutcome {
+    fn complete(&self, result: Result<(), MessagingError>) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.result = Some(result);
+        state.waiters.drain(..).for_each(Waker::wake);
+    }
+
+    async fn wait(&self) -> Result<(), MessagingError> {
+        poll_fn(|cx| {
+            let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+            match &state.result {
+                Some(result) => Poll::Ready(result.clone()),
+                None => {
+                    state.waiters.push(cx.waker().clone());
+                    Poll::Pending
+                }
+            }
+        })
+        .await
+    }
+}
+
+impl BatchingHandler {
+    /// Creates a new batching handler.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The batch handler to flush batches to.
+    /// * `max_size` - The maximum number of messages per batch (at least 1).
+    /// * `max_wait` - The maximum time a message waits for its batch to fill up.
+    /// * `timer` - The timer used to measure the time window.
+    ///
+    /// # Returns
+    ///
+    /// A new `BatchingHandler` instance.
+    pub fn new(
+        inner: Arc<dyn BatchConsumerHandler>,
+        max_size: usize,
+        max_wait: Duration,
+        timer: Arc<dyn Timer>,
+    ) -> Self {
+        BatchingHandler {
+            inner,
+            max_size: max_size.max(1),
+            max_wait,
+            timer,
+            pending: Mutex::new(PendingBatch::default()),
+        }
+    }
+
+    fn pending(&self) -> MutexGuard<'_, PendingBatch> {
+        self.pending.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    async fn flush(
+        &self,
+        ctx: &Context,
+        msgs: Vec<ConsumerMessage>,
+        outcome: &BatchOutcome,
+    ) -> Result<(), MessagingError> {
+        let mut guard = CancelGuard {
+            outcome: Some(outcome),
+        };
+        let result = self.inner.exec_batch(ctx, &msgs).await;
+
+        guard.outcome = None;
+        outcome.complete(result.clone());
+        result
+    }
+}
+
+#[async_trait]
+impl ConsumerHandler for BatchingHandler {
+    async fn exec(&self, ctx: &Context, msg: &ConsumerMessage) -> Result<(), MessagingError> {
+        let (outcome, full) = {
+            let mut pending = self.pending();
+            pending.msgs.push(msg.clone());
+            let outcome = pending.outcome.clone();
+
+            if pending.msgs.len() >= self.max_size {
+                (outcome, Some(mem::take(&mut *pending).msgs))
+            } else {
+                (outcome, None)
+            }
+        };
+
+        if let Some(msgs) = full {
+            return self.flush(ctx, msgs, &outcome).await;
+        }
+
+        if let Some(result) = timer::timeout(&*self.timer, self.max_wait, outcome.wait()).await {
+            return result;
+        }
+
+        let expired = {
+            let mut pending = self.pending();
+            Arc::ptr_eq(&pending.outcome, &outcome).then(|| mem::take(&mut *pending).msgs)
+        };
+
+        match expired {
+            Some(msgs) => self.flush(ctx, msgs, &outcome).await,
+            None => outcome.wait().await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_support::{NeverTimer, block_on, join_all, poll_once},
+        timer::ThreadTimer,
+    };
+    use std::{future::pending, pin::pin};
+
+    #[derive(Default)]
+    struct RecordingBatchHandler {
+        batches: Mutex<Vec<Vec<String>>>,
+        failure: Option<MessagingError>,
+        hang: bool,
+    }
+
+    #[async_trait]
+    impl BatchConsumerHandler for RecordingBatchHandler {
+        async fn exec_batch(
+            &self,
+            _ctx: &Context,
+            msgs: &[ConsumerMessage],
+        ) -> Result<(), MessagingError> {
+            if self.hang {
+                pending::<()>().await;
+            }
+
+            self.batches
+                .lock()
+                .unwrap()
+                .push(msgs.iter().map(|msg| msg.data_string().unwrap()).collect());
+            self.failure.clone().map_or(Ok(()), Err)
+        }
+    }
+
+    fn message(data: &str) -> ConsumerMessage {
+        ConsumerMessage::new("orders", "event", data.as_bytes(), None)
+    }
+
+    fn batching(
+        inner: &Arc<RecordingBatchHandler>,
+        max_size: usize,
+        max_wait: Duration,
+        timer: Arc<dyn Timer>,
+    ) -> BatchingHandler {
+        BatchingHandler::new(inner.clone(), max_size, max_wait, timer)
+    }
+
+    #[test]
+    fn groups_messages_by_count() {
+        let inner = Arc::new(RecordingBatchHandler::default());
+        let handler = batching(&inner, 2, Duration::from_secs(60), Arc::new(NeverTimer));
+        let ctx = Context::new();
+        let msgs: Vec<_> = ["1", "2", "3", "4"].map(message).into();
+
+        let results = block_on(join_all(
+            msgs.iter().map(|msg| handler.exec(&ctx, msg)).collect(),
+        ));
+
+        assert!(results.iter().all(Result::is_ok));
+        assert_eq!(*inner.batches.lock().unwrap(), [["1", "2"], ["3", "4"]]);
+    }
+
+    #[test]
+    fn flushes_a_partial_batch_after_the_time_window() {
+        let inner = Arc::new(RecordingBatchHandler::default());
+        let handler = batching(&inner, 10, Duration::from_millis(10), Arc::new(ThreadTimer));
+
+        assert_eq!(
+            block_on(handler.exec(&Context::new(), &message("1"))),
+            Ok(())
+        );
+        assert_eq!(*inner.batches.lock().unwrap(), [["1"]]);
+    }
+
+    #[test]
+    fn batch_failure_fails_every_message() {
+        let inner = Arc::new(RecordingBatchHandler {
+            failure: Some(MessagingError::HandlerError),
+            ..RecordingBatchHandler::default()
+        });
+        let handler = batching(&inner, 2, Duration::from_secs(60), Arc::new(NeverTimer));
+        let ctx = Context::new();
+        let msgs = [message("1"), message("2")];
+
+        let results = block_on(join_all(
+            msgs.iter().map(|msg| handler.exec(&ctx, msg)).collect(),
+        ));
+
+        assert_eq!(
+            results,
+            [
+                Err(MessagingError::HandlerError),
+                Err(MessagingError::HandlerError)
+            ]
+        );
+    }
+
+    #[test]
+    fn cancelled_flush_fails_the_other_messages() {
+        let inner = Arc::new(RecordingBatchHandler {
+            hang: true,
+            ..RecordingBatchHandler::default()
+        });
+        let handler = batching(&inner, 2, Duration::from_secs(60), Arc::new(NeverTimer));
+        let ctx = Context::new();
+        let (first, second) = (message("1"), message("2"));
+
+        let mut waiting = pin!(handler.exec(&ctx, &first));
+        assert!(poll_once(waiting.as_mut()).is_pending());
+        {
+            let mut flushing = pin!(handler.exec(&ctx, &second));
+            assert!(poll_once(flushing.as_mut()).is_pending());
+        }
+
+        match poll_once(waiting.as_mut()) {
+            Poll::Ready(Err(err)) => assert_eq!(err.code(), Some("cancelled")),
+            other => panic!("expected the cancellation error, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,462 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Buffering
+//!
+//! This module provides a publisher middleware that batches messages locally.
+//!
+//! High-volume producers can hand messages to the [`BufferingPublisher`] cheaply and let it
+//! forward them to the inner publisher in bursts, once enough messages accumulated or the
+//! oldest buffered message waited long enough.
+
+use crate::{
+    errors::MessagingError,
+    publisher::{PublishMessage, PublishReceipt, Publisher, delegate_publisher},
+    spawn::Spawner,
+    timer::Timer,
+};
+use async_trait::async_trait;
+use opentelemetry::Context;
+use std::{
+    future::{Future, poll_fn},
+    mem,
+    pin::pin,
+    sync::{Arc, Mutex, MutexGuard, PoisonError, Weak},
+    task::{self, Poll, Waker},
+    time::{Duration, Instant},
+};
+
+/// A publisher middleware that buffers messages and publishes them in batches.
+///
+/// `publish` only appends the message to the buffer. The buffer is flushed to the inner
+/// publisher, in publish order, when it holds `max_size` messages or, if a maximum age is
+/// set, when a message is published after the oldest buffered one waited longer than that
+/// age. The age is only checked on `publish`, so a message buffered without a later publish
+/// waits for the next flush; [`BufferingPublisher::spawn_flush_interval`] starts a
+/// background task flushing the buffer on a fixed interval.
+///
+/// Flushes run one at a time: a flush started while another is in progress waits for it,
+/// so buffered messages reach the inner publisher in publish order even when several tasks
+/// publish or flush concurrently. If a flush is cancelled, the messages it did not publish
+/// yet go back to the front of the buffer.
+///
+/// If a flush triggered by `publish` fails, the unsent messages stay buffered and the
+/// error is returned by the next `publish`, which does not buffer its message, or by the
+/// next `flush`. `publish_confirmed` flushes the buffer first and then publishes directly,
+/// and `barrier` flushes the buffer before waiting on the inner publisher.
+///
+/// `disconnect` flushes the buffer before disconnecting the inner publisher. Dropping the
+/// publisher without disconnecting makes a best-effort attempt to flush the remaining
+/// messages: the flush is polled once and abandoned if the inner publisher cannot complete
+/// immediately, losing the messages. Call `flush` or `disconnect` before dropping to avoid
+/// that.
+pub struct BufferingPublisher {
+    inner: Arc<dyn Publisher>,
+    max_size: usize,
+    max_age: Option<Duration>,
+    state: Mutex<BufferState>,
+}
+
+#[derive(Default)]
+struct BufferState {
+    msgs: Vec<PublishMessage>,
+    oldest: Option<Instant>,
+    error: Option<MessagingError>,
+    flushing: bool,
+    flush_waiters: Vec<Waker>,
+}
+
+/// A flush in progress, returning the unpublished messages to the buffer and letting the
+/// next flush start when dropped.
+struct Flushing<'a> {
+    publisher: &'a BufferingPublisher,
+    msgs: Vec<PublishMessage>,
+    sent: usize,
+}
+
+impl Drop for Flushing<'_> {
+    fn drop(&mut self) {
+        let mut state = self.publisher.state();
+        if self.sent < self.msgs.len() {
+            let newer = mem::replace(&mut state.msgs, self.msgs.split_off(self.sent));
+            state.msgs.extend(newer);
+            state.oldest.get_or_insert_with(Instant::now);
+        }
+
+        state.flushing = false;
+        state.flush_waiters.drain(..).for_each(Waker::wake);
+    }
+}
+
+impl BufferingPublisher {
+    /// Creates a new buffering publisher.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The publisher to flush buffered messages to.
+    /// * `max_size` - The number of buffered messages that triggers a flush (at least 1).
+    ///
+    /// # Returns
+    ///
+    /// A new `BufferingPublisher` instance.
+    pub fn new(inner: Arc<dyn Publisher>, max_size: usize) -> Self {
+        BufferingPublisher {
+            inner,
+            max_size: max_size.max(1),
+            max_age: None,
+            state: Mutex::new(BufferState::default()),
+        }
+    }
+
+    /// Sets the age of the oldest buffered message that triggers a flush on `publish`.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_age` - The maximum time a message waits in the buffer before the next
+    ///   `publish` flushes it.
+    ///
+    /// # Returns
+    ///
+    /// The updated `BufferingPublisher`.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Returns the number of messages waiting in the buffer.
+    pub fn buffered(&self) -> usize {
+        self.state().msgs.len()
+    }
+
+    /// Publishes all buffered messages to the inner publisher.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The OpenTelemetry context for tracing and monitoring.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or containing the first publish error. On error, the
+    /// messages not yet published stay buffered.
+    pub async fn flush(&self, ctx: &Context) -> Result<(), MessagingError> {
+        self.state().error = None;
+        self.flush_buffer(ctx).await
+    }
+
+    /// Starts flushing the buffer on a fixed interval in the background.
+    ///
+    /// The task sleeps for `interval` on the timer, flushes the buffered messages, if any,
+    /// and repeats until the publisher is dropped. A failed flush is handled like a flush
+    /// triggered by `publish`: the unsent messages stay buffered and the error is returned
+    /// by the next `publish` or `flush`.
+    ///
+    /// # Arguments
+    ///
+    /// * `spawner` - The spawner running the flush task.
+    /// * `timer` - The timer used to wait between flushes.
+    /// * `interval` - The time between two flushes.
+    pub fn spawn_flush_interval(
+        self: &Arc<Self>,
+        spawner: &dyn Spawner,
+        timer: Arc<dyn Timer>,
+        interval: Duration,
+    ) {
+        let publisher = Arc::downgrade(self);
+        spawner.spawn(Box::pin(async move {
+            loop {
+                timer.sleep(interval).await;
+                let Some(publisher) = Weak::upgrade(&publisher) else {
+                    return;
+                };
+
+                if publisher.buffered() > 0
+                    && let Err(err) = publisher.flush_buffer(&Context::current()).await
+                {
+                    publisher.state().error = Some(err);
+                }
+            }
+        }));
+    }
+
+    fn state(&self) -> MutexGuard<'_, BufferState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Waits for the flush in progress, if any, then takes the buffered messages.
+    async fn start_flush(&self) -> Flushing<'_> {
+        let msgs = poll_fn(|cx| {
+            let mut state = self.state();
+            if state.flushing {
+                state.flush_waiters.push(cx.waker().clone());
+                return Poll::Pending;
+            }
+
+            state.flushing = true;
+            state.oldest = None;
+            Poll::Ready(mem::take(&mut state.msgs))
+        })
+        .await;
+
+        Flushing {
+            publisher: self,
+            msgs,
+            sent: 0,
+        }
+    }
+
+    async fn flush_buffer(&self, ctx: &Context) -> Result<(), MessagingError> {
+        let mut flushing = self.start_flush().await;
+
+        while let Some(msg) = flushing.msgs.get(flushing.sent) {
+            self.inner.publish(ctx, msg).await?;
+            flushing.sent += 1;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Publisher for BufferingPublisher {
+    async fn publish(&self, ctx: &Context, msg: &PublishMessage) -> Result<(), MessagingError> {
+        let due = {
+            let mut state = self.state();
+            if let Some(err) = state.error.take() {
+                return Err(err);
+            }
+
+            state.msgs.push(msg.clone());
+            let oldest = *state.oldest.get_or_insert_with(Instant::now);
+            state.msgs.len() >= self.max_size
+                || self
+                    .max_age
+                    .is_some_and(|max_age| oldest.elapsed() >= max_age)
+        };
+
+        if due && let Err(err) = self.flush_buffer(ctx).await {
+            self.state().error = Some(err);
+        }
+
+        Ok(())
+    }
+
+    async fn barrier(&self) -> Result<(), MessagingError> {
+        self.flush(&Context::current()).await?;
+        self.inner.barrier().await
+    }
+
+    async fn publish_confirmed(
+        &self,
+        ctx: &Context,
+        msg: &PublishMessage,
+    ) -> Result<PublishReceipt, MessagingError> {
+        self.flush(ctx).await?;
+        self.inner.publish_confirmed(ctx, msg).await
+    }
+
+    async fn disconnect(&self) -> Result<(), MessagingError> {
+        self.flush(&Context::current()).await?;
+        self.inner.disconnect().await
+    }
+
+    delegate_publisher!(inner, except = [barrier, disconnect]);
+}
+
+impl Drop for BufferingPublisher {
+    fn drop(&mut self) {
+        if self.state().msgs.is_empty() {
+            return;
+        }
+
+        let ctx = Context::current();
+        let flush = pin!(self.flush_buffer(&ctx));
+        let _ = flush.poll(&mut task::Context::from_waker(task::Waker::noop()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        spawn::BackgroundTask,
+        test_support::{RecordingTimer, TestPublisher, block_on, message, poll_once},
+    };
+
+    fn buffering(inner: &Arc<TestPublisher>, max_size: usize) -> BufferingPublisher {
+        BufferingPublisher::new(inner.clone(), max_size)
+    }
+
+    #[test]
+    fn flushes_in_publish_order_once_full() {
+        let inner = Arc::new(TestPublisher::default());
+        let publisher = buffering(&inner, 3);
+        let ctx = Context::new();
+
+        for data in ["1", "2"] {
+            block_on(publisher.publish(&ctx, &message("orders", data))).unwrap();
+        }
+        assert!(inner.published().is_empty());
+        assert_eq!(publisher.buffered(), 2);
+
+        block_on(publisher.publish(&ctx, &message("orders", "3"))).unwrap();
+        assert_eq!(inner.published_data(), ["1", "2", "3"]);
+        assert_eq!(publisher.buffered(), 0);
+    }
+
+    #[test]
+    fn concurrent_flushes_keep_publish_order() {
+        let inner = Arc::new(TestPublisher::held());
+        let publisher = buffering(&inner, 100);
+        let ctx = Context::new();
+
+        for data in ["1", "2"] {
+            block_on(publisher.publish(&ctx, &message("orders", data))).unwrap();
+        }
+        let mut first = pin!(publisher.flush(&ctx));
+        assert!(poll_once(first.as_mut()).is_pending());
+
+        block_on(publisher.publish(&ctx, &message("orders", "3"))).unwrap();
+        let mut second = pin!(publisher.flush(&ctx));
+        assert!(poll_once(second.as_mut()).is_pending());
+
+        inner.release();
+        assert!(poll_once(second.as_mut()).is_pending());
+        assert_eq!(poll_once(first.as_mut()), Poll::Ready(Ok(())));
+        assert_eq!(poll_once(second.as_mut()), Poll::Ready(Ok(())));
+        assert_eq!(inner.published_data(), ["1", "2", "3"]);
+    }
+
+    #[test]
+    fn cancelled_flush_returns_messages_to_the_buffer() {
+        let inner = Arc::new(TestPublisher::held());
+        let publisher = buffering(&inner, 100);
+        let ctx = Context::new();
+
+        for data in ["1", "2"] {
+            block_on(publisher.publish(&ctx, &message("orders", data))).unwrap();
+        }
+        {
+            let mut flush = pin!(publisher.flush(&ctx));
+            assert!(poll_once(flush.as_mut()).is_pending());
+            block_on(publisher.publish(&ctx, &message("orders", "3"))).unwrap();
+        }
+        assert_eq!(publisher.buffered(), 3);
+
+        inner.release();
+        block_on(publisher.flush(&ctx)).unwrap();
+        assert_eq!(inner.published_data(), ["1", "2", "3"]);
+    }
+
+    #[test]
+    fn failed_flush_keeps_unsent_messages_and_reports_on_next_publish() {
+        let inner = Arc::new(TestPublisher::default());
+        let publisher = buffering(&inner, 2);
+        let ctx = Context::new();
+
+        inner.fail_with(Some(MessagingError::PublisherError { detail: None }));
+        block_on(publisher.publish(&ctx, &message("orders", "1"))).unwrap();
+        block_on(publisher.publish(&ctx, &message("orders", "2"))).unwrap();
+        assert_eq!(publisher.buffered(), 2);
+
+        assert_eq!(
+            block_on(publisher.publish(&ctx, &message("orders", "3"))),
+            Err(MessagingError::PublisherError { detail: None })
+        );
+        assert_eq!(publisher.buffered(), 2);
+
+        inner.fail_with(None);
+        block_on(publisher.flush(&ctx)).unwrap();
+        assert_eq!(inner.published_data(), ["1", "2"]);
+    }
+
+    #[test]
+    fn flushes_on_publish_after_max_age() {
+        let inner = Arc::new(TestPublisher::default());
+        let publisher = buffering(&inner, 100).with_max_age(Duration::ZERO);
+
+        block_on(publisher.publish(&Context::new(), &message("orders", "1"))).unwrap();
+        assert_eq!(inner.published_data(), ["1"]);
+    }
+
+    #[test]
+    fn drop_flushes_remaining_messages() {
+        let inner = Arc::new(TestPublisher::default());
+        let publisher = buffering(&inner, 100);
+
+        block_on(publisher.publish(&Context::new(), &message("orders", "1"))).unwrap();
+        drop(publisher);
+        assert_eq!(inner.published_data(), ["1"]);
+    }
+
+    /// A spawner keeping the spawned tasks for the test to poll.
+    #[derive(Default)]
+    struct CapturingSpawner {
+        tasks: Mutex<Vec<BackgroundTask>>,
+    }
+
+    impl CapturingSpawner {
+        fn task(&self) -> BackgroundTask {
+            self.tasks.lock().unwrap().pop().unwrap()
+        }
+    }
+
+    impl Spawner for CapturingSpawner {
+        fn spawn(&self, task: BackgroundTask) {
+            self.tasks.lock().unwrap().push(task);
+        }
+    }
+
+    #[test]
+    fn flush_interval_flushes_without_a_later_publish() {
+        let inner = Arc::new(TestPublisher::default());
+        let publisher = Arc::new(buffering(&inner, 100));
+        let spawner = CapturingSpawner::default();
+        let timer = Arc::new(RecordingTimer::completing(2));
+
+        publisher.spawn_flush_interval(&spawner, timer.clone(), Duration::from_secs(5));
+        block_on(publisher.publish(&Context::new(), &message("orders", "1"))).unwrap();
+        let mut task = spawner.task();
+
+        assert!(poll_once(task.as_mut()).is_pending());
+        assert_eq!(inner.published_data(), ["1"]);
+        assert_eq!(publisher.buffered(), 0);
+        assert_eq!(timer.sleeps(), [Duration::from_secs(5); 3]);
+    }
+
+    #[test]
+    fn flush_interval_keeps_failures_for_the_next_publish() {
+        let inner = Arc::new(TestPublisher::default());
+        let publisher = Arc::new(buffering(&inner, 100));
+        let spawner = CapturingSpawner::default();
+
+        publisher.spawn_flush_interval(
+            &spawner,
+            Arc::new(RecordingTimer::completing(1)),
+            Duration::from_secs(5),
+        );
+        block_on(publisher.publish(&Context::new(), &message("orders", "1"))).unwrap();
+        inner.fail_with(Some(MessagingError::ConnectionError));
+        assert!(poll_once(spawner.task().as_mut()).is_pending());
+
+        assert_eq!(publisher.buffered(), 1);
+        assert_eq!(
+            block_on(publisher.publish(&Context::new(), &message("orders", "2"))),
+            Err(MessagingError::ConnectionError)
+        );
+    }
+
+    #[test]
+    fn flush_interval_stops_once_the_publisher_is_dropped() {
+        let inner = Arc::new(TestPublisher::default());
+        let publisher = Arc::new(buffering(&inner, 100));
+        let spawner = CapturingSpawner::default();
+        let timer = Arc::new(RecordingTimer::default());
+
+        publisher.spawn_flush_interval(&spawner, timer.clone(), Duration::from_secs(5));
+        drop(publisher);
+
+        assert_eq!(poll_once(spawner.task().as_mut()), Poll::Ready(()));
+        assert_eq!(timer.sleeps().len(), 1);
+    }
+}
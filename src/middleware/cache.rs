@@ -0,0 +1,224 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Cache
+//!
+//! This module provides the bounded cache keyed by message id shared by the middleware
+//! that remember messages, such as deduplication and delivery counting.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    time::{Duration, Instant},
+};
+
+/// A cache of values keyed by message id, bounded in size and in age.
+///
+/// Entries are kept in least-recently-used order: reading or replacing an entry makes it
+/// the most recent one. Entries not used for `ttl` expire, and once `capacity` entries
+/// are cached, inserting a new one evicts the least recently used.
+///
+/// The use order is a queue of `(key, generation)` pairs, where only the pair carrying the
+/// current generation of an entry is live. Touched and removed entries leave stale pairs
+/// behind, which are skipped when popped and compacted away once they outnumber the live
+/// ones, so the queue stays proportional to the number of entries.
+pub(crate) struct BoundedCache<V> {
+    capacity: usize,
+    ttl: Duration,
+    entries: HashMap<String, Entry<V>>,
+    order: VecDeque<(String, u64)>,
+    generation: u64,
+}
+
+struct Entry<V> {
+    value: V,
+    used: Instant,
+    generation: u64,
+}
+
+impl<V> BoundedCache<V> {
+    /// Creates an empty cache holding at most `capacity` entries (at least 1).
+    pub(crate) fn new(capacity: usize, ttl: Duration) -> Self {
+        BoundedCache {
+            capacity: capacity.max(1),
+            ttl,
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+            generation: 0,
+        }
+    }
+
+    /// Returns the value of a key that has not expired, marking it as recently used.
+    pub(crate) fn get_mut(&mut self, key: &str, now: Instant) -> Option<&mut V> {
+        self.expire(now);
+        if !self.entries.contains_key(key) {
+            return None;
+        }
+
+        let generation = self.next_generation(key);
+        if let Some(entry) = self.entries.get_mut(key) {
+            entry.used = now;
+            entry.generation = generation;
+        }
+        self.compact();
+        self.entries.get_mut(key).map(|entry| &mut entry.value)
+    }
+
+    /// Inserts or replaces the value of a key, evicting the least recently used entry if
+    /// the cache is full.
+    pub(crate) fn insert(&mut self, key: &str, value: V, now: Instant) {
+        self.expire(now);
+        if !self.entries.contains_key(key) {
+            while self.entries.len() >= self.capacity && !self.order.is_empty() {
+                self.pop_oldest();
+            }
+        }
+
+        let generation = self.next_generation(key);
+        self.entries.insert(
+            key.to_owned(),
+            Entry {
+                value,
+                used: now,
+                generation,
+            },
+        );
+        self.compact();
+    }
+
+    /// Removes a key, returning its value if it was cached.
+    pub(crate) fn remove(&mut self, key: &str) -> Option<V> {
+        let entry = self.entries.remove(key)?;
+        self.compact();
+        Some(entry.value)
+    }
+
+    /// Returns the number of cached entries, including expired ones not yet dropped.
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns the number of pairs in the use order, live or stale.
+    #[cfg(test)]
+    fn order_len(&self) -> usize {
+        self.order.len()
+    }
+
+    /// Appends a new live pair for a key to the use order and returns its generation.
+    fn next_generation(&mut self, key: &str) -> u64 {
+        self.generation += 1;
+        self.order.push_back((key.to_owned(), self.generation));
+        self.generation
+    }
+
+    /// Drops the entries not used for the time to live.
+    fn expire(&mut self, now: Instant) {
+        while let Some((key, generation)) = self.order.front() {
+            let live = self
+                .entries
+                .get(key)
+                .filter(|entry| entry.generation == *generation);
+            if live.is_some_and(|entry| now.duration_since(entry.used) < self.ttl) {
+                break;
+            }
+            self.pop_oldest();
+        }
+    }
+
+    /// Pops the oldest pair of the use order, removing its entry if the pair is live.
+    fn pop_oldest(&mut self) {
+        if let Some((key, generation)) = self.order.pop_front()
+            && self
+                .entries
+                .get(&key)
+                .is_some_and(|entry| entry.generation == generation)
+        {
+            self.entries.remove(&key);
+        }
+    }
+
+    /// Drops the stale pairs once they outnumber the live ones.
+    fn compact(&mut self) {
+        if self.order.len() <= 2 * self.entries.len() + 16 {
+            return;
+        }
+
+        let entries = &self.entries;
+        self.order.retain(|(key, generation)| {
+            entries
+                .get(key)
+                .is_some_and(|entry| entry.generation == *generation)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: Duration = Duration::from_secs(3600);
+
+    #[test]
+    fn evicts_the_least_recently_used_entry() {
+        let mut cache = BoundedCache::new(2, HOUR);
+        let now = Instant::now();
+
+        cache.insert("a", 1, now);
+        cache.insert("b", 2, now);
+        assert_eq!(cache.get_mut("a", now), Some(&mut 1));
+        cache.insert("c", 3, now);
+
+        assert_eq!(cache.get_mut("a", now), Some(&mut 1));
+        assert_eq!(cache.get_mut("b", now), None);
+        assert_eq!(cache.get_mut("c", now), Some(&mut 3));
+    }
+
+    #[test]
+    fn expires_entries_by_time_since_last_use() {
+        let mut cache = BoundedCache::new(10, Duration::from_secs(10));
+        let start = Instant::now();
+
+        cache.insert("a", (), start);
+        cache.insert("b", (), start);
+        assert!(cache.get_mut("a", start + Duration::from_secs(8)).is_some());
+
+        let later = start + Duration::from_secs(12);
+        assert!(cache.get_mut("a", later).is_some());
+        assert!(cache.get_mut("b", later).is_none());
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn removed_entries_do_not_evict_live_ones() {
+        let mut cache = BoundedCache::new(2, HOUR);
+        let now = Instant::now();
+
+        cache.insert("a", (), now);
+        cache.insert("b", (), now);
+        cache.remove("a");
+        cache.insert("a", (), now);
+        cache.insert("c", (), now);
+
+        assert!(cache.get_mut("a", now).is_some());
+        assert!(cache.get_mut("b", now).is_none());
+        assert!(cache.get_mut("c", now).is_some());
+    }
+
+    #[test]
+    fn use_order_stays_bounded() {
+        let mut cache = BoundedCache::new(4, HOUR);
+        let now = Instant::now();
+
+        cache.insert("hot", 0, now);
+        for n in 0..10_000 {
+            cache.insert("retried", n, now);
+            cache.remove("retried");
+            *cache.get_mut("hot", now).unwrap() += 1;
+        }
+
+        assert_eq!(cache.len(), 1);
+        assert!(cache.order_len() <= 2 * cache.len() + 17);
+        assert_eq!(cache.get_mut("hot", now), Some(&mut 10_000));
+    }
+}
@@ -0,0 +1,134 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Chain
+//!
+//! This module provides a consumer handler trying several handlers in order.
+//!
+//! Handlers in a [`ChainHandler`] decline a message by returning
+//! `MessagingError::UnregisteredHandler`, letting the next handler in the chain try it.
+
+use crate::{
+    errors::MessagingError,
+    handler::{ConsumerHandler, ConsumerMessage},
+};
+use async_trait::async_trait;
+use opentelemetry::Context;
+use std::sync::Arc;
+
+/// A consumer handler invoking handlers in order until one accepts the message.
+///
+/// Each handler is invoked in turn. The chain short-circuits on the first result other
+/// than `MessagingError::UnregisteredHandler`: a success, or any other error, is returned
+/// immediately and the remaining handlers are not invoked. If every handler declines the
+/// message, or the chain is empty, `MessagingError::UnregisteredHandler` is returned.
+#[derive(Clone, Default)]
+pub struct ChainHandler {
+    handlers: Vec<Arc<dyn ConsumerHandler>>,
+}
+
+impl ChainHandler {
+    /// Creates a new, empty chain.
+    ///
+    /// # Returns
+    ///
+    /// A new `ChainHandler` instance.
+    pub fn new() -> Self {
+        ChainHandler::default()
+    }
+
+    /// Appends a handler to the chain.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - The handler to try after the ones already in the chain.
+    ///
+    /// # Returns
+    ///
+    /// The updated `ChainHandler`.
+    pub fn then(mut self, handler: Arc<dyn ConsumerHandler>) -> Self {
+        self.handlers.push(handler);
+        self
+    }
+}
+
+impl From<Vec<Arc<dyn ConsumerHandler>>> for ChainHandler {
+    /// Creates a chain trying the handlers in the order of the vector.
+    fn from(handlers: Vec<Arc<dyn ConsumerHandler>>) -> Self {
+        ChainHandler { handlers }
+    }
+}
+
+#[async_trait]
+impl ConsumerHandler for ChainHandler {
+    async fn exec(&self, ctx: &Context, msg: &ConsumerMessage) -> Result<(), MessagingError> {
+        for handler in &self.handlers {
+            match handler.exec(ctx, msg).await {
+                Err(MessagingError::UnregisteredHandler) => continue,
+                result => return result,
+            }
+        }
+
+        Err(MessagingError::UnregisteredHandler)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{TestHandler, block_on, delivered, message};
+
+    fn declining() -> Arc<TestHandler> {
+        Arc::new(TestHandler::failing(MessagingError::UnregisteredHandler))
+    }
+
+    fn exec(chain: &ChainHandler) -> Result<(), MessagingError> {
+        let msg = delivered(&message("orders", "created"));
+        block_on(chain.exec(&Context::new(), &msg))
+    }
+
+    #[test]
+    fn tries_handlers_until_one_accepts() {
+        let (first, second, third) = (declining(), Arc::new(TestHandler::default()), declining());
+        let chain = ChainHandler::new()
+            .then(first.clone())
+            .then(second.clone())
+            .then(third.clone());
+
+        assert_eq!(exec(&chain), Ok(()));
+        assert_eq!(first.received().len(), 1);
+        assert_eq!(second.received().len(), 1);
+        assert!(third.received().is_empty());
+    }
+
+    #[test]
+    fn stops_at_the_first_other_error() {
+        let failing = Arc::new(TestHandler::failing(MessagingError::HandlerError));
+        let accepting = Arc::new(TestHandler::default());
+        let chain = ChainHandler::from(vec![
+            declining() as Arc<dyn ConsumerHandler>,
+            failing.clone(),
+            accepting.clone(),
+        ]);
+
+        assert_eq!(exec(&chain), Err(MessagingError::HandlerError));
+        assert_eq!(failing.received().len(), 1);
+        assert!(accepting.received().is_empty());
+    }
+
+    #[test]
+    fn declines_when_every_handler_declines() {
+        let chain = ChainHandler::new().then(declining()).then(declining());
+
+        assert_eq!(exec(&chain), Err(MessagingError::UnregisteredHandler));
+    }
+
+    #[test]
+    fn empty_chain_declines() {
+        assert_eq!(
+            exec(&ChainHandler::new()),
+            Err(MessagingError::UnregisteredHandler)
+        );
+    }
+}
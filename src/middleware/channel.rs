@@ -0,0 +1,387 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Channel
+//!
+//! This module provides a dispatcher wrapper that hands consumed messages to a channel.
+//!
+//! Applications running their own processing loop can wrap a dispatcher in a
+//! [`ChannelDispatcher`] and pull the messages of a subscription from a
+//! [`MessageReceiver`] instead of registering a handler. The channel is bounded and does
+//! not depend on an async runtime.
+
+use crate::{
+    dispatcher::{Dispatcher, DispatcherDefinition, DispatcherStats, SeekPosition},
+    errors::MessagingError,
+    handler::{ConsumerHandler, ConsumerMessage},
+};
+use async_trait::async_trait;
+use opentelemetry::Context;
+use std::{
+    collections::VecDeque,
+    future::poll_fn,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    task::{Poll, Waker},
+    time::Duration,
+};
+
+/// A dispatcher wrapper forwarding the messages of a subscription into a bounded channel.
+///
+/// A forwarding handler is registered on the inner dispatcher for the definition. It
+/// completes once its message is in the channel, so the message is acknowledged when it is
+/// queued, not when the receiver processed it; queued messages are lost if the receiver
+/// is dropped. When the channel is full, the forwarding handler waits for room, which
+/// stops the inner dispatcher from taking more messages once its concurrency or prefetch
+/// limit is reached. Once the receiver is dropped, forwarded messages fail with
+/// `MessagingError::ConsumerError`.
+///
+/// All other methods are delegated to the inner dispatcher.
+pub struct ChannelDispatcher<D> {
+    inner: D,
+}
+
+/// The receiving side of a [`ChannelDispatcher`].
+pub struct MessageReceiver {
+    channel: Arc<Channel>,
+}
+
+struct Channel {
+    capacity: usize,
+    state: Mutex<ChannelState>,
+}
+
+#[derive(Default)]
+struct ChannelState {
+    msgs: VecDeque<ConsumerMessage>,
+    receiver_dropped: bool,
+    senders_dropped: bool,
+    receiver_waker: Option<Waker>,
+    sender_waiters: Vec<Waker>,
+}
+
+struct ForwardingHandler {
+    channel: Arc<Channel>,
+}
+
+impl Channel {
+    fn state(&self) -> MutexGuard<'_, ChannelState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[async_trait]
+impl ConsumerHandler for ForwardingHandler {
+    async fn exec(&self, _ctx: &Context, msg: &ConsumerMessage) -> Result<(), MessagingError> {
+        let mut msg = Some(msg.clone());
+
+        poll_fn(|cx| {
+            let mut state = self.channel.state();
+            if state.receiver_dropped {
+                return Poll::Ready(Err(MessagingError::consumer("channel receiver dropped")));
+            }
+            if state.msgs.len() >= self.channel.capacity {
+                state.sender_waiters.push(cx.waker().clone());
+                return Poll::Pending;
+            }
+
+            state.msgs.extend(msg.take());
+            if let Some(waker) = state.receiver_waker.take() {
+                waker.wake();
+            }
+            Poll::Ready(Ok(()))
+        })
+        .await
+    }
+}
+
+impl Drop for ForwardingHandler {
+    fn drop(&mut self) {
+        let mut state = self.channel.state();
+        state.senders_dropped = true;
+        if let Some(waker) = state.receiver_waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<D> ChannelDispatcher<D>
+where
+    D: Dispatcher,
+{
+    /// Creates a new channel dispatcher and registers its forwarding handler.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The dispatcher to consume messages with.
+    /// * `definition` - The subscription whose messages are forwarded.
+    /// * `capacity` - The maximum number of messages waiting in the channel (at least 1).
+    ///
+    /// # Returns
+    ///
+    /// The new `ChannelDispatcher` and the receiver of the forwarded messages.
+    pub fn new(
+        inner: D,
+        definition: &DispatcherDefinition,
+        capacity: usize,
+    ) -> (Self, MessageReceiver) {
+        let channel = Arc::new(Channel {
+            capacity: capacity.max(1),
+            state: Mutex::new(ChannelState::default()),
+        });
+
+        let inner = inner.register(
+            definition,
+            Arc::new(ForwardingHandler {
+                channel: channel.clone(),
+            }),
+        );
+
+        (ChannelDispatcher { inner }, MessageReceiver { channel })
+    }
+
+    /// Returns the wrapped dispatcher.
+    pub fn inner(&self) -> &D {
+        &self.inner
+    }
+}
+
+impl MessageReceiver {
+    /// Waits for the next forwarded message.
+    ///
+    /// # Returns
+    ///
+    /// The next message, or `None` once the channel is empty and the inner dispatcher, with
+    /// its forwarding handler, was dropped.
+    pub async fn recv(&self) -> Option<ConsumerMessage> {
+        poll_fn(|cx| {
+            let mut state = self.channel.state();
+            if let Some(msg) = state.msgs.pop_front() {
+                state.sender_waiters.drain(..).for_each(Waker::wake);
+                return Poll::Ready(Some(msg));
+            }
+            if state.senders_dropped {
+                return Poll::Ready(None);
+            }
+
+            state.receiver_waker = Some(cx.waker().clone());
+            Poll::Pending
+        })
+        .await
+    }
+
+    /// Takes the next forwarded message without waiting.
+    ///
+    /// # Returns
+    ///
+    /// The next message, or `None` if the channel is empty.
+    pub fn try_recv(&self) -> Option<ConsumerMessage> {
+        let mut state = self.channel.state();
+        let msg = state.msgs.pop_front()?;
+        state.sender_waiters.drain(..).for_each(Waker::wake);
+        Some(msg)
+    }
+
+    /// Returns the number of messages waiting in the channel.
+    pub fn len(&self) -> usize {
+        self.channel.state().msgs.len()
+    }
+
+    /// Returns `true` if no message is waiting in the channel.
+    pub fn is_empty(&self) -> bool {
+        self.channel.state().msgs.is_empty()
+    }
+}
+
+impl Drop for MessageReceiver {
+    fn drop(&mut self) {
+        let mut state = self.channel.state();
+        state.receiver_dropped = true;
+        state.sender_waiters.drain(..).for_each(Waker::wake);
+    }
+}
+
+#[async_trait]
+impl<D> Dispatcher for ChannelDispatcher<D>
+where
+    D: Dispatcher,
+{
+    fn register(
+        mut self,
+        definition: &DispatcherDefinition,
+        handler: Arc<dyn ConsumerHandler>,
+    ) -> Self {
+        self.inner = self.inner.register(definition, handler);
+        self
+    }
+
+    fn register_ref(
+        &mut self,
+        definition: &DispatcherDefinition,
+        handler: Arc<dyn ConsumerHandler>,
+    ) -> &mut Self {
+        self.inner.register_ref(definition, handler);
+        self
+    }
+
+    fn with_prefetch(mut self, prefetch: u16) -> Self {
+        self.inner = self.inner.with_prefetch(prefetch);
+        self
+    }
+
+    fn register_fallback(mut self, handler: Arc<dyn ConsumerHandler>) -> Self {
+        self.inner = self.inner.register_fallback(handler);
+        self
+    }
+
+    fn freeze(mut self) -> Self {
+        self.inner = self.inner.freeze();
+        self
+    }
+
+    fn is_frozen(&self) -> bool {
+        self.inner.is_frozen()
+    }
+
+    async fn consume_blocking(&self) -> Result<(), MessagingError> {
+        self.inner.consume_blocking().await
+    }
+
+    async fn connect(&self) -> Result<(), MessagingError> {
+        self.inner.connect().await
+    }
+
+    async fn disconnect(&self) -> Result<(), MessagingError> {
+        self.inner.disconnect().await
+    }
+
+    async fn health_check(&self) -> Result<(), MessagingError> {
+        self.inner.health_check().await
+    }
+
+    async fn drain(&self, timeout: Duration) -> Result<(), MessagingError> {
+        self.inner.drain(timeout).await
+    }
+
+    async fn consume_n(&self, n: usize) -> Result<Vec<ConsumerMessage>, MessagingError> {
+        self.inner.consume_n(n).await
+    }
+
+    async fn list_destinations(&self) -> Result<Vec<String>, MessagingError> {
+        self.inner.list_destinations().await
+    }
+
+    async fn seek(
+        &self,
+        definition: &DispatcherDefinition,
+        position: SeekPosition,
+    ) -> Result<(), MessagingError> {
+        self.inner.seek(definition, position).await
+    }
+
+    fn stats(&self) -> DispatcherStats {
+        self.inner.stats()
+    }
+
+    fn definitions(&self) -> Vec<DispatcherDefinition> {
+        self.inner.definitions()
+    }
+
+    fn handler_count(&self) -> usize {
+        self.inner.handler_count()
+    }
+
+    async fn pause(&self) {
+        self.inner.pause().await
+    }
+
+    async fn resume(&self) {
+        self.inner.resume().await
+    }
+
+    fn is_paused(&self) -> bool {
+        self.inner.is_paused()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{CapturingDispatcher, block_on, poll_once};
+    use std::pin::pin;
+
+    fn msg(data: &str) -> ConsumerMessage {
+        ConsumerMessage::new("orders", "created", data.as_bytes(), None)
+    }
+
+    fn channel(
+        capacity: usize,
+    ) -> (
+        ChannelDispatcher<CapturingDispatcher>,
+        MessageReceiver,
+        Arc<dyn ConsumerHandler>,
+    ) {
+        let inner = CapturingDispatcher::default();
+        let (dispatcher, receiver) = ChannelDispatcher::new(
+            inner.clone(),
+            &DispatcherDefinition::new("orders", None),
+            capacity,
+        );
+        let forwarding = inner.handler("orders");
+        (dispatcher, receiver, forwarding)
+    }
+
+    #[test]
+    fn messages_flow_through_the_channel_in_order() {
+        let (_dispatcher, receiver, forwarding) = channel(4);
+        let ctx = Context::new();
+
+        for data in ["1", "2"] {
+            assert_eq!(block_on(forwarding.exec(&ctx, &msg(data))), Ok(()));
+        }
+
+        assert_eq!(receiver.len(), 2);
+        assert_eq!(block_on(receiver.recv()), Some(msg("1")));
+        assert_eq!(receiver.try_recv(), Some(msg("2")));
+        assert!(receiver.is_empty());
+        assert_eq!(receiver.try_recv(), None);
+    }
+
+    #[test]
+    fn a_full_channel_holds_the_forwarding_handler() {
+        let (_dispatcher, receiver, forwarding) = channel(1);
+        let ctx = Context::new();
+        block_on(forwarding.exec(&ctx, &msg("1"))).unwrap();
+
+        let second = msg("2");
+        let mut forward = pin!(forwarding.exec(&ctx, &second));
+        assert!(poll_once(forward.as_mut()).is_pending());
+        assert!(poll_once(forward.as_mut()).is_pending());
+
+        assert_eq!(receiver.try_recv(), Some(msg("1")));
+        assert_eq!(poll_once(forward.as_mut()), Poll::Ready(Ok(())));
+        assert_eq!(receiver.try_recv(), Some(msg("2")));
+    }
+
+    #[test]
+    fn forwarding_fails_once_the_receiver_is_dropped() {
+        let (_dispatcher, receiver, forwarding) = channel(1);
+        drop(receiver);
+
+        assert_eq!(
+            block_on(forwarding.exec(&Context::new(), &msg("1"))),
+            Err(MessagingError::consumer("channel receiver dropped"))
+        );
+    }
+
+    #[test]
+    fn receiving_ends_once_the_dispatcher_is_dropped() {
+        let (dispatcher, receiver, forwarding) = channel(2);
+        block_on(forwarding.exec(&Context::new(), &msg("1"))).unwrap();
+
+        drop((dispatcher, forwarding));
+
+        assert_eq!(block_on(receiver.recv()), Some(msg("1")));
+        assert_eq!(block_on(receiver.recv()), None);
+    }
+}
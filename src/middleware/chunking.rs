@@ -0,0 +1,434 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Chunking
+//!
+//! This module provides middleware for sending payloads larger than the broker allows.
+//!
+//! The [`ChunkingPublisher`] splits the data of large messages into chunks published as
+//! separate messages, tagged with the [`CHUNK_ID`](headers::CHUNK_ID),
+//! [`CHUNK_INDEX`](headers::CHUNK_INDEX) and [`CHUNK_COUNT`](headers::CHUNK_COUNT)
+//! headers. The [`ReassemblingHandler`] buffers the chunks on the consumer side and invokes
+//! its inner handler once with the reassembled message.
+
+use crate::{
+    errors::MessagingError,
+    handler::{ConsumerHandler, ConsumerMessage},
+    headers,
+    publisher::{HeaderValues, PublishMessage, PublishReceipt, Publisher, delegate_publisher},
+};
+use async_trait::async_trait;
+use opentelemetry::Context;
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc, Mutex, MutexGuard, PoisonError,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// The default maximum number of chunks a [`ReassemblingHandler`] accepts per message.
+pub const DEFAULT_MAX_CHUNKS: usize = 1024;
+
+/// A publisher middleware splitting messages larger than a chunk size into several
+/// messages.
+///
+/// Every chunk carries the destination, key, type and headers of the original message,
+/// plus the chunk headers. Messages whose data fits into one chunk are published unchanged.
+/// Chunks are published in order and a failure stops the remaining chunks, so consumers
+/// must rely on the reassembly timeout of the [`ReassemblingHandler`] to discard
+/// incomplete messages.
+pub struct ChunkingPublisher {
+    inner: Arc<dyn Publisher>,
+    chunk_size: usize,
+    prefix: String,
+    sequence: AtomicU64,
+}
+
+impl ChunkingPublisher {
+    /// Creates a new chunking publisher.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The publisher to publish the chunks to.
+    /// * `chunk_size` - The maximum size of the data of a chunk in bytes. Zero is treated
+    ///   as one.
+    ///
+    /// # Returns
+    ///
+    /// A new `ChunkingPublisher` instance.
+    pub fn new(inner: Arc<dyn Publisher>, chunk_size: usize) -> Self {
+        let started = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+
+        ChunkingPublisher {
+            inner,
+            chunk_size: chunk_size.max(1),
+            prefix: format!("{started:x}"),
+            sequence: AtomicU64::new(0),
+        }
+    }
+
+    /// Splits a message into chunks, or returns `None` if it fits into a single chunk.
+    fn split(&self, msg: &PublishMessage) -> Option<Vec<PublishMessage>> {
+        if msg.data.len() <= self.chunk_size {
+            return None;
+        }
+
+        let id = format!(
+            "{}-{:x}",
+            self.prefix,
+            self.sequence.fetch_add(1, Ordering::Relaxed)
+        );
+        let count = msg.data.len().div_ceil(self.chunk_size);
+
+        let mut template = msg.clone();
+        template.data = Box::default();
+        template.set_header(headers::CHUNK_ID, HeaderValues::ShortString(id));
+        template.set_header(headers::CHUNK_COUNT, count as u64);
+
+        let chunks = msg
+            .data
+            .chunks(self.chunk_size)
+            .enumerate()
+            .map(|(index, data)| {
+                let mut chunk = template.clone();
+                chunk.data = data.into();
+                chunk.set_header(headers::CHUNK_INDEX, index as u64);
+                chunk
+            })
+            .collect();
+
+        Some(chunks)
+    }
+}
+
+#[async_trait]
+impl Publisher for ChunkingPublisher {
+    async fn publish(&self, ctx: &Context, msg: &PublishMessage) -> Result<(), MessagingError> {
+        let Some(chunks) = self.split(msg) else {
+            return self.inner.publish(ctx, msg).await;
+        };
+
+        for chunk in &chunks {
+            self.inner.publish(ctx, chunk).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Publishes every chunk confirmed and returns the receipt of the last chunk, which is
+    /// only `confirmed` when all chunks were.
+    async fn publish_confirmed(
+        &self,
+        ctx: &Context,
+        msg: &PublishMessage,
+    ) -> Result<PublishReceipt, MessagingError> {
+        let Some(chunks) = self.split(msg) else {
+            return self.inner.publish_confirmed(ctx, msg).await;
+        };
+
+        let mut receipt = PublishReceipt::default();
+        let mut confirmed = true;
+        for chunk in &chunks {
+            receipt = self.inner.publish_confirmed(ctx, chunk).await?;
+            confirmed &= receipt.confirmed;
+        }
+
+        receipt.confirmed = confirmed;
+        Ok(receipt)
+    }
+
+    delegate_publisher!(inner);
+}
+
+/// A consumer middleware reassembling messages split by a [`ChunkingPublisher`].
+///
+/// Chunks may arrive in any order and duplicates replace the earlier copy. Each chunk is
+/// acknowledged once buffered, and the inner handler runs when the last missing chunk
+/// arrives, with the headers of that chunk minus the chunk headers. If the inner handler
+/// fails, the chunks stay buffered, so a redelivery of the final chunk retries the whole
+/// message. Messages without a chunk id are passed through unchanged.
+///
+/// Incomplete messages are discarded once their first chunk is older than the reassembly
+/// timeout; expiry is checked whenever a chunk arrives. Buffered chunks live in memory
+/// only and are lost on restart. Chunks with missing, invalid or inconsistent chunk
+/// headers fail with `MessagingError::DeserializingError`, as do chunks of a message with
+/// more chunks than the configured maximum, so a malformed count cannot make the handler
+/// allocate an arbitrary amount of memory.
+pub struct ReassemblingHandler {
+    inner: Arc<dyn ConsumerHandler>,
+    timeout: Duration,
+    max_chunks: usize,
+    partials: Mutex<HashMap<String, Partial>>,
+}
+
+struct Partial {
+    started: Instant,
+    chunks: Vec<Option<Box<[u8]>>>,
+    received: usize,
+}
+
+impl ReassemblingHandler {
+    /// Creates a new reassembling handler accepting up to [`DEFAULT_MAX_CHUNKS`] chunks per
+    /// message.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The handler to invoke with reassembled messages.
+    /// * `timeout` - How long to wait for the missing chunks of a message, counted from its
+    ///   first buffered chunk.
+    ///
+    /// # Returns
+    ///
+    /// A new `ReassemblingHandler` instance.
+    pub fn new(inner: Arc<dyn ConsumerHandler>, timeout: Duration) -> Self {
+        ReassemblingHandler {
+            inner,
+            timeout,
+            max_chunks: DEFAULT_MAX_CHUNKS,
+            partials: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Sets the maximum number of chunks accepted per message.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_chunks` - The maximum chunk count.
+    ///
+    /// # Returns
+    ///
+    /// The updated `ReassemblingHandler`.
+    pub fn with_max_chunks(mut self, max_chunks: usize) -> Self {
+        self.max_chunks = max_chunks;
+        self
+    }
+
+    fn partials(&self) -> MutexGuard<'_, HashMap<String, Partial>> {
+        self.partials.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Buffers a chunk, returning the complete set of chunks once every chunk arrived.
+    fn buffer(
+        &self,
+        id: &str,
+        index: usize,
+        count: usize,
+        msg: &ConsumerMessage,
+    ) -> Result<Option<Partial>, MessagingError> {
+        let mut partials = self.partials();
+        let now = Instant::now();
+        partials.retain(|_, partial| now.duration_since(partial.started) < self.timeout);
+
+        let partial = partials.entry(id.to_owned()).or_insert_with(|| Partial {
+            started: now,
+            chunks: vec![None; count],
+            received: 0,
+        });
+        if partial.chunks.len() != count || index >= count {
+            return Err(MessagingError::DeserializingError);
+        }
+
+        if partial.chunks[index].replace(msg.data.clone()).is_none() {
+            partial.received += 1;
+        }
+
+        if partial.received < count {
+            return Ok(None);
+        }
+
+        Ok(partials.remove(id))
+    }
+}
+
+/// Reads the index and count headers of a chunk, checking that the index lies within a
+/// count of at most `max_chunks`.
+fn chunk_position(
+    msg: &ConsumerMessage,
+    max_chunks: usize,
+) -> Result<(usize, usize), MessagingError> {
+    let parse = |key| {
+        msg.header(key)
+            .and_then(|value| value.parse::<usize>().ok())
+            .ok_or(MessagingError::DeserializingError)
+    };
+
+    let index = parse(headers::CHUNK_INDEX)?;
+    let count = parse(headers::CHUNK_COUNT)?;
+    if index < count && count <= max_chunks {
+        Ok((index, count))
+    } else {
+        Err(MessagingError::DeserializingError)
+    }
+}
+
+#[async_trait]
+impl ConsumerHandler for ReassemblingHandler {
+    async fn exec(&self, ctx: &Context, msg: &ConsumerMessage) -> Result<(), MessagingError> {
+        let Some(id) = msg.header(headers::CHUNK_ID) else {
+            return self.inner.exec(ctx, msg).await;
+        };
+
+        let (index, count) = chunk_position(msg, self.max_chunks)?;
+        let Some(partial) = self.buffer(id, index, count, msg)? else {
+            return Ok(());
+        };
+
+        let mut assembled = msg.clone();
+        assembled.data = partial
+            .chunks
+            .iter()
+            .flatten()
+            .flat_map(|c| c.iter())
+            .copied()
+            .collect();
+        assembled.remove_header(headers::CHUNK_ID);
+        assembled.remove_header(headers::CHUNK_INDEX);
+        assembled.remove_header(headers::CHUNK_COUNT);
+
+        let result = self.inner.exec(ctx, &assembled).await;
+        if result.is_err() {
+            self.partials().insert(id.to_owned(), partial);
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{TestHandler, TestPublisher, block_on, delivered, message};
+
+    const MINUTE: Duration = Duration::from_secs(60);
+
+    fn chunks(chunk_size: usize, data: &str) -> Vec<PublishMessage> {
+        let inner = Arc::new(TestPublisher::default());
+        let publisher = ChunkingPublisher::new(inner.clone(), chunk_size);
+        block_on(publisher.publish(&Context::new(), &message("files", data))).unwrap();
+        inner.published()
+    }
+
+    fn reassemble(
+        handler: &ReassemblingHandler,
+        chunk: &PublishMessage,
+    ) -> Result<(), MessagingError> {
+        block_on(handler.exec(&Context::new(), &delivered(chunk)))
+    }
+
+    #[test]
+    fn publishes_messages_fitting_into_a_chunk_unchanged() {
+        let published = chunks(4, "abcd");
+
+        assert_eq!(published, [message("files", "abcd")]);
+    }
+
+    #[test]
+    fn splits_large_messages_into_tagged_chunks() {
+        let published = chunks(4, "abcdefghij");
+
+        let data: Vec<_> = published.iter().map(|chunk| &*chunk.data).collect();
+        assert_eq!(data, [&b"abcd"[..], b"efgh", b"ij"]);
+        for (index, chunk) in published.iter().enumerate() {
+            let received = delivered(chunk);
+            assert_eq!(
+                received.header(headers::CHUNK_INDEX),
+                Some(&*index.to_string())
+            );
+            assert_eq!(received.header(headers::CHUNK_COUNT), Some("3"));
+            assert_eq!(
+                received.header(headers::CHUNK_ID),
+                delivered(&published[0]).header(headers::CHUNK_ID)
+            );
+            assert_eq!(chunk.to, "files");
+        }
+    }
+
+    #[test]
+    fn reassembles_chunks_arriving_in_any_order() {
+        let published = chunks(4, "abcdefghij");
+        let inner = Arc::new(TestHandler::default());
+        let handler = ReassemblingHandler::new(inner.clone(), MINUTE);
+
+        for chunk in [&published[2], &published[0], &published[0], &published[1]] {
+            reassemble(&handler, chunk).unwrap();
+        }
+
+        let received = inner.received();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].data_str(), Ok("abcdefghij"));
+        assert_eq!(received[0].header(headers::CHUNK_ID), None);
+        assert_eq!(received[0].header(headers::CHUNK_INDEX), None);
+        assert_eq!(received[0].header(headers::CHUNK_COUNT), None);
+    }
+
+    #[test]
+    fn passes_messages_without_a_chunk_id_through() {
+        let inner = Arc::new(TestHandler::default());
+        let handler = ReassemblingHandler::new(inner.clone(), MINUTE);
+
+        reassemble(&handler, &message("files", "small")).unwrap();
+
+        assert_eq!(inner.received()[0].data_str(), Ok("small"));
+    }
+
+    #[test]
+    fn rejects_chunks_with_a_count_above_the_maximum() {
+        let published = chunks(4, "abcdefghij");
+        let inner = Arc::new(TestHandler::default());
+        let handler = ReassemblingHandler::new(inner.clone(), MINUTE).with_max_chunks(2);
+
+        let result = reassemble(&handler, &published[0]);
+
+        assert_eq!(result, Err(MessagingError::DeserializingError));
+        assert!(inner.received().is_empty());
+    }
+
+    #[test]
+    fn rejects_chunks_with_invalid_headers() {
+        let mut chunk = chunks(4, "abcdefghij").remove(0);
+        chunk.set_header(headers::CHUNK_INDEX, HeaderValues::from("first"));
+        let handler = ReassemblingHandler::new(Arc::new(TestHandler::default()), MINUTE);
+
+        assert_eq!(
+            reassemble(&handler, &chunk),
+            Err(MessagingError::DeserializingError)
+        );
+    }
+
+    #[test]
+    fn keeps_the_chunks_when_the_inner_handler_fails() {
+        let published = chunks(4, "abcdefghij");
+        let inner = Arc::new(TestHandler::failing(MessagingError::InternalError));
+        let handler = ReassemblingHandler::new(inner.clone(), MINUTE);
+
+        reassemble(&handler, &published[0]).unwrap();
+        reassemble(&handler, &published[1]).unwrap();
+        let result = reassemble(&handler, &published[2]);
+        assert_eq!(result, Err(MessagingError::InternalError));
+
+        let retried = reassemble(&handler, &published[2]);
+        assert_eq!(retried, Err(MessagingError::InternalError));
+        let received = inner.received();
+        assert_eq!(received.len(), 2);
+        assert_eq!(received[1].data_str(), Ok("abcdefghij"));
+    }
+
+    #[test]
+    fn discards_incomplete_messages_after_the_timeout() {
+        let published = chunks(4, "abcdefghij");
+        let inner = Arc::new(TestHandler::default());
+        let handler = ReassemblingHandler::new(inner.clone(), Duration::ZERO);
+
+        for chunk in &published {
+            reassemble(&handler, chunk).unwrap();
+        }
+
+        assert!(inner.received().is_empty());
+    }
+}
@@ -0,0 +1,219 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Composite
+//!
+//! This module provides a publisher that routes messages to one of several publishers.
+//!
+//! In multi-broker setups some destinations live on one broker and others on another. The
+//! `CompositePublisher` picks the publisher for each message based on its destination.
+
+use crate::{
+    errors::MessagingError,
+    publisher::{PublishMessage, PublishReceipt, Publisher},
+};
+use async_trait::async_trait;
+use opentelemetry::Context;
+use std::sync::Arc;
+
+/// A publisher that dispatches each message to the first route whose prefix matches the
+/// message destination.
+///
+/// Routes are checked in the order they were added. When no route matches, the message
+/// goes to the fallback publisher if one is configured, and fails with a
+/// `MessagingError::PublisherError` naming the destination otherwise.
+#[derive(Default)]
+pub struct CompositePublisher {
+    routes: Vec<(String, Arc<dyn Publisher>)>,
+    fallback: Option<Arc<dyn Publisher>>,
+}
+
+impl CompositePublisher {
+    /// Creates a new composite publisher without any route.
+    ///
+    /// # Returns
+    ///
+    /// A new `CompositePublisher` instance.
+    pub fn new() -> Self {
+        CompositePublisher::default()
+    }
+
+    /// Adds a route for destinations starting with the given prefix.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The destination prefix to match.
+    /// * `publisher` - The publisher for matching destinations.
+    ///
+    /// # Returns
+    ///
+    /// The updated `CompositePublisher`.
+    pub fn route<T>(mut self, prefix: T, publisher: Arc<dyn Publisher>) -> Self
+    where
+        T: Into<String>,
+    {
+        self.routes.push((prefix.into(), publisher));
+        self
+    }
+
+    /// Sets the publisher for destinations matching no route.
+    ///
+    /// # Arguments
+    ///
+    /// * `publisher` - The fallback publisher.
+    ///
+    /// # Returns
+    ///
+    /// The updated `CompositePublisher`.
+    pub fn fallback(mut self, publisher: Arc<dyn Publisher>) -> Self {
+        self.fallback = Some(publisher);
+        self
+    }
+
+    fn publisher_for(&self, to: &str) -> Result<&Arc<dyn Publisher>, MessagingError> {
+        self.routes
+            .iter()
+            .find(|(prefix, _)| to.starts_with(prefix.as_str()))
+            .map(|(_, publisher)| publisher)
+            .or(self.fallback.as_ref())
+            .ok_or_else(|| MessagingError::publisher(format!("no route for destination `{to}`")))
+    }
+
+    fn publishers(&self) -> impl Iterator<Item = &Arc<dyn Publisher>> {
+        self.routes
+            .iter()
+            .map(|(_, publisher)| publisher)
+            .chain(self.fallback.iter())
+    }
+}
+
+#[async_trait]
+impl Publisher for CompositePublisher {
+    async fn publish(&self, ctx: &Context, msg: &PublishMessage) -> Result<(), MessagingError> {
+        self.publisher_for(&msg.to)?.publish(ctx, msg).await
+    }
+
+    async fn publish_confirmed(
+        &self,
+        ctx: &Context,
+        msg: &PublishMessage,
+    ) -> Result<PublishReceipt, MessagingError> {
+        self.publisher_for(&msg.to)?
+            .publish_confirmed(ctx, msg)
+            .await
+    }
+
+    async fn barrier(&self) -> Result<(), MessagingError> {
+        for publisher in self.publishers() {
+            publisher.barrier().await?;
+        }
+        Ok(())
+    }
+
+    async fn connect(&self) -> Result<(), MessagingError> {
+        for publisher in self.publishers() {
+            publisher.connect().await?;
+        }
+        Ok(())
+    }
+
+    async fn disconnect(&self) -> Result<(), MessagingError> {
+        for publisher in self.publishers() {
+            publisher.disconnect().await?;
+        }
+        Ok(())
+    }
+
+    async fn health_check(&self) -> Result<(), MessagingError> {
+        for publisher in self.publishers() {
+            publisher.health_check().await?;
+        }
+        Ok(())
+    }
+
+    /// Ensures the destination on the publisher `publish` would route it to.
+    async fn ensure_destination(&self, to: &str) -> Result<(), MessagingError> {
+        self.publisher_for(to)?.ensure_destination(to).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{TestPublisher, block_on, message};
+
+    fn composite() -> (CompositePublisher, Arc<TestPublisher>, Arc<TestPublisher>) {
+        let orders = Arc::new(TestPublisher::default());
+        let invoices = Arc::new(TestPublisher::default());
+        let composite = CompositePublisher::new()
+            .route("orders.", orders.clone())
+            .route("invoices.", invoices.clone());
+        (composite, orders, invoices)
+    }
+
+    fn publish(publisher: &CompositePublisher, to: &str) -> Result<(), MessagingError> {
+        block_on(publisher.publish(&Context::new(), &message(to, to)))
+    }
+
+    #[test]
+    fn routes_destinations_by_prefix() {
+        let (composite, orders, invoices) = composite();
+
+        publish(&composite, "orders.created").unwrap();
+        publish(&composite, "invoices.paid").unwrap();
+
+        assert_eq!(orders.published_data(), ["orders.created"]);
+        assert_eq!(invoices.published_data(), ["invoices.paid"]);
+    }
+
+    #[test]
+    fn uses_the_first_matching_route() {
+        let first = Arc::new(TestPublisher::default());
+        let second = Arc::new(TestPublisher::default());
+        let composite = CompositePublisher::new()
+            .route("orders", first.clone())
+            .route("orders.created", second.clone());
+
+        publish(&composite, "orders.created").unwrap();
+
+        assert_eq!(first.published().len(), 1);
+        assert!(second.published().is_empty());
+    }
+
+    #[test]
+    fn sends_unmatched_destinations_to_the_fallback() {
+        let (composite, orders, _) = composite();
+        let fallback = Arc::new(TestPublisher::default());
+        let composite = composite.fallback(fallback.clone());
+
+        publish(&composite, "audit").unwrap();
+
+        assert_eq!(fallback.published_data(), ["audit"]);
+        assert!(orders.published().is_empty());
+    }
+
+    #[test]
+    fn rejects_unmatched_destinations_without_fallback() {
+        let (composite, _, _) = composite();
+
+        assert_eq!(
+            publish(&composite, "audit").unwrap_err().to_string(),
+            "failure to publish message: no route for destination `audit`"
+        );
+        assert!(block_on(composite.ensure_destination("audit")).is_err());
+    }
+
+    #[test]
+    fn routes_confirmed_publishes() {
+        let (composite, orders, invoices) = composite();
+
+        let receipt = block_on(
+            composite.publish_confirmed(&Context::new(), &message("orders.created", "data")),
+        );
+
+        assert_eq!(receipt, Ok(PublishReceipt::default()));
+        assert_eq!(orders.published().len(), 1);
+        assert!(invoices.published().is_empty());
+    }
+}
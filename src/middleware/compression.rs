@@ -0,0 +1,255 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Compression
+//!
+//! This module provides transparent compression of message data.
+//!
+//! The [`CompressingPublisher`] compresses `data` with a [`Compressor`] and records the
+//! encoding in the `content-encoding` header, and the [`DecompressingHandler`] reverses it
+//! on consume. The codec itself is pluggable, so any compression library can be used.
+
+use crate::{
+    errors::MessagingError,
+    handler::{ConsumerHandler, ConsumerMessage},
+    headers,
+    publisher::{HeaderValues, PublishMessage, PublishReceipt, Publisher, delegate_publisher},
+};
+use async_trait::async_trait;
+use opentelemetry::Context;
+use std::sync::Arc;
+
+#[cfg(feature = "mocks")]
+use mockall::*;
+
+/// Defines a compression codec for message data.
+#[cfg_attr(feature = "mocks", automock)]
+pub trait Compressor: Send + Sync {
+    /// Returns the name of the encoding stored in the `content-encoding` header,
+    /// e.g. `gzip` or `zstd`.
+    fn encoding(&self) -> &str;
+
+    /// Compresses message data.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The data to compress.
+    ///
+    /// # Returns
+    ///
+    /// The compressed data, or `MessagingError::SerializingError` on failure.
+    fn compress(&self, data: &[u8]) -> Result<Vec<u8>, MessagingError>;
+
+    /// Decompresses message data.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The data to decompress.
+    ///
+    /// # Returns
+    ///
+    /// The decompressed data, or `MessagingError::DeserializingError` on failure.
+    fn decompress(&self, data: &[u8]) -> Result<Vec<u8>, MessagingError>;
+}
+
+/// A publisher middleware that compresses message data before publishing.
+///
+/// Messages that already carry a `content-encoding` header are published unchanged, so
+/// data is never compressed twice.
+pub struct CompressingPublisher {
+    inner: Arc<dyn Publisher>,
+    compressor: Arc<dyn Compressor>,
+}
+
+impl CompressingPublisher {
+    /// Creates a new compressing publisher.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The publisher to send compressed messages to.
+    /// * `compressor` - The codec used to compress data.
+    ///
+    /// # Returns
+    ///
+    /// A new `CompressingPublisher` instance.
+    pub fn new(inner: Arc<dyn Publisher>, compressor: Arc<dyn Compressor>) -> Self {
+        CompressingPublisher { inner, compressor }
+    }
+
+    fn compress(&self, msg: &PublishMessage) -> Result<Option<PublishMessage>, MessagingError> {
+        if msg
+            .headers
+            .as_ref()
+            .is_some_and(|map| map.contains_key(headers::CONTENT_ENCODING))
+        {
+            return Ok(None);
+        }
+
+        let mut compressed = msg.clone();
+        compressed.data = self.compressor.compress(&msg.data)?.into();
+        compressed.set_header(
+            headers::CONTENT_ENCODING,
+            HeaderValues::ShortString(self.compressor.encoding().to_owned()),
+        );
+
+        Ok(Some(compressed))
+    }
+}
+
+#[async_trait]
+impl Publisher for CompressingPublisher {
+    async fn publish(&self, ctx: &Context, msg: &PublishMessage) -> Result<(), MessagingError> {
+        match self.compress(msg)? {
+            Some(compressed) => self.inner.publish(ctx, &compressed).await,
+            None => self.inner.publish(ctx, msg).await,
+        }
+    }
+
+    async fn publish_confirmed(
+        &self,
+        ctx: &Context,
+        msg: &PublishMessage,
+    ) -> Result<PublishReceipt, MessagingError> {
+        match self.compress(msg)? {
+            Some(compressed) => self.inner.publish_confirmed(ctx, &compressed).await,
+            None => self.inner.publish_confirmed(ctx, msg).await,
+        }
+    }
+
+    delegate_publisher!(inner);
+}
+
+/// A consumer middleware that decompresses message data before invoking the inner handler.
+///
+/// Only messages whose `content-encoding` header matches the encoding of the compressor
+/// are decompressed, and the header is removed afterwards. All other messages, including
+/// uncompressed ones, are passed through untouched.
+pub struct DecompressingHandler {
+    inner: Arc<dyn ConsumerHandler>,
+    compressor: Arc<dyn Compressor>,
+}
+
+impl DecompressingHandler {
+    /// Creates a new decompressing handler.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The handler to invoke with decompressed messages.
+    /// * `compressor` - The codec used to decompress data.
+    ///
+    /// # Returns
+    ///
+    /// A new `DecompressingHandler` instance.
+    pub fn new(inner: Arc<dyn ConsumerHandler>, compressor: Arc<dyn Compressor>) -> Self {
+        DecompressingHandler { inner, compressor }
+    }
+}
+
+#[async_trait]
+impl ConsumerHandler for DecompressingHandler {
+    async fn exec(&self, ctx: &Context, msg: &ConsumerMessage) -> Result<(), MessagingError> {
+        if msg.content_encoding() != Some(self.compressor.encoding()) {
+            return self.inner.exec(ctx, msg).await;
+        }
+
+        let mut decompressed = msg.clone();
+        decompressed.data = self.compressor.decompress(&msg.data)?.into();
+        if let Some(map) = decompressed.headers.as_mut() {
+            map.remove(headers::CONTENT_ENCODING);
+        }
+
+        self.inner.exec(ctx, &decompressed).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{TestHandler, TestPublisher, block_on, delivered, message};
+
+    /// A codec "compressing" data by prefixing it with `z:`.
+    struct Prefixing;
+
+    impl Compressor for Prefixing {
+        fn encoding(&self) -> &str {
+            "prefix"
+        }
+
+        fn compress(&self, data: &[u8]) -> Result<Vec<u8>, MessagingError> {
+            Ok([b"z:", data].concat())
+        }
+
+        fn decompress(&self, data: &[u8]) -> Result<Vec<u8>, MessagingError> {
+            data.strip_prefix(b"z:")
+                .map(<[u8]>::to_vec)
+                .ok_or(MessagingError::DeserializingError)
+        }
+    }
+
+    fn publish(msg: &PublishMessage) -> PublishMessage {
+        let inner = Arc::new(TestPublisher::default());
+        let publisher = CompressingPublisher::new(inner.clone(), Arc::new(Prefixing));
+        block_on(publisher.publish(&Context::new(), msg)).unwrap();
+        inner.published().remove(0)
+    }
+
+    fn consume(msg: &ConsumerMessage) -> (Result<(), MessagingError>, Vec<ConsumerMessage>) {
+        let inner = Arc::new(TestHandler::default());
+        let handler = DecompressingHandler::new(inner.clone(), Arc::new(Prefixing));
+        let result = block_on(handler.exec(&Context::new(), msg));
+        (result, inner.received())
+    }
+
+    #[test]
+    fn compresses_data_and_sets_the_encoding() {
+        let published = publish(&message("orders", "data"));
+
+        assert_eq!(&*published.data, b"z:data");
+        assert_eq!(
+            published.headers.unwrap()[headers::CONTENT_ENCODING],
+            HeaderValues::ShortString("prefix".to_owned())
+        );
+    }
+
+    #[test]
+    fn does_not_compress_encoded_messages_twice() {
+        let mut msg = message("orders", "data");
+        msg.set_header(headers::CONTENT_ENCODING, "gzip");
+
+        assert_eq!(publish(&msg), msg);
+    }
+
+    #[test]
+    fn round_trips_compressed_messages() {
+        let msg = message("orders", "data");
+
+        let (result, received) = consume(&delivered(&publish(&msg)));
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(&*received[0].data, b"data");
+        assert_eq!(received[0].content_encoding(), None);
+    }
+
+    #[test]
+    fn passes_other_encodings_through() {
+        let mut msg = ConsumerMessage::new("orders", "event", b"data", None);
+        msg.set_header(headers::CONTENT_ENCODING, "gzip");
+
+        let (result, received) = consume(&msg);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(received, [msg]);
+    }
+
+    #[test]
+    fn fails_messages_that_cannot_be_decompressed() {
+        let mut msg = ConsumerMessage::new("orders", "event", b"corrupt", None);
+        msg.set_header(headers::CONTENT_ENCODING, "prefix");
+
+        let (result, received) = consume(&msg);
+
+        assert_eq!(result, Err(MessagingError::DeserializingError));
+        assert!(received.is_empty());
+    }
+}
@@ -0,0 +1,195 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Concurrency
+//!
+//! This module provides a consumer middleware that bounds how many messages are handled
+//! at the same time.
+//!
+//! Capping concurrency protects downstream systems when handlers do heavy work. Messages
+//! above the limit wait for a free slot instead of being rejected.
+
+use crate::{
+    dispatcher::Concurrency,
+    errors::MessagingError,
+    handler::{ConsumerHandler, ConsumerMessage},
+};
+use async_trait::async_trait;
+use opentelemetry::Context;
+use std::{
+    future::poll_fn,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    task::{Poll, Waker},
+};
+
+/// A consumer middleware that limits the number of concurrent `exec` calls of the inner
+/// handler.
+///
+/// A slot is taken before the inner handler runs and given back when it finishes, also
+/// when it fails or panics.
+pub struct ConcurrencyLimitHandler {
+    inner: Arc<dyn ConsumerHandler>,
+    semaphore: Option<Semaphore>,
+}
+
+struct Semaphore {
+    state: Mutex<SemaphoreState>,
+}
+
+struct SemaphoreState {
+    available: usize,
+    waiters: Vec<Waker>,
+}
+
+struct Permit<'a> {
+    semaphore: &'a Semaphore,
+}
+
+impl Semaphore {
+    fn new(permits: usize) -> Self {
+        Semaphore {
+            state: Mutex::new(SemaphoreState {
+                available: permits,
+                waiters: Vec::new(),
+            }),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, SemaphoreState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    async fn acquire(&self) -> Permit<'_> {
+        poll_fn(|cx| {
+            let mut state = self.state();
+            if state.available > 0 {
+                state.available -= 1;
+                return Poll::Ready(());
+            }
+            state.waiters.push(cx.waker().clone());
+            Poll::Pending
+        })
+        .await;
+
+        Permit { semaphore: self }
+    }
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        let mut state = self.semaphore.state();
+        state.available += 1;
+        // Waking every waiter keeps the wake-up from being lost when a woken waiter was
+        // cancelled before it could take the slot.
+        state.waiters.drain(..).for_each(Waker::wake);
+    }
+}
+
+impl ConcurrencyLimitHandler {
+    /// Creates a new concurrency-limiting handler.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The handler to limit.
+    /// * `max_concurrency` - The concurrency limit, resolved once at construction.
+    ///   `Concurrency::Unbounded` applies no limit.
+    ///
+    /// # Returns
+    ///
+    /// A new `ConcurrencyLimitHandler` instance.
+    pub fn new<C>(inner: Arc<dyn ConsumerHandler>, max_concurrency: C) -> Self
+    where
+        C: Into<Concurrency>,
+    {
+        ConcurrencyLimitHandler {
+            inner,
+            semaphore: max_concurrency.into().resolve().map(Semaphore::new),
+        }
+    }
+}
+
+#[async_trait]
+impl ConsumerHandler for ConcurrencyLimitHandler {
+    async fn exec(&self, ctx: &Context, msg: &ConsumerMessage) -> Result<(), MessagingError> {
+        let Some(semaphore) = &self.semaphore else {
+            return self.inner.exec(ctx, msg).await;
+        };
+
+        let _permit = semaphore.acquire().await;
+        self.inner.exec(ctx, msg).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        handler::fn_handler,
+        test_support::{block_on, join_all, yield_now},
+    };
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Default)]
+    struct Gauge {
+        current: AtomicUsize,
+        peak: AtomicUsize,
+    }
+
+    fn measuring(gauge: &Arc<Gauge>) -> Arc<dyn ConsumerHandler> {
+        let gauge = gauge.clone();
+        fn_handler(move |_, _| {
+            let gauge = gauge.clone();
+            async move {
+                let current = gauge.current.fetch_add(1, Ordering::SeqCst) + 1;
+                gauge.peak.fetch_max(current, Ordering::SeqCst);
+                for _ in 0..3 {
+                    yield_now().await;
+                }
+                gauge.current.fetch_sub(1, Ordering::SeqCst);
+                Ok(())
+            }
+        })
+    }
+
+    fn run(handler: &ConcurrencyLimitHandler, messages: usize) -> Vec<Result<(), MessagingError>> {
+        let ctx = Context::new();
+        let msg = ConsumerMessage::new("orders", "created", b"data", None);
+        block_on(join_all(
+            (0..messages).map(|_| handler.exec(&ctx, &msg)).collect(),
+        ))
+    }
+
+    #[test]
+    fn peak_concurrency_never_exceeds_the_limit() {
+        let gauge = Arc::new(Gauge::default());
+        let handler = ConcurrencyLimitHandler::new(measuring(&gauge), 3);
+
+        let results = run(&handler, 10);
+
+        assert!(results.iter().all(Result::is_ok));
+        assert_eq!(results.len(), 10);
+        assert_eq!(gauge.peak.load(Ordering::SeqCst), 3);
+        assert_eq!(gauge.current.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn unbounded_concurrency_applies_no_limit() {
+        let gauge = Arc::new(Gauge::default());
+        let handler = ConcurrencyLimitHandler::new(measuring(&gauge), Concurrency::Unbounded);
+
+        run(&handler, 10);
+
+        assert_eq!(gauge.peak.load(Ordering::SeqCst), 10);
+    }
+
+    #[test]
+    fn failed_handlers_release_their_slot() {
+        let inner = fn_handler(|_, _| async { Err(MessagingError::HandlerError) });
+        let handler = ConcurrencyLimitHandler::new(inner, 1);
+
+        let results = run(&handler, 3);
+
+        assert_eq!(results, [const { Err(MessagingError::HandlerError) }; 3]);
+    }
+}
@@ -0,0 +1,146 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Dead Letter
+//!
+//! This module provides a consumer middleware that forwards failed messages to a
+//! dead-letter destination.
+//!
+//! Instead of losing a message whose handler failed, the `DeadLetterHandler` republishes it
+//! through a [`Publisher`] together with the reason of the failure.
+
+use crate::{
+    errors::MessagingError,
+    handler::{ConsumerHandler, ConsumerMessage},
+    publisher::{PublishMessage, Publisher},
+};
+use async_trait::async_trait;
+use opentelemetry::Context;
+use std::sync::Arc;
+
+/// The header carrying the reason a message was dead-lettered.
+pub const DEATH_REASON_HEADER: &str = "x-death-reason";
+
+/// A consumer middleware that republishes messages to a dead-letter destination when the
+/// inner handler fails.
+///
+/// The dead-lettered message is built with `PublishMessage::from(&ConsumerMessage)`, sent to
+/// the configured target and tagged with the [`DEATH_REASON_HEADER`] header. The handler
+/// error is always returned afterwards, even if the dead-letter publish itself failed, so
+/// the dispatcher can still apply its own failure handling.
+pub struct DeadLetterHandler {
+    inner: Arc<dyn ConsumerHandler>,
+    publisher: Arc<dyn Publisher>,
+    target: String,
+}
+
+impl DeadLetterHandler {
+    /// Creates a new dead-letter handler.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The handler to process messages.
+    /// * `publisher` - The publisher used to send failed messages.
+    /// * `target` - The dead-letter destination.
+    ///
+    /// # Returns
+    ///
+    /// A new `DeadLetterHandler` instance.
+    pub fn new<T>(inner: Arc<dyn ConsumerHandler>, publisher: Arc<dyn Publisher>, target: T) -> Self
+    where
+        T: Into<String>,
+    {
+        DeadLetterHandler {
+            inner,
+            publisher,
+            target: target.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl ConsumerHandler for DeadLetterHandler {
+    async fn exec(&self, ctx: &Context, msg: &ConsumerMessage) -> Result<(), MessagingError> {
+        let Err(err) = self.inner.exec(ctx, msg).await else {
+            return Ok(());
+        };
+
+        let dead_letter = dead_letter(msg, &self.target, &err);
+        let _ = self.publisher.publish(ctx, &dead_letter).await;
+
+        Err(err)
+    }
+}
+
+/// Builds the message republished to a dead-letter destination.
+pub(crate) fn dead_letter(
+    msg: &ConsumerMessage,
+    target: &str,
+    err: &MessagingError,
+) -> PublishMessage {
+    let mut dead_letter = PublishMessage::from(msg);
+    dead_letter.to = target.to_owned();
+    dead_letter.set_header(DEATH_REASON_HEADER, err.to_string());
+
+    dead_letter
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        handler::fn_handler,
+        publisher::HeaderValues,
+        test_support::{TestPublisher, block_on},
+    };
+
+    fn handler(result: Result<(), MessagingError>, dlq: &Arc<TestPublisher>) -> DeadLetterHandler {
+        let inner = fn_handler(move |_, _| {
+            let result = result.clone();
+            async move { result }
+        });
+        DeadLetterHandler::new(inner, dlq.clone(), "orders.dlq")
+    }
+
+    fn exec(handler: &DeadLetterHandler) -> Result<(), MessagingError> {
+        let msg = ConsumerMessage::new("orders", "created", b"data", None);
+        block_on(handler.exec(&Context::new(), &msg))
+    }
+
+    #[test]
+    fn does_not_dead_letter_handled_messages() {
+        let dlq = Arc::new(TestPublisher::default());
+
+        assert_eq!(exec(&handler(Ok(()), &dlq)), Ok(()));
+        assert!(dlq.published().is_empty());
+    }
+
+    #[test]
+    fn dead_letters_failed_messages_with_the_reason() {
+        let dlq = Arc::new(TestPublisher::default());
+
+        let result = exec(&handler(Err(MessagingError::HandlerError), &dlq));
+
+        assert_eq!(result, Err(MessagingError::HandlerError));
+        let published = dlq.published();
+        assert_eq!(published.len(), 1);
+        assert_eq!(published[0].to, "orders.dlq");
+        assert_eq!(published[0].msg_type.as_deref(), Some("created"));
+        assert_eq!(&*published[0].data, b"data");
+        assert_eq!(
+            published[0].headers.as_ref().unwrap()[DEATH_REASON_HEADER],
+            HeaderValues::from(MessagingError::HandlerError.to_string())
+        );
+    }
+
+    #[test]
+    fn returns_the_handler_error_when_dead_lettering_fails() {
+        let dlq = Arc::new(TestPublisher::default());
+        dlq.fail_with(Some(MessagingError::publisher("broker unavailable")));
+
+        let result = exec(&handler(Err(MessagingError::HandlerError), &dlq));
+
+        assert_eq!(result, Err(MessagingError::HandlerError));
+    }
+}
@@ -0,0 +1,178 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Declaring
+//!
+//! This module provides a publisher middleware that makes sure destinations exist before
+//! publishing to them.
+//!
+//! The [`DeclaringPublisher`] calls [`Publisher::ensure_destination`] the first time a
+//! destination is published to, so that a misconfigured destination fails the publish
+//! instead of losing the message.
+
+use crate::{
+    errors::MessagingError,
+    publisher::{PublishMessage, PublishReceipt, Publisher, delegate_publisher},
+};
+use async_trait::async_trait;
+use opentelemetry::Context;
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+/// A publisher middleware ensuring each destination exists before its first publish.
+///
+/// Destinations ensured successfully are remembered, so `ensure_destination` of the inner
+/// publisher runs once per distinct `to`. A failure is not remembered: the publish fails
+/// with its error and the next publish to the destination tries again. Concurrent first
+/// publishes to the same destination may each ensure it.
+pub struct DeclaringPublisher {
+    inner: Arc<dyn Publisher>,
+    ensured: Mutex<HashSet<String>>,
+}
+
+impl DeclaringPublisher {
+    /// Creates a new declaring publisher.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The publisher to ensure destinations on and publish to.
+    ///
+    /// # Returns
+    ///
+    /// A new `DeclaringPublisher` instance.
+    pub fn new(inner: Arc<dyn Publisher>) -> Self {
+        DeclaringPublisher {
+            inner,
+            ensured: Mutex::new(HashSet::new()),
+        }
+    }
+
+    fn ensured(&self) -> MutexGuard<'_, HashSet<String>> {
+        self.ensured.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[async_trait]
+impl Publisher for DeclaringPublisher {
+    async fn publish(&self, ctx: &Context, msg: &PublishMessage) -> Result<(), MessagingError> {
+        self.ensure_destination(&msg.to).await?;
+        self.inner.publish(ctx, msg).await
+    }
+
+    async fn publish_confirmed(
+        &self,
+        ctx: &Context,
+        msg: &PublishMessage,
+    ) -> Result<PublishReceipt, MessagingError> {
+        self.ensure_destination(&msg.to).await?;
+        self.inner.publish_confirmed(ctx, msg).await
+    }
+
+    async fn ensure_destination(&self, to: &str) -> Result<(), MessagingError> {
+        if self.ensured().contains(to) {
+            return Ok(());
+        }
+
+        self.inner.ensure_destination(to).await?;
+        self.ensured().insert(to.to_owned());
+        Ok(())
+    }
+
+    delegate_publisher!(inner, except = [ensure_destination]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{TestPublisher, block_on, message};
+    #[cfg(feature = "mocks")]
+    use crate::{middleware::size_limit::SizeLimitedPublisher, publisher::MockPublisher};
+    #[cfg(feature = "mocks")]
+    use mockall::{Sequence, predicate::eq};
+
+    fn publish_to(publisher: &DeclaringPublisher, to: &str) -> Result<(), MessagingError> {
+        block_on(publisher.publish(&Context::new(), &message(to, "data")))
+    }
+
+    #[test]
+    fn publishes_after_the_default_ensure() {
+        let inner = Arc::new(TestPublisher::default());
+        let publisher = DeclaringPublisher::new(inner.clone());
+
+        assert_eq!(publish_to(&publisher, "orders"), Ok(()));
+        assert_eq!(publisher.ensured().len(), 1);
+        assert_eq!(inner.published().len(), 1);
+    }
+
+    #[cfg(feature = "mocks")]
+    fn publishing_mock(publishes: usize) -> MockPublisher {
+        let mut inner = MockPublisher::new();
+        inner
+            .expect_publish()
+            .times(publishes)
+            .returning(|_, _| Ok(()));
+        inner
+    }
+
+    #[cfg(feature = "mocks")]
+    #[test]
+    fn ensures_each_destination_once() {
+        let mut inner = publishing_mock(4);
+        for to in ["orders", "invoices"] {
+            inner
+                .expect_ensure_destination()
+                .with(eq(to))
+                .times(1)
+                .returning(|_| Ok(()));
+        }
+        let publisher = DeclaringPublisher::new(Arc::new(inner));
+
+        for to in ["orders", "invoices", "orders", "invoices"] {
+            assert_eq!(publish_to(&publisher, to), Ok(()));
+        }
+    }
+
+    #[cfg(feature = "mocks")]
+    #[test]
+    fn retries_destinations_that_failed_to_ensure() {
+        let mut inner = publishing_mock(2);
+        let mut sequence = Sequence::new();
+        inner
+            .expect_ensure_destination()
+            .times(1)
+            .in_sequence(&mut sequence)
+            .returning(|_| Err(MessagingError::publisher("no such queue")));
+        inner
+            .expect_ensure_destination()
+            .times(1)
+            .in_sequence(&mut sequence)
+            .returning(|_| Ok(()));
+        let publisher = DeclaringPublisher::new(Arc::new(inner));
+
+        assert_eq!(
+            publish_to(&publisher, "orders"),
+            Err(MessagingError::publisher("no such queue"))
+        );
+        assert_eq!(publish_to(&publisher, "orders"), Ok(()));
+        assert_eq!(publish_to(&publisher, "orders"), Ok(()));
+    }
+
+    #[cfg(feature = "mocks")]
+    #[test]
+    fn ensures_through_delegating_middleware() {
+        let mut inner = publishing_mock(2);
+        inner
+            .expect_ensure_destination()
+            .with(eq("orders"))
+            .times(1)
+            .returning(|_| Ok(()));
+        let limited = SizeLimitedPublisher::new(Arc::new(inner), 1024);
+        let publisher = DeclaringPublisher::new(Arc::new(limited));
+
+        assert_eq!(publish_to(&publisher, "orders"), Ok(()));
+        assert_eq!(publish_to(&publisher, "orders"), Ok(()));
+    }
+}
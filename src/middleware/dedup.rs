@@ -0,0 +1,534 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Dedup
+//!
+//! This module provides deduplicating consumer and publisher middleware.
+//!
+//! At-least-once brokers may deliver the same message more than once. The `DedupMiddleware`
+//! remembers recently processed `message_id`s and skips messages it has already seen.
+//! Likewise, producers retrying a publish may send a message twice; the `DedupPublisher`
+//! remembers recently published `message-id` headers and drops repeated publishes.
+
+use crate::{
+    errors::MessagingError,
+    handler::{ConsumerHandler, ConsumerMessage},
+    headers,
+    middleware::cache::BoundedCache,
+    publisher::{PublishMessage, PublishReceipt, Publisher, delegate_publisher},
+};
+use async_trait::async_trait;
+use opentelemetry::Context;
+use std::{
+    collections::HashMap,
+    future::{Future, poll_fn},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    task::{Poll, Waker},
+    time::{Duration, Instant},
+};
+
+/// A consumer middleware that skips messages whose `message_id` was recently processed.
+///
+/// Seen ids are kept in a bounded LRU cache: a duplicate refreshes its id, once `capacity`
+/// is reached the least recently seen id is evicted, and ids not seen for `ttl` are
+/// forgotten. Messages without a `message_id` are always passed through to the inner
+/// handler.
+///
+/// An id is recorded before the inner handler runs, so concurrent deliveries of the same
+/// message are processed only once. If the inner handler fails or is cancelled the id is
+/// forgotten again, allowing the broker redelivery to be processed.
+pub struct DedupMiddleware {
+    inner: Arc<dyn ConsumerHandler>,
+    seen: Mutex<BoundedCache<()>>,
+}
+
+/// Forgets a recorded id unless the processing of its message completed.
+struct Recorded<'a> {
+    seen: &'a Mutex<BoundedCache<()>>,
+    id: &'a str,
+    processed: bool,
+}
+
+impl Drop for Recorded<'_> {
+    fn drop(&mut self) {
+        if !self.processed {
+            lock(self.seen).remove(self.id);
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl DedupMiddleware {
+    /// Creates a new deduplicating middleware.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The handler to invoke for messages that were not seen before.
+    /// * `capacity` - The maximum number of ids to remember (at least 1).
+    /// * `ttl` - How long an id is remembered after it was last seen.
+    ///
+    /// # Returns
+    ///
+    /// A new `DedupMiddleware` instance.
+    pub fn new(inner: Arc<dyn ConsumerHandler>, capacity: usize, ttl: Duration) -> Self {
+        DedupMiddleware {
+            inner,
+            seen: Mutex::new(BoundedCache::new(capacity, ttl)),
+        }
+    }
+}
+
+#[async_trait]
+impl ConsumerHandler for DedupMiddleware {
+    async fn exec(&self, ctx: &Context, msg: &ConsumerMessage) -> Result<(), MessagingError> {
+        let Some(id) = msg.message_id() else {
+            return self.inner.exec(ctx, msg).await;
+        };
+
+        {
+            let mut seen = lock(&self.seen);
+            let now = Instant::now();
+            if seen.get_mut(id, now).is_some() {
+                return Ok(());
+            }
+            seen.insert(id, (), now);
+        }
+
+        let mut recorded = Recorded {
+            seen: &self.seen,
+            id,
+            processed: false,
+        };
+        let result = self.inner.exec(ctx, msg).await;
+        recorded.processed = result.is_ok();
+
+        result
+    }
+}
+
+/// A publisher middleware that drops messages whose `message-id` header was recently
+/// published.
+///
+/// Seen ids are kept in the same bounded LRU cache as in [`DedupMiddleware`]. A dropped
+/// message is reported as published; `publish_confirmed` returns an unconfirmed receipt
+/// for it. Messages without a `message-id` header are always published.
+///
+/// An id is only recorded once the inner publisher succeeded. While a publish of an id is
+/// in progress, concurrent publishes of the same id wait for its result: they are dropped
+/// if it succeeded, and one of them publishes instead if it failed or was cancelled. A
+/// failed or cancelled publish therefore never marks its id as seen.
+pub struct DedupPublisher {
+    inner: Arc<dyn Publisher>,
+    state: Mutex<PublishedIds>,
+}
+
+struct PublishedIds {
+    seen: BoundedCache<()>,
+    in_flight: HashMap<String, Arc<InFlight>>,
+}
+
+/// The pending result of the publish of an id, awaited by concurrent duplicates.
+#[derive(Default)]
+struct InFlight {
+    state: Mutex<InFlightState>,
+}
+
+#[derive(Default)]
+struct InFlightState {
+    published: Option<bool>,
+    waiters: Vec<Waker>,
+}
+
+impl InFlight {
+    /// Waits for the publish, returning whether it succeeded.
+    async fn wait(&self) -> bool {
+        poll_fn(|cx| {
+            let mut state = lock(&self.state);
+            match state.published {
+                Some(published) => Poll::Ready(published),
+                None => {
+                    state.waiters.push(cx.waker().clone());
+                    Poll::Pending
+                }
+            }
+        })
+        .await
+    }
+}
+
+enum Claim<'a> {
+    Duplicate,
+    Owner(Publishing<'a>),
+    Wait(Arc<InFlight>),
+}
+
+/// The right to publish an id, completing its in-flight entry when dropped.
+struct Publishing<'a> {
+    publisher: &'a DedupPublisher,
+    id: String,
+    in_flight: Arc<InFlight>,
+    published: bool,
+}
+
+impl Drop for Publishing<'_> {
+    fn drop(&mut self) {
+        {
+            let mut state = lock(&self.publisher.state);
+            state.in_flight.remove(&self.id);
+            if self.published {
+                state.seen.insert(&self.id, (), Instant::now());
+            }
+        }
+
+        let mut in_flight = lock(&self.in_flight.state);
+        in_flight.published = Some(self.published);
+        in_flight.waiters.drain(..).for_each(Waker::wake);
+    }
+}
+
+impl DedupPublisher {
+    /// Creates a new deduplicating publisher.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The publisher to send messages that were not seen before to.
+    /// * `capacity` - The maximum number of ids to remember (at least 1).
+    /// * `ttl` - How long an id is remembered after it was last published.
+    ///
+    /// # Returns
+    ///
+    /// A new `DedupPublisher` instance.
+    pub fn new(inner: Arc<dyn Publisher>, capacity: usize, ttl: Duration) -> Self {
+        DedupPublisher {
+            inner,
+            state: Mutex::new(PublishedIds {
+                seen: BoundedCache::new(capacity, ttl),
+                in_flight: HashMap::new(),
+            }),
+        }
+    }
+
+    fn claim(&self, id: &str) -> Claim<'_> {
+        let mut state = lock(&self.state);
+        if state.seen.get_mut(id, Instant::now()).is_some() {
+            return Claim::Duplicate;
+        }
+        if let Some(in_flight) = state.in_flight.get(id) {
+            return Claim::Wait(in_flight.clone());
+        }
+
+        let in_flight = Arc::new(InFlight::default());
+        state.in_flight.insert(id.to_owned(), in_flight.clone());
+        Claim::Owner(Publishing {
+            publisher: self,
+            id: id.to_owned(),
+            in_flight,
+            published: false,
+        })
+    }
+
+    /// Publishes a message unless its id was published or is being published successfully.
+    async fn publish_once<T, F, Fut>(
+        &self,
+        msg: &PublishMessage,
+        duplicate: T,
+        publish: F,
+    ) -> Result<T, MessagingError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, MessagingError>>,
+    {
+        let Some(id) = message_id(msg) else {
+            return publish().await;
+        };
+
+        let mut publishing = loop {
+            match self.claim(&id) {
+                Claim::Duplicate => return Ok(duplicate),
+                Claim::Owner(publishing) => break publishing,
+                Claim::Wait(in_flight) => {
+                    if in_flight.wait().await {
+                        return Ok(duplicate);
+                    }
+                }
+            }
+        };
+
+        let result = publish().await;
+        publishing.published = result.is_ok();
+        result
+    }
+}
+
+/// Returns the `message-id` header of a message.
+fn message_id(msg: &PublishMessage) -> Option<String> {
+    msg.headers
+        .as_ref()?
+        .get(headers::MESSAGE_ID)
+        .map(|id| String::from(id.clone()))
+}
+
+#[async_trait]
+impl Publisher for DedupPublisher {
+    async fn publish(&self, ctx: &Context, msg: &PublishMessage) -> Result<(), MessagingError> {
+        self.publish_once(msg, (), || self.inner.publish(ctx, msg))
+            .await
+    }
+
+    async fn publish_confirmed(
+        &self,
+        ctx: &Context,
+        msg: &PublishMessage,
+    ) -> Result<PublishReceipt, MessagingError> {
+        self.publish_once(msg, PublishReceipt::default(), || {
+            self.inner.publish_confirmed(ctx, msg)
+        })
+        .await
+    }
+
+    delegate_publisher!(inner);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        handler::fn_handler,
+        publisher::HeaderValues,
+        test_support::{TestPublisher, block_on, message, poll_once},
+    };
+    use std::{
+        future::pending,
+        pin::pin,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    const HOUR: Duration = Duration::from_secs(3600);
+
+    fn counting(
+        count: &Arc<AtomicUsize>,
+        result: Result<(), MessagingError>,
+    ) -> Arc<dyn ConsumerHandler> {
+        let count = count.clone();
+        fn_handler(move |_, _| {
+            count.fetch_add(1, Ordering::SeqCst);
+            let result = result.clone();
+            async move { result }
+        })
+    }
+
+    fn delivery(id: Option<&str>) -> ConsumerMessage {
+        let mut msg = ConsumerMessage::new("orders", "event", b"data", None);
+        msg.message_id = id.map(str::to_owned);
+        msg
+    }
+
+    fn exec(middleware: &DedupMiddleware, id: Option<&str>) -> Result<(), MessagingError> {
+        block_on(middleware.exec(&Context::new(), &delivery(id)))
+    }
+
+    #[test]
+    fn processes_the_same_id_once() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let middleware = DedupMiddleware::new(counting(&count, Ok(())), 10, HOUR);
+
+        assert_eq!(exec(&middleware, Some("a")), Ok(()));
+        assert_eq!(exec(&middleware, Some("a")), Ok(()));
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn processes_different_ids_and_messages_without_id() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let middleware = DedupMiddleware::new(counting(&count, Ok(())), 10, HOUR);
+
+        exec(&middleware, Some("a")).unwrap();
+        exec(&middleware, Some("b")).unwrap();
+        exec(&middleware, None).unwrap();
+        exec(&middleware, None).unwrap();
+        assert_eq!(count.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn failed_processing_allows_the_redelivery() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let middleware = DedupMiddleware::new(
+            counting(&count, Err(MessagingError::HandlerError)),
+            10,
+            HOUR,
+        );
+
+        assert_eq!(
+            exec(&middleware, Some("a")),
+            Err(MessagingError::HandlerError)
+        );
+        assert_eq!(
+            exec(&middleware, Some("a")),
+            Err(MessagingError::HandlerError)
+        );
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn cancelled_processing_allows_the_redelivery() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let hanging = DedupMiddleware::new(fn_handler(|_, _| pending()), 10, HOUR);
+        let (ctx, msg) = (Context::new(), delivery(Some("a")));
+        {
+            let mut exec = pin!(hanging.exec(&ctx, &msg));
+            assert!(poll_once(exec.as_mut()).is_pending());
+        }
+
+        let middleware = DedupMiddleware {
+            inner: counting(&count, Ok(())),
+            seen: hanging.seen,
+        };
+        exec(&middleware, Some("a")).unwrap();
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn duplicates_keep_their_id_recent() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let middleware = DedupMiddleware::new(counting(&count, Ok(())), 2, HOUR);
+
+        for id in ["a", "b", "a", "c", "a"] {
+            exec(&middleware, Some(id)).unwrap();
+        }
+        assert_eq!(count.load(Ordering::SeqCst), 3);
+
+        exec(&middleware, Some("b")).unwrap();
+        assert_eq!(count.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn forgets_ids_after_the_ttl() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let middleware = DedupMiddleware::new(counting(&count, Ok(())), 10, Duration::ZERO);
+
+        exec(&middleware, Some("a")).unwrap();
+        exec(&middleware, Some("a")).unwrap();
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+    }
+
+    fn identified(id: &str, data: &str) -> PublishMessage {
+        let mut msg = message("orders", data);
+        msg.set_header(
+            headers::MESSAGE_ID,
+            HeaderValues::ShortString(id.to_owned()),
+        );
+        msg
+    }
+
+    fn dedup_publisher(inner: &Arc<TestPublisher>) -> DedupPublisher {
+        DedupPublisher::new(inner.clone(), 10, HOUR)
+    }
+
+    #[test]
+    fn publishes_the_same_id_once_and_new_ids_again() {
+        let inner = Arc::new(TestPublisher::default());
+        let publisher = dedup_publisher(&inner);
+        let ctx = Context::new();
+
+        block_on(publisher.publish(&ctx, &identified("a", "1"))).unwrap();
+        block_on(publisher.publish(&ctx, &identified("a", "2"))).unwrap();
+        block_on(publisher.publish(&ctx, &identified("b", "3"))).unwrap();
+        block_on(publisher.publish(&ctx, &message("orders", "4"))).unwrap();
+        block_on(publisher.publish(&ctx, &message("orders", "5"))).unwrap();
+
+        assert_eq!(inner.published_data(), ["1", "3", "4", "5"]);
+    }
+
+    #[test]
+    fn duplicate_publish_confirmed_returns_an_unconfirmed_receipt() {
+        let inner = Arc::new(TestPublisher::default());
+        let publisher = dedup_publisher(&inner);
+        let ctx = Context::new();
+
+        block_on(publisher.publish(&ctx, &identified("a", "1"))).unwrap();
+        let receipt = block_on(publisher.publish_confirmed(&ctx, &identified("a", "2"))).unwrap();
+
+        assert_eq!(receipt, PublishReceipt::default());
+        assert_eq!(inner.published_data(), ["1"]);
+    }
+
+    #[test]
+    fn failed_publish_does_not_mark_the_id() {
+        let inner = Arc::new(TestPublisher::default());
+        let publisher = dedup_publisher(&inner);
+        let ctx = Context::new();
+
+        inner.fail_with(Some(MessagingError::PublisherError { detail: None }));
+        assert_eq!(
+            block_on(publisher.publish(&ctx, &identified("a", "1"))),
+            Err(MessagingError::PublisherError { detail: None })
+        );
+
+        inner.fail_with(None);
+        block_on(publisher.publish(&ctx, &identified("a", "2"))).unwrap();
+        assert_eq!(inner.published_data(), ["2"]);
+    }
+
+    #[test]
+    fn concurrent_duplicate_waits_for_the_publish_in_flight() {
+        let inner = Arc::new(TestPublisher::held());
+        let publisher = dedup_publisher(&inner);
+        let ctx = Context::new();
+        let (first, second) = (identified("a", "1"), identified("a", "2"));
+
+        let mut publishing = pin!(publisher.publish(&ctx, &first));
+        let mut duplicate = pin!(publisher.publish(&ctx, &second));
+        assert!(poll_once(publishing.as_mut()).is_pending());
+        assert!(poll_once(duplicate.as_mut()).is_pending());
+
+        inner.release();
+        assert!(poll_once(duplicate.as_mut()).is_pending());
+        assert_eq!(poll_once(publishing.as_mut()), Poll::Ready(Ok(())));
+        assert_eq!(poll_once(duplicate.as_mut()), Poll::Ready(Ok(())));
+        assert_eq!(inner.published_data(), ["1"]);
+    }
+
+    #[test]
+    fn concurrent_duplicate_publishes_when_the_publish_in_flight_fails() {
+        let inner = Arc::new(TestPublisher::held());
+        let publisher = dedup_publisher(&inner);
+        let ctx = Context::new();
+        let (first, second) = (identified("a", "1"), identified("a", "2"));
+
+        let mut failing = pin!(publisher.publish(&ctx, &first));
+        let mut duplicate = pin!(publisher.publish(&ctx, &second));
+        assert!(poll_once(failing.as_mut()).is_pending());
+        assert!(poll_once(duplicate.as_mut()).is_pending());
+
+        inner.fail_with(Some(MessagingError::PublisherError { detail: None }));
+        inner.release();
+        assert_eq!(
+            poll_once(failing.as_mut()),
+            Poll::Ready(Err(MessagingError::PublisherError { detail: None }))
+        );
+
+        inner.fail_with(None);
+        assert_eq!(poll_once(duplicate.as_mut()), Poll::Ready(Ok(())));
+        assert_eq!(inner.published_data(), ["2"]);
+    }
+
+    #[test]
+    fn cancelled_publish_does_not_mark_the_id() {
+        let inner = Arc::new(TestPublisher::held());
+        let publisher = dedup_publisher(&inner);
+        let ctx = Context::new();
+        let first = identified("a", "1");
+        {
+            let mut cancelled = pin!(publisher.publish(&ctx, &first));
+            assert!(poll_once(cancelled.as_mut()).is_pending());
+        }
+
+        inner.release();
+        block_on(publisher.publish(&ctx, &identified("a", "2"))).unwrap();
+        assert_eq!(inner.published_data(), ["2"]);
+    }
+}
@@ -0,0 +1,131 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Default Headers
+//!
+//! This module provides a publisher middleware that adds a fixed set of headers to every
+//! message, e.g. the name, environment and version of the publishing service.
+
+use crate::{
+    errors::MessagingError,
+    publisher::{HeaderValues, PublishMessage, PublishReceipt, Publisher, delegate_publisher},
+};
+use async_trait::async_trait;
+use opentelemetry::Context;
+use std::{collections::HashMap, sync::Arc};
+
+/// A publisher middleware that merges default headers into every message.
+///
+/// Defaults only fill in missing keys: a header already set on the message always takes
+/// precedence over the default for the same key.
+pub struct WithDefaultHeaders {
+    inner: Arc<dyn Publisher>,
+    defaults: HashMap<String, HeaderValues>,
+}
+
+impl WithDefaultHeaders {
+    /// Creates a new default-headers publisher.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The publisher to send messages to.
+    /// * `defaults` - The headers added to messages that do not set them, e.g. a `HashMap`
+    ///   or a [`Headers`](crate::headers::Headers) builder.
+    ///
+    /// # Returns
+    ///
+    /// A new `WithDefaultHeaders` instance.
+    pub fn new<H>(inner: Arc<dyn Publisher>, defaults: H) -> Self
+    where
+        H: Into<HashMap<String, HeaderValues>>,
+    {
+        WithDefaultHeaders {
+            inner,
+            defaults: defaults.into(),
+        }
+    }
+
+    fn merge(&self, msg: &PublishMessage) -> PublishMessage {
+        let mut merged = msg.clone();
+        if self.defaults.is_empty() {
+            return merged;
+        }
+
+        let headers = merged.ensure_headers();
+        for (key, value) in &self.defaults {
+            headers.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+
+        merged
+    }
+}
+
+#[async_trait]
+impl Publisher for WithDefaultHeaders {
+    async fn publish(&self, ctx: &Context, msg: &PublishMessage) -> Result<(), MessagingError> {
+        self.inner.publish(ctx, &self.merge(msg)).await
+    }
+
+    async fn publish_confirmed(
+        &self,
+        ctx: &Context,
+        msg: &PublishMessage,
+    ) -> Result<PublishReceipt, MessagingError> {
+        self.inner.publish_confirmed(ctx, &self.merge(msg)).await
+    }
+
+    delegate_publisher!(inner);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        headers::Headers,
+        test_support::{TestPublisher, block_on, message},
+    };
+
+    fn publish(defaults: Headers, msg: &PublishMessage) -> Option<HashMap<String, HeaderValues>> {
+        let inner = Arc::new(TestPublisher::default());
+        let publisher = WithDefaultHeaders::new(inner.clone(), defaults);
+        block_on(publisher.publish(&Context::new(), msg)).unwrap();
+        inner.published().remove(0).headers
+    }
+
+    #[test]
+    fn caller_headers_win_over_defaults() {
+        let mut msg = message("orders", "data");
+        msg.set_header("tenant", "acme");
+
+        let headers = publish(Headers::new().str("tenant", "default"), &msg).unwrap();
+
+        assert_eq!(headers["tenant"], HeaderValues::from("acme"));
+    }
+
+    #[test]
+    fn defaults_fill_in_missing_keys() {
+        let mut msg = message("orders", "data");
+        msg.set_header("tenant", "acme");
+
+        let headers = publish(Headers::new().str("service", "billing"), &msg).unwrap();
+
+        assert_eq!(headers.len(), 2);
+        assert_eq!(
+            headers["service"],
+            HeaderValues::LongString("billing".to_owned())
+        );
+    }
+
+    #[test]
+    fn defaults_apply_to_messages_without_headers() {
+        let headers = publish(Headers::new().int("version", 2), &message("orders", "data"));
+
+        assert_eq!(headers.unwrap()["version"], HeaderValues::LongLongInt(2));
+    }
+
+    #[test]
+    fn no_defaults_leave_messages_unchanged() {
+        assert_eq!(publish(Headers::new(), &message("orders", "data")), None);
+    }
+}
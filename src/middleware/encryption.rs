@@ -0,0 +1,268 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Encryption
+//!
+//! This module provides transparent encryption of message data.
+//!
+//! The [`EncryptingPublisher`] encrypts `data` with a [`Cipher`] and stores the nonce in the
+//! `x-encryption-nonce` header, and the [`DecryptingHandler`] verifies and decrypts it on
+//! consume. The cipher is pluggable and is expected to be an authenticated one, such as
+//! AES-GCM, so that tampered data is detected.
+
+use crate::{
+    errors::MessagingError,
+    handler::{ConsumerHandler, ConsumerMessage},
+    headers,
+    publisher::{HeaderValues, PublishMessage, PublishReceipt, Publisher, delegate_publisher},
+};
+use async_trait::async_trait;
+use opentelemetry::Context;
+use std::{fmt::Write, sync::Arc};
+
+#[cfg(feature = "mocks")]
+use mockall::*;
+
+/// Defines an authenticated cipher for message data.
+#[cfg_attr(feature = "mocks", automock)]
+pub trait Cipher: Send + Sync {
+    /// Encrypts message data with a fresh nonce.
+    ///
+    /// # Arguments
+    ///
+    /// * `plaintext` - The data to encrypt.
+    ///
+    /// # Returns
+    ///
+    /// The nonce and the ciphertext, or `MessagingError::SerializingError` on failure.
+    fn encrypt(&self, plaintext: &[u8]) -> Result<(Vec<u8>, Vec<u8>), MessagingError>;
+
+    /// Verifies and decrypts message data.
+    ///
+    /// # Arguments
+    ///
+    /// * `nonce` - The nonce used to encrypt the data.
+    /// * `ciphertext` - The data to decrypt.
+    ///
+    /// # Returns
+    ///
+    /// The plaintext, or an error if the data fails authentication.
+    fn decrypt(&self, nonce: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, MessagingError>;
+}
+
+/// A publisher middleware that encrypts message data before publishing.
+pub struct EncryptingPublisher {
+    inner: Arc<dyn Publisher>,
+    cipher: Arc<dyn Cipher>,
+}
+
+impl EncryptingPublisher {
+    /// Creates a new encrypting publisher.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The publisher to send encrypted messages to.
+    /// * `cipher` - The cipher used to encrypt data.
+    ///
+    /// # Returns
+    ///
+    /// A new `EncryptingPublisher` instance.
+    pub fn new(inner: Arc<dyn Publisher>, cipher: Arc<dyn Cipher>) -> Self {
+        EncryptingPublisher { inner, cipher }
+    }
+
+    fn encrypt(&self, msg: &PublishMessage) -> Result<PublishMessage, MessagingError> {
+        let (nonce, ciphertext) = self.cipher.encrypt(&msg.data)?;
+
+        let mut encrypted = msg.clone();
+        encrypted.data = ciphertext.into();
+        encrypted.set_header(
+            headers::ENCRYPTION_NONCE,
+            HeaderValues::ShortString(encode_hex(&nonce)),
+        );
+
+        Ok(encrypted)
+    }
+}
+
+#[async_trait]
+impl Publisher for EncryptingPublisher {
+    async fn publish(&self, ctx: &Context, msg: &PublishMessage) -> Result<(), MessagingError> {
+        self.inner.publish(ctx, &self.encrypt(msg)?).await
+    }
+
+    async fn publish_confirmed(
+        &self,
+        ctx: &Context,
+        msg: &PublishMessage,
+    ) -> Result<PublishReceipt, MessagingError> {
+        self.inner.publish_confirmed(ctx, &self.encrypt(msg)?).await
+    }
+
+    delegate_publisher!(inner);
+}
+
+/// A consumer middleware that decrypts message data before invoking the inner handler.
+///
+/// Every message must carry a valid `x-encryption-nonce` header and pass authentication;
+/// otherwise it is rejected with `MessagingError::DeserializingError` and the inner handler
+/// is not invoked.
+pub struct DecryptingHandler {
+    inner: Arc<dyn ConsumerHandler>,
+    cipher: Arc<dyn Cipher>,
+}
+
+impl DecryptingHandler {
+    /// Creates a new decrypting handler.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The handler to invoke with decrypted messages.
+    /// * `cipher` - The cipher used to decrypt data.
+    ///
+    /// # Returns
+    ///
+    /// A new `DecryptingHandler` instance.
+    pub fn new(inner: Arc<dyn ConsumerHandler>, cipher: Arc<dyn Cipher>) -> Self {
+        DecryptingHandler { inner, cipher }
+    }
+}
+
+#[async_trait]
+impl ConsumerHandler for DecryptingHandler {
+    async fn exec(&self, ctx: &Context, msg: &ConsumerMessage) -> Result<(), MessagingError> {
+        let nonce = msg
+            .header(headers::ENCRYPTION_NONCE)
+            .and_then(decode_hex)
+            .ok_or(MessagingError::DeserializingError)?;
+
+        let plaintext = self
+            .cipher
+            .decrypt(&nonce, &msg.data)
+            .map_err(|_| MessagingError::DeserializingError)?;
+
+        let mut decrypted = msg.clone();
+        decrypted.data = plaintext.into();
+        if let Some(map) = decrypted.headers.as_mut() {
+            map.remove(headers::ENCRYPTION_NONCE);
+        }
+
+        self.inner.exec(ctx, &decrypted).await
+    }
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut hex, byte| {
+        let _ = write!(hex, "{byte:02x}");
+        hex
+    })
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{TestHandler, TestPublisher, block_on, delivered, message};
+
+    /// A toy cipher XOR-ing data with the first nonce byte and appending the second one as
+    /// authentication tag.
+    struct Xor;
+
+    impl Cipher for Xor {
+        fn encrypt(&self, plaintext: &[u8]) -> Result<(Vec<u8>, Vec<u8>), MessagingError> {
+            let nonce = vec![0x5a, 0x0f];
+            let mut ciphertext: Vec<u8> = plaintext.iter().map(|b| b ^ nonce[0]).collect();
+            ciphertext.push(nonce[1]);
+            Ok((nonce, ciphertext))
+        }
+
+        fn decrypt(&self, nonce: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, MessagingError> {
+            match (nonce, ciphertext.split_last()) {
+                ([key, tag], Some((last, data))) if last == tag => {
+                    Ok(data.iter().map(|b| b ^ key).collect())
+                }
+                _ => Err(MessagingError::InternalError),
+            }
+        }
+    }
+
+    fn publish(msg: &PublishMessage) -> PublishMessage {
+        let inner = Arc::new(TestPublisher::default());
+        let publisher = EncryptingPublisher::new(inner.clone(), Arc::new(Xor));
+        block_on(publisher.publish(&Context::new(), msg)).unwrap();
+        inner.published().remove(0)
+    }
+
+    fn consume(msg: &ConsumerMessage) -> (Result<(), MessagingError>, Vec<ConsumerMessage>) {
+        let inner = Arc::new(TestHandler::default());
+        let handler = DecryptingHandler::new(inner.clone(), Arc::new(Xor));
+        let result = block_on(handler.exec(&Context::new(), msg));
+        (result, inner.received())
+    }
+
+    #[test]
+    fn encrypts_data_and_sets_the_nonce() {
+        let published = publish(&message("orders", "data"));
+
+        assert_ne!(&*published.data, b"data");
+        assert_eq!(
+            published.headers.unwrap()[headers::ENCRYPTION_NONCE],
+            HeaderValues::ShortString("5a0f".to_owned())
+        );
+    }
+
+    #[test]
+    fn round_trips_encrypted_messages() {
+        let (result, received) = consume(&delivered(&publish(&message("orders", "data"))));
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(&*received[0].data, b"data");
+        assert_eq!(received[0].header(headers::ENCRYPTION_NONCE), None);
+    }
+
+    #[test]
+    fn rejects_tampered_messages() {
+        let mut msg = delivered(&publish(&message("orders", "data")));
+        let mut data = msg.data.to_vec();
+        *data.last_mut().unwrap() ^= 1;
+        msg.data = data.into();
+
+        let (result, received) = consume(&msg);
+
+        assert_eq!(result, Err(MessagingError::DeserializingError));
+        assert!(received.is_empty());
+    }
+
+    #[test]
+    fn rejects_messages_without_a_valid_nonce() {
+        let mut msg = delivered(&publish(&message("orders", "data")));
+        for nonce in [None, Some("5a0"), Some("zz0f")] {
+            match nonce {
+                Some(nonce) => msg.set_header(headers::ENCRYPTION_NONCE, nonce),
+                None => msg.remove_header(headers::ENCRYPTION_NONCE),
+            };
+
+            let (result, received) = consume(&msg);
+
+            assert_eq!(result, Err(MessagingError::DeserializingError));
+            assert!(received.is_empty());
+        }
+    }
+
+    #[test]
+    fn hex_round_trips() {
+        assert_eq!(encode_hex(&[0x00, 0xff, 0x10]), "00ff10");
+        assert_eq!(decode_hex("00ff10"), Some(vec![0x00, 0xff, 0x10]));
+    }
+}
@@ -0,0 +1,139 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Error Hook
+//!
+//! This module provides a consumer middleware that reports handler failures to callbacks.
+//!
+//! Wrapping handlers in an [`ErrorHookHandler`] centralizes error reporting, e.g. to logs
+//! or an error tracker, instead of repeating it in every handler.
+
+use crate::{
+    errors::MessagingError,
+    handler::{ConsumerHandler, ConsumerMessage},
+};
+use async_trait::async_trait;
+use opentelemetry::Context;
+use std::sync::Arc;
+
+type ErrorCallback = Box<dyn Fn(&ConsumerMessage, &MessagingError) + Send + Sync>;
+
+/// A consumer middleware that invokes callbacks when the inner handler fails.
+///
+/// The callbacks run in registration order with the failed message and its error, before
+/// the error is returned to the dispatcher for ack or nack disposition. They cannot change
+/// the outcome: the error is always returned unchanged.
+pub struct ErrorHookHandler {
+    inner: Arc<dyn ConsumerHandler>,
+    callbacks: Vec<ErrorCallback>,
+}
+
+impl ErrorHookHandler {
+    /// Creates a new error hook handler without callbacks.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The handler to process messages.
+    ///
+    /// # Returns
+    ///
+    /// A new `ErrorHookHandler` instance.
+    pub fn new(inner: Arc<dyn ConsumerHandler>) -> Self {
+        ErrorHookHandler {
+            inner,
+            callbacks: Vec::new(),
+        }
+    }
+
+    /// Adds a callback invoked whenever the inner handler returns an error.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The callback receiving the failed message and the error.
+    ///
+    /// # Returns
+    ///
+    /// The updated `ErrorHookHandler`.
+    pub fn on_error<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&ConsumerMessage, &MessagingError) + Send + Sync + 'static,
+    {
+        self.callbacks.push(Box::new(f));
+        self
+    }
+}
+
+#[async_trait]
+impl ConsumerHandler for ErrorHookHandler {
+    async fn exec(&self, ctx: &Context, msg: &ConsumerMessage) -> Result<(), MessagingError> {
+        let result = self.inner.exec(ctx, msg).await;
+
+        if let Err(err) = &result {
+            self.callbacks
+                .iter()
+                .for_each(|callback| callback(msg, err));
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{TestHandler, block_on, delivered, message};
+    use std::sync::Mutex;
+
+    type Observed = Arc<Mutex<Vec<(String, String, MessagingError)>>>;
+
+    fn observing(handler: TestHandler, observed: &Observed, label: &str) -> ErrorHookHandler {
+        let (first, second) = (observed.clone(), observed.clone());
+        let (first_label, second_label) = (format!("{label}-1"), format!("{label}-2"));
+        ErrorHookHandler::new(Arc::new(handler))
+            .on_error(move |msg, err| {
+                first.lock().unwrap().push((
+                    first_label.clone(),
+                    msg.data_string().unwrap(),
+                    err.clone(),
+                ))
+            })
+            .on_error(move |msg, err| {
+                second.lock().unwrap().push((
+                    second_label.clone(),
+                    msg.data_string().unwrap(),
+                    err.clone(),
+                ))
+            })
+    }
+
+    #[test]
+    fn callbacks_observe_the_message_and_error_in_order() {
+        let observed = Observed::default();
+        let failure = MessagingError::consumer("boom");
+        let handler = observing(TestHandler::failing(failure.clone()), &observed, "hook");
+
+        let msg = delivered(&message("orders", "created"));
+        let result = block_on(handler.exec(&Context::new(), &msg));
+
+        assert_eq!(result, Err(failure.clone()));
+        assert_eq!(
+            *observed.lock().unwrap(),
+            [
+                ("hook-1".to_owned(), "created".to_owned(), failure.clone()),
+                ("hook-2".to_owned(), "created".to_owned(), failure),
+            ]
+        );
+    }
+
+    #[test]
+    fn callbacks_are_not_invoked_on_success() {
+        let observed = Observed::default();
+        let handler = observing(TestHandler::default(), &observed, "hook");
+
+        let msg = delivered(&message("orders", "created"));
+
+        assert_eq!(block_on(handler.exec(&Context::new(), &msg)), Ok(()));
+        assert!(observed.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,99 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Filter
+//!
+//! This module provides a consumer middleware that skips unwanted messages.
+//!
+//! Wrapping a handler in a [`FilterHandler`] drops messages matching no business need,
+//! e.g. those of a deprecated producer, before the handler sees them.
+
+use crate::{
+    errors::MessagingError,
+    handler::{ConsumerHandler, ConsumerMessage},
+};
+use async_trait::async_trait;
+use opentelemetry::Context;
+use std::sync::Arc;
+
+type Predicate = Box<dyn Fn(&ConsumerMessage) -> bool + Send + Sync>;
+
+/// A consumer middleware that only passes messages accepted by a predicate to the inner
+/// handler.
+///
+/// A message rejected by the predicate is not handed to the inner handler and `Ok(())` is
+/// returned, so filtering counts as successful processing: the dispatcher acknowledges
+/// the message and it is not redelivered.
+pub struct FilterHandler {
+    inner: Arc<dyn ConsumerHandler>,
+    predicate: Predicate,
+}
+
+impl FilterHandler {
+    /// Creates a new filtering handler.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The handler to process accepted messages.
+    /// * `predicate` - The function returning `true` for messages to process.
+    ///
+    /// # Returns
+    ///
+    /// A new `FilterHandler` instance.
+    pub fn new<F>(inner: Arc<dyn ConsumerHandler>, predicate: F) -> Self
+    where
+        F: Fn(&ConsumerMessage) -> bool + Send + Sync + 'static,
+    {
+        FilterHandler {
+            inner,
+            predicate: Box::new(predicate),
+        }
+    }
+}
+
+#[async_trait]
+impl ConsumerHandler for FilterHandler {
+    async fn exec(&self, ctx: &Context, msg: &ConsumerMessage) -> Result<(), MessagingError> {
+        if !(self.predicate)(msg) {
+            return Ok(());
+        }
+
+        self.inner.exec(ctx, msg).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{TestHandler, block_on};
+
+    fn from_orders(inner: &Arc<TestHandler>) -> FilterHandler {
+        FilterHandler::new(inner.clone(), |msg| msg.from == "orders")
+    }
+
+    #[test]
+    fn passes_accepted_messages_through() {
+        let inner = Arc::new(TestHandler::failing(MessagingError::HandlerError));
+        let handler = from_orders(&inner);
+
+        let msg = ConsumerMessage::new("orders", "created", b"data", None);
+
+        assert_eq!(
+            block_on(handler.exec(&Context::new(), &msg)),
+            Err(MessagingError::HandlerError)
+        );
+        assert_eq!(inner.received(), [msg]);
+    }
+
+    #[test]
+    fn acknowledges_filtered_messages_without_handling_them() {
+        let inner = Arc::new(TestHandler::failing(MessagingError::HandlerError));
+        let handler = from_orders(&inner);
+
+        let msg = ConsumerMessage::new("legacy", "created", b"data", None);
+
+        assert_eq!(block_on(handler.exec(&Context::new(), &msg)), Ok(()));
+        assert!(inner.received().is_empty());
+    }
+}
@@ -0,0 +1,120 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Header Validation
+//!
+//! This module provides a publisher middleware that rejects messages with invalid headers
+//! before they reach the broker client.
+//!
+//! Failing early with a `SerializingError` naming the invalid header is easier to diagnose
+//! than an error raised deep inside a broker protocol implementation.
+
+use crate::{
+    errors::MessagingError,
+    headers::{HeaderLimits, validate_headers_with},
+    publisher::{PublishMessage, PublishReceipt, Publisher, delegate_publisher},
+};
+use async_trait::async_trait;
+use opentelemetry::Context;
+use std::sync::Arc;
+
+/// A publisher middleware that validates headers with
+/// [`validate_headers_with`](crate::headers::validate_headers_with) before publishing.
+///
+/// The default [`HeaderLimits`] apply unless others are set with
+/// [`HeaderValidatingPublisher::with_limits`].
+pub struct HeaderValidatingPublisher {
+    inner: Arc<dyn Publisher>,
+    limits: HeaderLimits,
+}
+
+impl HeaderValidatingPublisher {
+    /// Creates a new header-validating publisher.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The publisher to send valid messages to.
+    ///
+    /// # Returns
+    ///
+    /// A new `HeaderValidatingPublisher` instance.
+    pub fn new(inner: Arc<dyn Publisher>) -> Self {
+        HeaderValidatingPublisher {
+            inner,
+            limits: HeaderLimits::default(),
+        }
+    }
+
+    /// Sets the limits on the header count and size.
+    ///
+    /// # Arguments
+    ///
+    /// * `limits` - The limits every message must stay within.
+    ///
+    /// # Returns
+    ///
+    /// The updated `HeaderValidatingPublisher`.
+    pub fn with_limits(mut self, limits: HeaderLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    fn validate(&self, msg: &PublishMessage) -> Result<(), MessagingError> {
+        msg.headers.as_ref().map_or(Ok(()), |headers| {
+            validate_headers_with(headers, &self.limits)
+        })
+    }
+}
+
+#[async_trait]
+impl Publisher for HeaderValidatingPublisher {
+    async fn publish(&self, ctx: &Context, msg: &PublishMessage) -> Result<(), MessagingError> {
+        self.validate(msg)?;
+        self.inner.publish(ctx, msg).await
+    }
+
+    async fn publish_confirmed(
+        &self,
+        ctx: &Context,
+        msg: &PublishMessage,
+    ) -> Result<PublishReceipt, MessagingError> {
+        self.validate(msg)?;
+        self.inner.publish_confirmed(ctx, msg).await
+    }
+
+    delegate_publisher!(inner);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        publisher::HeaderValues,
+        test_support::{TestPublisher, block_on, message},
+    };
+
+    #[test]
+    fn rejects_invalid_headers_before_publishing() {
+        let inner = Arc::new(TestPublisher::default());
+        let publisher = HeaderValidatingPublisher::new(inner.clone());
+        let mut msg = message("orders", "1");
+        msg.set_header("", HeaderValues::from("value"));
+
+        let err = block_on(publisher.publish(&Context::new(), &msg)).unwrap_err();
+        assert_eq!(err.detail(), Some("header key is empty"));
+        assert!(inner.published().is_empty());
+    }
+
+    #[test]
+    fn publishes_valid_headers() {
+        let inner = Arc::new(TestPublisher::default());
+        let publisher = HeaderValidatingPublisher::new(inner.clone());
+        let mut msg = message("orders", "1");
+        msg.set_header("x-tenant", HeaderValues::from("acme"));
+
+        block_on(publisher.publish(&Context::new(), &msg)).unwrap();
+        block_on(publisher.publish(&Context::new(), &message("orders", "2"))).unwrap();
+        assert_eq!(inner.published_data(), ["1", "2"]);
+    }
+}
@@ -0,0 +1,264 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Max Retries
+//!
+//! This module provides a consumer middleware that caps the delivery attempts of a message.
+//!
+//! Under at-least-once delivery a permanently failing message is requeued forever. The
+//! [`MaxRetriesHandler`] counts the attempts of every message and, once the cap is reached,
+//! dead-letters it instead of letting it be requeued again.
+
+use crate::{
+    errors::MessagingError,
+    handler::{ConsumerHandler, ConsumerMessage},
+    middleware::{cache::BoundedCache, dead_letter::dead_letter},
+    publisher::Publisher,
+};
+use async_trait::async_trait;
+use opentelemetry::Context;
+use std::{
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::{Duration, Instant},
+};
+
+/// The default maximum number of messages whose attempts are tracked in memory.
+pub const DEFAULT_TRACKED_MESSAGES: usize = 10_000;
+
+/// The default time after its last attempt for which the attempts of a message are kept.
+pub const DEFAULT_TRACKING_TTL: Duration = Duration::from_secs(3600);
+
+/// A consumer middleware that dead-letters a message after a number of failed attempts.
+///
+/// The attempt number is taken from [`ConsumerMessage::delivery_count`] when the broker
+/// provides it, counting the current delivery. Otherwise attempts are tracked in memory by message
+/// id; messages carrying neither are seen as a first attempt on every delivery. The
+/// in-memory count of a message is dropped once it succeeds or is dead-lettered. The
+/// tracked counts are bounded like a dedup cache: at most [`DEFAULT_TRACKED_MESSAGES`]
+/// messages are tracked, evicting the least recently attempted, and a count expires
+/// [`DEFAULT_TRACKING_TTL`] after its last attempt. An evicted or expired message starts
+/// over with its first attempt; see [`MaxRetriesHandler::with_tracking`].
+///
+/// While attempts remain, the handler error is returned so the dispatcher requeues the
+/// message. On the last attempt, the message is published to the dead-letter target with
+/// the `x-death-reason` header and `Ok(())` is returned so it is acknowledged. If the
+/// dead-letter publish fails, its error is returned instead and the message is requeued.
+pub struct MaxRetriesHandler {
+    inner: Arc<dyn ConsumerHandler>,
+    publisher: Arc<dyn Publisher>,
+    target: String,
+    max_attempts: u32,
+    attempts: Mutex<BoundedCache<u32>>,
+}
+
+impl MaxRetriesHandler {
+    /// Creates a new max-retries handler.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The handler to process messages.
+    /// * `publisher` - The publisher used to send dead-lettered messages.
+    /// * `target` - The dead-letter destination.
+    /// * `max_attempts` - The number of attempts before a message is dead-lettered (at least 1).
+    ///
+    /// # Returns
+    ///
+    /// A new `MaxRetriesHandler` instance.
+    pub fn new<T>(
+        inner: Arc<dyn ConsumerHandler>,
+        publisher: Arc<dyn Publisher>,
+        target: T,
+        max_attempts: u32,
+    ) -> Self
+    where
+        T: Into<String>,
+    {
+        MaxRetriesHandler {
+            inner,
+            publisher,
+            target: target.into(),
+            max_attempts: max_attempts.max(1),
+            attempts: Mutex::new(BoundedCache::new(
+                DEFAULT_TRACKED_MESSAGES,
+                DEFAULT_TRACKING_TTL,
+            )),
+        }
+    }
+
+    /// Sets the bounds of the in-memory attempt tracking, dropping the counts tracked so far.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The maximum number of messages tracked (at least 1).
+    /// * `ttl` - How long the count of a message is kept after its last attempt.
+    ///
+    /// # Returns
+    ///
+    /// The updated `MaxRetriesHandler`.
+    pub fn with_tracking(mut self, capacity: usize, ttl: Duration) -> Self {
+        self.attempts = Mutex::new(BoundedCache::new(capacity, ttl));
+        self
+    }
+
+    fn attempts(&self) -> MutexGuard<'_, BoundedCache<u32>> {
+        self.attempts.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn attempt(&self, msg: &ConsumerMessage) -> u32 {
+        if let Some(count) = msg.delivery_count() {
+            return count.max(1);
+        }
+
+        match msg.message_id() {
+            Some(id) => {
+                let mut attempts = self.attempts();
+                let now = Instant::now();
+                let attempt = attempts.get_mut(id, now).map_or(1, |attempt| *attempt + 1);
+                attempts.insert(id, attempt, now);
+                attempt
+            }
+            None => 1,
+        }
+    }
+
+    fn forget(&self, msg: &ConsumerMessage) {
+        if let Some(id) = msg.message_id() {
+            self.attempts().remove(id);
+        }
+    }
+}
+
+#[async_trait]
+impl ConsumerHandler for MaxRetriesHandler {
+    async fn exec(&self, ctx: &Context, msg: &ConsumerMessage) -> Result<(), MessagingError> {
+        let attempt = self.attempt(msg);
+
+        let Err(err) = self.inner.exec(ctx, msg).await else {
+            self.forget(msg);
+            return Ok(());
+        };
+
+        if attempt < self.max_attempts {
+            return Err(err);
+        }
+
+        let dead_letter = dead_letter(msg, &self.target, &err);
+        self.publisher.publish(ctx, &dead_letter).await?;
+        self.forget(msg);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        handler::fn_handler,
+        middleware::dead_letter::DEATH_REASON_HEADER,
+        publisher::HeaderValues,
+        test_support::{TestPublisher, block_on},
+    };
+
+    fn failing() -> Arc<dyn ConsumerHandler> {
+        fn_handler(|_, _| async { Err(MessagingError::HandlerError) })
+    }
+
+    fn delivery(id: Option<&str>) -> ConsumerMessage {
+        let mut msg = ConsumerMessage::new("orders", "event", b"data", None);
+        msg.message_id = id.map(str::to_owned);
+        msg
+    }
+
+    fn handler(inner: Arc<dyn ConsumerHandler>, dlq: &Arc<TestPublisher>) -> MaxRetriesHandler {
+        MaxRetriesHandler::new(inner, dlq.clone(), "orders.dlq", 3)
+    }
+
+    fn exec(handler: &MaxRetriesHandler, msg: &ConsumerMessage) -> Result<(), MessagingError> {
+        block_on(handler.exec(&Context::new(), msg))
+    }
+
+    #[test]
+    fn dead_letters_after_the_configured_attempts() {
+        let dlq = Arc::new(TestPublisher::default());
+        let handler = handler(failing(), &dlq);
+        let msg = delivery(Some("a"));
+
+        assert_eq!(exec(&handler, &msg), Err(MessagingError::HandlerError));
+        assert_eq!(exec(&handler, &msg), Err(MessagingError::HandlerError));
+        assert!(dlq.published().is_empty());
+
+        assert_eq!(exec(&handler, &msg), Ok(()));
+        let published = dlq.published();
+        assert_eq!(published.len(), 1);
+        assert_eq!(published[0].to, "orders.dlq");
+        assert_eq!(
+            published[0].headers.as_ref().unwrap()[DEATH_REASON_HEADER],
+            HeaderValues::from(MessagingError::HandlerError.to_string())
+        );
+    }
+
+    #[test]
+    fn dead_lettering_restarts_the_count() {
+        let dlq = Arc::new(TestPublisher::default());
+        let handler = handler(failing(), &dlq);
+        let msg = delivery(Some("a"));
+
+        for _ in 0..3 {
+            let _ = exec(&handler, &msg);
+        }
+        assert_eq!(exec(&handler, &msg), Err(MessagingError::HandlerError));
+        assert_eq!(dlq.published().len(), 1);
+    }
+
+    #[test]
+    fn uses_the_broker_delivery_count() {
+        let dlq = Arc::new(TestPublisher::default());
+        let handler = handler(failing(), &dlq);
+        let mut msg = delivery(None);
+        msg.delivery_count = Some(3);
+
+        assert_eq!(exec(&handler, &msg), Ok(()));
+        assert_eq!(dlq.published().len(), 1);
+    }
+
+    #[test]
+    fn messages_without_id_or_count_are_always_a_first_attempt() {
+        let dlq = Arc::new(TestPublisher::default());
+        let handler = handler(failing(), &dlq);
+        let msg = delivery(None);
+
+        for _ in 0..5 {
+            assert_eq!(exec(&handler, &msg), Err(MessagingError::HandlerError));
+        }
+        assert!(dlq.published().is_empty());
+    }
+
+    #[test]
+    fn tracking_is_bounded() {
+        let dlq = Arc::new(TestPublisher::default());
+        let handler = handler(failing(), &dlq).with_tracking(1, DEFAULT_TRACKING_TTL);
+        let (a, b) = (delivery(Some("a")), delivery(Some("b")));
+
+        for _ in 0..3 {
+            assert_eq!(exec(&handler, &a), Err(MessagingError::HandlerError));
+            assert_eq!(exec(&handler, &b), Err(MessagingError::HandlerError));
+        }
+        assert!(dlq.published().is_empty());
+    }
+
+    #[test]
+    fn failed_dead_letter_publish_requeues_the_message() {
+        let dlq = Arc::new(TestPublisher::default());
+        let handler = handler(failing(), &dlq);
+        let mut msg = delivery(None);
+        msg.delivery_count = Some(3);
+
+        dlq.fail_with(Some(MessagingError::PublisherError { detail: None }));
+        assert_eq!(
+            exec(&handler, &msg),
+            Err(MessagingError::PublisherError { detail: None })
+        );
+    }
+}
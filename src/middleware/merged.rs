@@ -0,0 +1,630 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Merged
+//!
+//! This module provides a dispatcher driving several dispatchers at once.
+//!
+//! Applications consuming from several brokers or connections can combine their
+//! dispatchers into a [`MergedDispatcher`] and manage them with a single
+//! `consume_blocking` call and a single shutdown.
+
+use crate::{
+    dispatcher::{Dispatcher, DispatcherDefinition, DispatcherStats, SeekPosition},
+    errors::MessagingError,
+    handler::ConsumerHandler,
+};
+use async_trait::async_trait;
+use std::{
+    future::poll_fn,
+    mem,
+    sync::Arc,
+    task::Poll,
+    time::{Duration, Instant},
+};
+
+/// A dispatcher with its by-value builder methods made callable through a trait object.
+trait Member: Send + Sync {
+    fn dispatcher(&self) -> &dyn Dispatcher;
+
+    fn freeze_boxed(self: Box<Self>) -> Box<dyn Member>;
+
+    fn register_boxed(
+        self: Box<Self>,
+        definition: &DispatcherDefinition,
+        handler: Arc<dyn ConsumerHandler>,
+    ) -> Box<dyn Member>;
+
+    fn with_prefetch_boxed(self: Box<Self>, prefetch: u16) -> Box<dyn Member>;
+
+    fn register_fallback_boxed(
+        self: Box<Self>,
+        handler: Arc<dyn ConsumerHandler>,
+    ) -> Box<dyn Member>;
+}
+
+impl<D> Member for D
+where
+    D: Dispatcher + 'static,
+{
+    fn dispatcher(&self) -> &dyn Dispatcher {
+        self
+    }
+
+    fn freeze_boxed(self: Box<Self>) -> Box<dyn Member> {
+        Box::new(self.freeze())
+    }
+
+    fn register_boxed(
+        self: Box<Self>,
+        definition: &DispatcherDefinition,
+        handler: Arc<dyn ConsumerHandler>,
+    ) -> Box<dyn Member> {
+        Box::new(self.register(definition, handler))
+    }
+
+    fn with_prefetch_boxed(self: Box<Self>, prefetch: u16) -> Box<dyn Member> {
+        Box::new(self.with_prefetch(prefetch))
+    }
+
+    fn register_fallback_boxed(
+        self: Box<Self>,
+        handler: Arc<dyn ConsumerHandler>,
+    ) -> Box<dyn Member> {
+        Box::new(self.register_fallback(handler))
+    }
+}
+
+const NO_MEMBER: &str = "handler registered on a merged dispatcher without members";
+
+/// A dispatcher combining several named dispatchers.
+///
+/// `consume_blocking` runs every member concurrently on the calling task. It returns the
+/// first error reported by a member, abandoning the others, or `Ok(())` once all of them
+/// completed. Lifecycle methods, `pause` and `resume` apply to every member in the order
+/// they were added; lifecycle methods stop at the first error.
+///
+/// `register` and `register_fallback` apply to the most recently added member; use
+/// [`MergedDispatcher::register_on`] to target a member by name. `with_prefetch` and
+/// `freeze` apply to every member, and `seek` to the members subscribed to the definition.
+///
+/// Registering a handler when no member was added, or on a name no member has, does not
+/// panic: the handler is dropped and the misregistration is reported as a
+/// `MessagingError::ConsumerError` with the code `misregistered` by `consume_blocking`.
+#[derive(Default)]
+pub struct MergedDispatcher {
+    members: Vec<(String, Box<dyn Member>)>,
+    misregistered: Option<MessagingError>,
+}
+
+impl MergedDispatcher {
+    /// Creates a new merged dispatcher without members.
+    ///
+    /// # Returns
+    ///
+    /// A new `MergedDispatcher` instance.
+    pub fn new() -> Self {
+        MergedDispatcher::default()
+    }
+
+    /// Adds a member dispatcher.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name identifying the member, e.g. the broker it consumes from.
+    /// * `dispatcher` - The dispatcher to add.
+    ///
+    /// # Returns
+    ///
+    /// The updated `MergedDispatcher`.
+    pub fn add<T, D>(mut self, name: T, dispatcher: D) -> Self
+    where
+        T: Into<String>,
+        D: Dispatcher + 'static,
+    {
+        self.members.push((name.into(), Box::new(dispatcher)));
+        self
+    }
+
+    /// Registers a handler on the member with the given name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the member.
+    /// * `definition` - The dispatcher definition specifying what to subscribe to.
+    /// * `handler` - The handler to process messages matching the definition.
+    ///
+    /// # Returns
+    ///
+    /// The updated `MergedDispatcher`. If no member has the given name, the handler is
+    /// dropped and `consume_blocking` fails.
+    pub fn register_on(
+        self,
+        name: &str,
+        definition: &DispatcherDefinition,
+        handler: Arc<dyn ConsumerHandler>,
+    ) -> Self {
+        let index = self.members.iter().position(|(member, _)| member == name);
+        self.map_member(index, format!("no dispatcher named `{name}`"), |member| {
+            member.register_boxed(definition, handler)
+        })
+    }
+
+    /// Returns the names of the members in the order they were added.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.members.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Applies a builder method to the member at `index`, or records `missing` as the
+    /// misregistration if there is no such member.
+    fn map_member<F>(mut self, index: Option<usize>, missing: String, f: F) -> Self
+    where
+        F: FnOnce(Box<dyn Member>) -> Box<dyn Member>,
+    {
+        let Some(index) = index else {
+            self.misregistered.get_or_insert_with(|| {
+                MessagingError::consumer_with_code(missing, "misregistered".to_owned())
+            });
+            return self;
+        };
+
+        let (name, member) = self.members.remove(index);
+        self.members.insert(index, (name, f(member)));
+        self
+    }
+
+    fn last_member(&self) -> Option<usize> {
+        self.members.len().checked_sub(1)
+    }
+
+    fn dispatchers(&self) -> impl Iterator<Item = &dyn Dispatcher> {
+        self.members.iter().map(|(_, member)| member.dispatcher())
+    }
+}
+
+#[async_trait]
+impl Dispatcher for MergedDispatcher {
+    /// Registers a handler on the most recently added member.
+    ///
+    /// If no member was added, the handler is dropped and `consume_blocking` fails.
+    fn register(
+        self,
+        definition: &DispatcherDefinition,
+        handler: Arc<dyn ConsumerHandler>,
+    ) -> Self {
+        let index = self.last_member();
+        self.map_member(index, NO_MEMBER.to_owned(), |member| {
+            member.register_boxed(definition, handler)
+        })
+    }
+
+    fn register_ref(
+        &mut self,
+        definition: &DispatcherDefinition,
+        handler: Arc<dyn ConsumerHandler>,
+    ) -> &mut Self {
+        *self = mem::take(self).register(definition, handler);
+        self
+    }
+
+    fn with_prefetch(self, prefetch: u16) -> Self {
+        MergedDispatcher {
+            members: self
+                .members
+                .into_iter()
+                .map(|(name, member)| (name, member.with_prefetch_boxed(prefetch)))
+                .collect(),
+            misregistered: self.misregistered,
+        }
+    }
+
+    fn freeze(self) -> Self {
+        MergedDispatcher {
+            members: self
+                .members
+                .into_iter()
+                .map(|(name, member)| (name, member.freeze_boxed()))
+                .collect(),
+            misregistered: self.misregistered,
+        }
+    }
+
+    /// Returns `true` if every member is frozen.
+    fn is_frozen(&self) -> bool {
+        self.dispatchers().all(|dispatcher| dispatcher.is_frozen())
+    }
+
+    /// Registers the fallback handler on the most recently added member.
+    ///
+    /// If no member was added, the handler is dropped and `consume_blocking` fails.
+    fn register_fallback(self, handler: Arc<dyn ConsumerHandler>) -> Self {
+        let index = self.last_member();
+        self.map_member(index, NO_MEMBER.to_owned(), |member| {
+            member.register_fallback_boxed(handler)
+        })
+    }
+
+    /// Runs every member, or fails without running any with the first misregistration, or
+    /// with `MessagingError::InternalError` if a member is not frozen.
+    async fn consume_blocking(&self) -> Result<(), MessagingError> {
+        if let Some(err) = &self.misregistered {
+            return Err(err.clone());
+        }
+        if !self.is_frozen() {
+            return Err(MessagingError::InternalError);
+        }
+
+        let mut running: Vec<_> = self
+            .dispatchers()
+            .map(|dispatcher| Some(dispatcher.consume_blocking()))
+            .collect();
+
+        poll_fn(|cx| {
+            for slot in running.iter_mut() {
+                if let Some(consume) = slot
+                    && let Poll::Ready(result) = consume.as_mut().poll(cx)
+                {
+                    *slot = None;
+                    result?;
+                }
+            }
+
+            if running.iter().all(Option::is_none) {
+                Poll::Ready(Ok(()))
+            } else {
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    async fn connect(&self) -> Result<(), MessagingError> {
+        for dispatcher in self.dispatchers() {
+            dispatcher.connect().await?;
+        }
+        Ok(())
+    }
+
+    async fn disconnect(&self) -> Result<(), MessagingError> {
+        for dispatcher in self.dispatchers() {
+            dispatcher.disconnect().await?;
+        }
+        Ok(())
+    }
+
+    async fn health_check(&self) -> Result<(), MessagingError> {
+        for dispatcher in self.dispatchers() {
+            dispatcher.health_check().await?;
+        }
+        Ok(())
+    }
+
+    /// Drains every member in turn, sharing the timeout between them.
+    async fn drain(&self, timeout: Duration) -> Result<(), MessagingError> {
+        let deadline = Instant::now() + timeout;
+        for dispatcher in self.dispatchers() {
+            dispatcher
+                .drain(deadline.saturating_duration_since(Instant::now()))
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Lists the destinations of every member supporting it, sorted and without duplicates.
+    ///
+    /// Fails like the default implementation if no member supports it.
+    async fn list_destinations(&self) -> Result<Vec<String>, MessagingError> {
+        let mut destinations = None::<Vec<String>>;
+        for dispatcher in self.dispatchers() {
+            match dispatcher.list_destinations().await {
+                Ok(names) => destinations.get_or_insert_with(Vec::new).extend(names),
+                Err(MessagingError::InternalError) => {}
+                Err(err) => return Err(err),
+            }
+        }
+
+        let mut destinations = destinations.ok_or(MessagingError::InternalError)?;
+        destinations.sort();
+        destinations.dedup();
+        Ok(destinations)
+    }
+
+    /// Seeks the members subscribed to a definition with the same name and message type.
+    ///
+    /// Fails like the default implementation if no member is subscribed to it.
+    async fn seek(
+        &self,
+        definition: &DispatcherDefinition,
+        position: SeekPosition,
+    ) -> Result<(), MessagingError> {
+        let mut subscribed = self.dispatchers().filter(|dispatcher| {
+            dispatcher.definitions().iter().any(|registered| {
+                registered.name == definition.name && registered.msg_type == definition.msg_type
+            })
+        });
+
+        let Some(first) = subscribed.next() else {
+            return Err(MessagingError::consumer_with_code(
+                "seeking is not supported",
+                "unsupported",
+            ));
+        };
+
+        first.seek(definition, position).await?;
+        for dispatcher in subscribed {
+            dispatcher.seek(definition, position).await?;
+        }
+        Ok(())
+    }
+
+    fn stats(&self) -> DispatcherStats {
+        DispatcherStats {
+            requeue_rate: self
+                .dispatchers()
+                .map(|dispatcher| dispatcher.stats().requeue_rate)
+                .sum(),
+        }
+    }
+
+    fn definitions(&self) -> Vec<DispatcherDefinition> {
+        self.dispatchers()
+            .flat_map(|dispatcher| dispatcher.definitions())
+            .collect()
+    }
+
+    fn handler_count(&self) -> usize {
+        self.dispatchers()
+            .map(|dispatcher| dispatcher.handler_count())
+            .sum()
+    }
+
+    async fn pause(&self) {
+        for dispatcher in self.dispatchers() {
+            dispatcher.pause().await;
+        }
+    }
+
+    async fn resume(&self) {
+        for dispatcher in self.dispatchers() {
+            dispatcher.resume().await;
+        }
+    }
+
+    /// Returns `true` if there is at least one member and all members are paused.
+    fn is_paused(&self) -> bool {
+        !self.members.is_empty() && self.dispatchers().all(|dispatcher| dispatcher.is_paused())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "mocks")]
+    use crate::dispatcher::MockDispatcher;
+    use crate::{
+        handler::fn_handler,
+        noop::NoopDispatcher,
+        test_support::{CapturingDispatcher, TestHandler, block_on},
+    };
+
+    /// A dispatcher that only consumes once frozen.
+    #[derive(Default)]
+    struct Barrier {
+        frozen: bool,
+    }
+
+    #[async_trait]
+    impl Dispatcher for Barrier {
+        fn register(
+            self,
+            _definition: &DispatcherDefinition,
+            _handler: Arc<dyn ConsumerHandler>,
+        ) -> Self {
+            Barrier { frozen: false }
+        }
+
+        fn register_ref(
+            &mut self,
+            _definition: &DispatcherDefinition,
+            _handler: Arc<dyn ConsumerHandler>,
+        ) -> &mut Self {
+            self.frozen = false;
+            self
+        }
+
+        fn freeze(self) -> Self {
+            Barrier { frozen: true }
+        }
+
+        fn is_frozen(&self) -> bool {
+            self.frozen
+        }
+
+        async fn consume_blocking(&self) -> Result<(), MessagingError> {
+            if self.frozen {
+                Ok(())
+            } else {
+                Err(MessagingError::InternalError)
+            }
+        }
+    }
+
+    fn merged() -> MergedDispatcher {
+        MergedDispatcher::new()
+            .add("noop", NoopDispatcher::new().returning_immediately())
+            .add("barrier", Barrier::default())
+            .register(
+                &DispatcherDefinition::new("orders", None),
+                fn_handler(|_, _| async { Ok(()) }),
+            )
+    }
+
+    #[test]
+    fn consume_fails_until_every_member_is_frozen() {
+        let dispatcher = merged();
+
+        assert!(!dispatcher.is_frozen());
+        assert_eq!(
+            block_on(dispatcher.consume_blocking()),
+            Err(MessagingError::InternalError)
+        );
+    }
+
+    #[test]
+    fn freeze_applies_to_every_member() {
+        let dispatcher = merged().freeze();
+
+        assert!(dispatcher.is_frozen());
+        assert_eq!(block_on(dispatcher.consume_blocking()), Ok(()));
+    }
+
+    fn definition(name: &str) -> DispatcherDefinition {
+        DispatcherDefinition::new(name, None)
+    }
+
+    fn names(dispatcher: &CapturingDispatcher) -> Vec<String> {
+        dispatcher
+            .registrations()
+            .into_iter()
+            .map(|definition| definition.name)
+            .collect()
+    }
+
+    #[test]
+    fn registers_on_the_last_member_or_by_name() {
+        let (first, second) = (
+            CapturingDispatcher::default(),
+            CapturingDispatcher::default(),
+        );
+        let handler = Arc::new(TestHandler::default());
+        let dispatcher = MergedDispatcher::new()
+            .add("first", first.clone())
+            .add("second", second.clone())
+            .register(&definition("orders"), handler.clone())
+            .register_on("first", &definition("payments"), handler)
+            .freeze();
+
+        assert_eq!(dispatcher.names().collect::<Vec<_>>(), ["first", "second"]);
+        assert_eq!(names(&first), ["payments"]);
+        assert_eq!(names(&second), ["orders"]);
+        assert_eq!(block_on(dispatcher.consume_blocking()), Ok(()));
+    }
+
+    #[test]
+    fn registering_on_an_unknown_name_fails_consumption() {
+        let member = CapturingDispatcher::default();
+        let dispatcher = MergedDispatcher::new()
+            .add("first", member.clone())
+            .register_on(
+                "missing",
+                &definition("orders"),
+                Arc::new(TestHandler::default()),
+            )
+            .freeze();
+
+        let err = block_on(dispatcher.consume_blocking()).unwrap_err();
+
+        assert_eq!(err.code(), Some("misregistered"));
+        assert!(err.to_string().contains("no dispatcher named `missing`"));
+        assert!(member.registrations().is_empty());
+    }
+
+    #[test]
+    fn registering_without_members_fails_consumption() {
+        let dispatcher = MergedDispatcher::new()
+            .register(&definition("orders"), Arc::new(TestHandler::default()))
+            .register_fallback(Arc::new(TestHandler::default()));
+
+        let err = block_on(dispatcher.consume_blocking()).unwrap_err();
+
+        assert_eq!(err.code(), Some("misregistered"));
+        assert!(err.to_string().contains(NO_MEMBER));
+    }
+
+    #[cfg(feature = "mocks")]
+    fn consuming(result: Result<(), MessagingError>) -> MockDispatcher {
+        let mut dispatcher = MockDispatcher::new();
+        dispatcher.expect_is_frozen().return_const(true);
+        dispatcher
+            .expect_consume_blocking()
+            .times(1)
+            .return_once(move || result);
+        dispatcher
+    }
+
+    #[cfg(feature = "mocks")]
+    #[test]
+    fn consumes_two_mock_members() {
+        let dispatcher = MergedDispatcher::new()
+            .add("rabbitmq", consuming(Ok(())))
+            .add("kafka", consuming(Ok(())));
+
+        assert_eq!(block_on(dispatcher.consume_blocking()), Ok(()));
+    }
+
+    #[cfg(feature = "mocks")]
+    #[test]
+    fn returns_the_error_of_a_failing_mock_member() {
+        let dispatcher = MergedDispatcher::new()
+            .add("rabbitmq", consuming(Ok(())))
+            .add("kafka", consuming(Err(MessagingError::ConnectionError)));
+
+        assert_eq!(
+            block_on(dispatcher.consume_blocking()),
+            Err(MessagingError::ConnectionError)
+        );
+    }
+
+    #[test]
+    fn listing_fails_when_no_member_supports_it() {
+        let dispatcher = MergedDispatcher::new()
+            .add("first", CapturingDispatcher::default())
+            .add("second", CapturingDispatcher::default());
+
+        assert_eq!(
+            block_on(dispatcher.list_destinations()),
+            Err(MessagingError::InternalError)
+        );
+    }
+
+    #[cfg(feature = "mocks")]
+    fn listing(result: Result<Vec<&'static str>, MessagingError>) -> MockDispatcher {
+        let mut dispatcher = MockDispatcher::new();
+        dispatcher.expect_list_destinations().return_once(move || {
+            result.map(|names| names.into_iter().map(str::to_owned).collect())
+        });
+        dispatcher
+    }
+
+    #[cfg(feature = "mocks")]
+    #[test]
+    fn lists_the_sorted_destinations_of_the_supporting_members() {
+        let dispatcher = MergedDispatcher::new()
+            .add("rabbitmq", listing(Ok(vec!["payments", "orders"])))
+            .add("noop", listing(Err(MessagingError::InternalError)))
+            .add("kafka", listing(Ok(vec!["orders", "invoices"])));
+
+        assert_eq!(
+            block_on(dispatcher.list_destinations()),
+            Ok(vec![
+                "invoices".to_owned(),
+                "orders".to_owned(),
+                "payments".to_owned()
+            ])
+        );
+    }
+
+    #[cfg(feature = "mocks")]
+    #[test]
+    fn returns_the_listing_error_of_a_failing_member() {
+        let dispatcher = MergedDispatcher::new()
+            .add("rabbitmq", listing(Ok(vec!["orders"])))
+            .add("kafka", listing(Err(MessagingError::ConnectionError)));
+
+        assert_eq!(
+            block_on(dispatcher.list_destinations()),
+            Err(MessagingError::ConnectionError)
+        );
+    }
+}
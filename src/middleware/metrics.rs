@@ -0,0 +1,241 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Metrics
+//!
+//! This module provides hooks for recording publish and consume metrics.
+//!
+//! The [`MessagingMetrics`] trait receives an event for every publish and every consumed
+//! message, so counters and histograms can be recorded once instead of in every broker
+//! implementation. [`MeteredPublisher`] and [`MeteredHandler`] invoke the hooks.
+
+use crate::{
+    errors::MessagingError,
+    handler::{ConsumerHandler, ConsumerMessage},
+    publisher::{PublishMessage, PublishReceipt, Publisher, delegate_publisher},
+};
+use async_trait::async_trait;
+use opentelemetry::Context;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "mocks")]
+use mockall::*;
+
+/// Defines the hooks invoked for publish and consume events.
+///
+/// All methods default to doing nothing, so implementations only override the events
+/// they are interested in.
+#[cfg_attr(feature = "mocks", automock)]
+pub trait MessagingMetrics: Send + Sync {
+    /// Called after a message was published.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - The destination of the message.
+    /// * `result` - The result of the publish.
+    fn on_publish(&self, _to: &str, _result: &Result<(), MessagingError>) {}
+
+    /// Called after a consumed message was handled.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The source of the message.
+    /// * `msg_type` - The type of the message.
+    /// * `duration` - How long the handler took.
+    /// * `result` - The result of the handler.
+    fn on_consume(
+        &self,
+        _from: &str,
+        _msg_type: &str,
+        _duration: Duration,
+        _result: &Result<(), MessagingError>,
+    ) {
+    }
+}
+
+/// A metrics implementation that records nothing.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopMetrics;
+
+impl MessagingMetrics for NoopMetrics {}
+
+/// A publisher middleware that reports every publish to a [`MessagingMetrics`].
+pub struct MeteredPublisher {
+    inner: Arc<dyn Publisher>,
+    metrics: Arc<dyn MessagingMetrics>,
+}
+
+impl MeteredPublisher {
+    /// Creates a new metered publisher.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The publisher to meter.
+    /// * `metrics` - The hooks to invoke.
+    ///
+    /// # Returns
+    ///
+    /// A new `MeteredPublisher` instance.
+    pub fn new(inner: Arc<dyn Publisher>, metrics: Arc<dyn MessagingMetrics>) -> Self {
+        MeteredPublisher { inner, metrics }
+    }
+}
+
+#[async_trait]
+impl Publisher for MeteredPublisher {
+    async fn publish(&self, ctx: &Context, msg: &PublishMessage) -> Result<(), MessagingError> {
+        let result = self.inner.publish(ctx, msg).await;
+        self.metrics.on_publish(&msg.to, &result);
+        result
+    }
+
+    async fn publish_confirmed(
+        &self,
+        ctx: &Context,
+        msg: &PublishMessage,
+    ) -> Result<PublishReceipt, MessagingError> {
+        let result = self.inner.publish_confirmed(ctx, msg).await;
+        self.metrics
+            .on_publish(&msg.to, &result.as_ref().map(|_| ()).map_err(Clone::clone));
+        result
+    }
+
+    delegate_publisher!(inner);
+}
+
+/// A consumer middleware that reports every handled message to a [`MessagingMetrics`].
+pub struct MeteredHandler {
+    inner: Arc<dyn ConsumerHandler>,
+    metrics: Arc<dyn MessagingMetrics>,
+}
+
+impl MeteredHandler {
+    /// Creates a new metered handler.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The handler to meter.
+    /// * `metrics` - The hooks to invoke.
+    ///
+    /// # Returns
+    ///
+    /// A new `MeteredHandler` instance.
+    pub fn new(inner: Arc<dyn ConsumerHandler>, metrics: Arc<dyn MessagingMetrics>) -> Self {
+        MeteredHandler { inner, metrics }
+    }
+}
+
+#[async_trait]
+impl ConsumerHandler for MeteredHandler {
+    async fn exec(&self, ctx: &Context, msg: &ConsumerMessage) -> Result<(), MessagingError> {
+        let started = Instant::now();
+        let result = self.inner.exec(ctx, msg).await;
+        self.metrics
+            .on_consume(&msg.from, &msg.msg_type, started.elapsed(), &result);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{TestHandler, TestPublisher, block_on, message};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct CountingMetrics {
+        publishes: Mutex<Vec<(String, bool)>>,
+        consumes: Mutex<Vec<(String, String, bool)>>,
+    }
+
+    impl MessagingMetrics for CountingMetrics {
+        fn on_publish(&self, to: &str, result: &Result<(), MessagingError>) {
+            self.publishes
+                .lock()
+                .unwrap()
+                .push((to.to_owned(), result.is_ok()));
+        }
+
+        fn on_consume(
+            &self,
+            from: &str,
+            msg_type: &str,
+            _duration: Duration,
+            result: &Result<(), MessagingError>,
+        ) {
+            self.consumes.lock().unwrap().push((
+                from.to_owned(),
+                msg_type.to_owned(),
+                result.is_ok(),
+            ));
+        }
+    }
+
+    #[test]
+    fn counts_successful_and_failed_publishes() {
+        let inner = Arc::new(TestPublisher::default());
+        let metrics = Arc::new(CountingMetrics::default());
+        let publisher = MeteredPublisher::new(inner.clone(), metrics.clone());
+        let ctx = Context::new();
+
+        block_on(publisher.publish(&ctx, &message("orders", "data"))).unwrap();
+        inner.fail_with(Some(MessagingError::ConnectionError));
+        let failed = block_on(publisher.publish(&ctx, &message("invoices", "data")));
+
+        assert_eq!(failed, Err(MessagingError::ConnectionError));
+        assert_eq!(
+            *metrics.publishes.lock().unwrap(),
+            [("orders".to_owned(), true), ("invoices".to_owned(), false)]
+        );
+    }
+
+    #[test]
+    fn counts_confirmed_publishes() {
+        let inner = Arc::new(TestPublisher::default());
+        let metrics = Arc::new(CountingMetrics::default());
+        let publisher = MeteredPublisher::new(inner.clone(), metrics.clone());
+
+        let receipt =
+            block_on(publisher.publish_confirmed(&Context::new(), &message("orders", "data")));
+
+        assert_eq!(receipt, Ok(PublishReceipt::default()));
+        assert_eq!(
+            *metrics.publishes.lock().unwrap(),
+            [("orders".to_owned(), true)]
+        );
+    }
+
+    #[test]
+    fn counts_handled_messages() {
+        let metrics = Arc::new(CountingMetrics::default());
+        let ctx = Context::new();
+        let msg = ConsumerMessage::new("orders", "created", b"data", None);
+
+        let handled = MeteredHandler::new(Arc::new(TestHandler::default()), metrics.clone());
+        block_on(handled.exec(&ctx, &msg)).unwrap();
+        let failing = Arc::new(TestHandler::failing(MessagingError::HandlerError));
+        let failed = MeteredHandler::new(failing, metrics.clone());
+        assert_eq!(
+            block_on(failed.exec(&ctx, &msg)),
+            Err(MessagingError::HandlerError)
+        );
+
+        let consume = |ok| ("orders".to_owned(), "created".to_owned(), ok);
+        assert_eq!(
+            *metrics.consumes.lock().unwrap(),
+            [consume(true), consume(false)]
+        );
+    }
+
+    #[test]
+    fn noop_metrics_accept_every_event() {
+        let handler = MeteredHandler::new(Arc::new(TestHandler::default()), Arc::new(NoopMetrics));
+        let msg = ConsumerMessage::new("orders", "created", b"data", None);
+
+        assert_eq!(block_on(handler.exec(&Context::new(), &msg)), Ok(()));
+    }
+}
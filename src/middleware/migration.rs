@@ -0,0 +1,254 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Migration
+//!
+//! This module provides in-place schema migration of message data on consume.
+//!
+//! During rollouts consumers may receive several live versions of a message schema. A
+//! [`Migrator`] holds a chain of upgrade steps, and the [`MigratingHandler`] applies it so
+//! that the inner handler only ever sees the current version.
+
+use crate::{
+    errors::MessagingError,
+    handler::{ConsumerHandler, ConsumerMessage},
+    headers,
+};
+use async_trait::async_trait;
+use opentelemetry::Context;
+use std::sync::Arc;
+
+type MigrationFn = dyn Fn(&[u8]) -> Result<Vec<u8>, MessagingError> + Send + Sync;
+
+struct MigrationStep {
+    from: u32,
+    to: u32,
+    migrate: Box<MigrationFn>,
+}
+
+/// A chain of schema migration steps upgrading message data to a current version.
+///
+/// Starting from the version of a message, the migrator repeatedly applies the step whose
+/// `from` version matches, until the current version is reached. When several steps share
+/// the same `from` version, the one added first is used. Every step must move to a higher
+/// version, which guarantees the chain terminates.
+pub struct Migrator {
+    current: u32,
+    steps: Vec<MigrationStep>,
+}
+
+impl Migrator {
+    /// Creates a new migrator without any step.
+    ///
+    /// # Arguments
+    ///
+    /// * `current` - The schema version handlers expect.
+    ///
+    /// # Returns
+    ///
+    /// A new `Migrator` instance.
+    pub fn new(current: u32) -> Self {
+        Migrator {
+            current,
+            steps: Vec::new(),
+        }
+    }
+
+    /// Adds a migration step.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The version the step upgrades from.
+    /// * `to` - The version the step upgrades to, which must be greater than `from`.
+    /// * `migrate` - The function converting the data.
+    ///
+    /// # Returns
+    ///
+    /// The updated `Migrator`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `to` is not greater than `from`.
+    pub fn with_step<F>(mut self, from: u32, to: u32, migrate: F) -> Self
+    where
+        F: Fn(&[u8]) -> Result<Vec<u8>, MessagingError> + Send + Sync + 'static,
+    {
+        assert!(
+            to > from,
+            "a migration step must upgrade to a higher version"
+        );
+
+        self.steps.push(MigrationStep {
+            from,
+            to,
+            migrate: Box::new(migrate),
+        });
+        self
+    }
+
+    /// Returns the schema version handlers expect.
+    pub fn current(&self) -> u32 {
+        self.current
+    }
+
+    /// Upgrades data of the given version to the current version.
+    ///
+    /// # Arguments
+    ///
+    /// * `version` - The schema version of the data.
+    /// * `data` - The data to upgrade.
+    ///
+    /// # Returns
+    ///
+    /// The upgraded data, or `MessagingError::DeserializingError` if no migration path
+    /// leads to the current version. Errors returned by a step are propagated.
+    pub fn migrate(&self, version: u32, data: &[u8]) -> Result<Vec<u8>, MessagingError> {
+        let mut version = version;
+        let mut data = data.to_vec();
+
+        while version != self.current {
+            let step = self
+                .steps
+                .iter()
+                .find(|step| step.from == version && step.to <= self.current)
+                .ok_or(MessagingError::DeserializingError)?;
+
+            data = (step.migrate)(&data)?;
+            version = step.to;
+        }
+
+        Ok(data)
+    }
+}
+
+/// A consumer middleware that upgrades message data with a [`Migrator`] before invoking the
+/// inner handler.
+///
+/// The version is read from the `schema-version` header. Messages without it are assumed
+/// to already be at the current version. After migration, the header is updated to the
+/// current version.
+pub struct MigratingHandler {
+    inner: Arc<dyn ConsumerHandler>,
+    migrator: Migrator,
+}
+
+impl MigratingHandler {
+    /// Creates a new migrating handler.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The handler to invoke with the upgraded message.
+    /// * `migrator` - The migration steps to apply.
+    ///
+    /// # Returns
+    ///
+    /// A new `MigratingHandler` instance.
+    pub fn new(inner: Arc<dyn ConsumerHandler>, migrator: Migrator) -> Self {
+        MigratingHandler { inner, migrator }
+    }
+}
+
+#[async_trait]
+impl ConsumerHandler for MigratingHandler {
+    async fn exec(&self, ctx: &Context, msg: &ConsumerMessage) -> Result<(), MessagingError> {
+        let version = match msg.schema_version() {
+            Some(version) if version != self.migrator.current() => version,
+            _ => return self.inner.exec(ctx, msg).await,
+        };
+
+        let mut migrated = msg.clone();
+        migrated.data = self.migrator.migrate(version, &msg.data)?.into();
+        migrated.set_header(headers::SCHEMA_VERSION, self.migrator.current().to_string());
+
+        self.inner.exec(ctx, &migrated).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{TestHandler, block_on};
+
+    fn migrator() -> Migrator {
+        Migrator::new(3)
+            .with_step(1, 2, |data| Ok([data, b"+v2"].concat()))
+            .with_step(2, 3, |data| Ok([data, b"+v3"].concat()))
+    }
+
+    fn versioned(version: Option<&str>) -> ConsumerMessage {
+        let mut msg = ConsumerMessage::new("orders", "created", b"v1", None);
+        if let Some(version) = version {
+            msg.set_header(headers::SCHEMA_VERSION, version);
+        }
+        msg
+    }
+
+    fn exec(msg: &ConsumerMessage) -> (Result<(), MessagingError>, Vec<ConsumerMessage>) {
+        let inner = Arc::new(TestHandler::default());
+        let handler = MigratingHandler::new(inner.clone(), migrator());
+        let result = block_on(handler.exec(&Context::new(), msg));
+        (result, inner.received())
+    }
+
+    #[test]
+    fn migrates_through_every_step() {
+        assert_eq!(migrator().migrate(1, b"v1"), Ok(b"v1+v2+v3".to_vec()));
+        assert_eq!(migrator().migrate(3, b"v3"), Ok(b"v3".to_vec()));
+    }
+
+    #[test]
+    fn fails_without_a_migration_path() {
+        assert_eq!(
+            migrator().migrate(0, b"v0"),
+            Err(MessagingError::DeserializingError)
+        );
+        assert_eq!(
+            migrator().migrate(4, b"v4"),
+            Err(MessagingError::DeserializingError)
+        );
+    }
+
+    #[test]
+    fn propagates_step_errors() {
+        let migrator = Migrator::new(2).with_step(1, 2, |_| Err(MessagingError::InternalError));
+
+        assert_eq!(
+            migrator.migrate(1, b"v1"),
+            Err(MessagingError::InternalError)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "higher version")]
+    fn rejects_steps_that_do_not_upgrade() {
+        let _ = Migrator::new(2).with_step(2, 1, |data| Ok(data.to_vec()));
+    }
+
+    #[test]
+    fn handler_sees_the_upgraded_message() {
+        let (result, received) = exec(&versioned(Some("1")));
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(&*received[0].data, b"v1+v2+v3");
+        assert_eq!(received[0].schema_version(), Some(3));
+    }
+
+    #[test]
+    fn passes_current_and_unversioned_messages_through() {
+        for msg in [versioned(Some("3")), versioned(None)] {
+            let (result, received) = exec(&msg);
+
+            assert_eq!(result, Ok(()));
+            assert_eq!(received, [msg]);
+        }
+    }
+
+    #[test]
+    fn does_not_invoke_the_handler_when_migration_fails() {
+        let (result, received) = exec(&versioned(Some("0")));
+
+        assert_eq!(result, Err(MessagingError::DeserializingError));
+        assert!(received.is_empty());
+    }
+}
@@ -0,0 +1,47 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Middleware
+//!
+//! This module provides composable middleware for message consumption and publishing.
+//!
+//! A consumer middleware wraps an inner [`ConsumerHandler`](crate::handler::ConsumerHandler)
+//! and implements `ConsumerHandler` itself, so it can be registered with a dispatcher like
+//! any other handler and stacked with other middleware. Likewise, a publisher middleware
+//! wraps an inner [`Publisher`](crate::publisher::Publisher) and implements `Publisher`, and
+//! a dispatcher wrapper wraps an inner [`Dispatcher`](crate::dispatcher::Dispatcher).
+
+pub mod batch;
+pub mod buffering;
+mod cache;
+pub mod chain;
+pub mod channel;
+pub mod chunking;
+pub mod composite;
+pub mod compression;
+pub mod concurrency;
+pub mod dead_letter;
+pub mod declaring;
+pub mod dedup;
+pub mod default_headers;
+pub mod encryption;
+pub mod error_hook;
+pub mod filter;
+pub mod header_validation;
+pub mod max_retries;
+pub mod merged;
+pub mod metrics;
+pub mod migration;
+pub mod ordered;
+pub mod outbox;
+pub mod pipeline;
+pub mod rate_limit;
+pub mod reconnect;
+pub mod routing;
+pub mod size_limit;
+pub mod timeout;
+#[cfg(feature = "tracing")]
+pub mod traced;
+pub mod validation;
+pub mod version_guard;
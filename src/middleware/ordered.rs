@@ -0,0 +1,259 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Ordered
+//!
+//! This module provides a consumer middleware that handles messages sharing a key in
+//! order.
+//!
+//! Concurrent dispatch speeds up consumption but lets related messages overtake each
+//! other. The [`OrderedByKeyHandler`] keeps concurrency between unrelated messages while
+//! running the messages of each key one at a time, in arrival order.
+
+use crate::{
+    errors::MessagingError,
+    handler::{ConsumerHandler, ConsumerMessage},
+};
+use async_trait::async_trait;
+use opentelemetry::Context;
+use std::{
+    collections::{HashMap, VecDeque},
+    future::poll_fn,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    task::{Poll, Waker},
+};
+
+type KeyFn = Box<dyn Fn(&ConsumerMessage) -> Option<String> + Send + Sync>;
+
+/// A consumer middleware that serializes the `exec` calls of messages with the same key.
+///
+/// The key of a message is computed by a key function, e.g. reading the header the
+/// producer stored its partition key in. Messages with the same key run one after the
+/// other, in the order their `exec` calls started; messages with different keys, and
+/// messages without a key, run concurrently. A message whose handler fails or is cancelled
+/// releases its key to the next message.
+pub struct OrderedByKeyHandler {
+    inner: Arc<dyn ConsumerHandler>,
+    key: KeyFn,
+    sequence: Mutex<Sequence>,
+}
+
+#[derive(Default)]
+struct Sequence {
+    next_ticket: u64,
+    queues: HashMap<String, VecDeque<Waiter>>,
+}
+
+struct Waiter {
+    ticket: u64,
+    waker: Option<Waker>,
+}
+
+/// Removes a message from the queue of its key once it completes or is cancelled.
+struct Turn<'a> {
+    handler: &'a OrderedByKeyHandler,
+    key: String,
+    ticket: u64,
+}
+
+impl Drop for Turn<'_> {
+    fn drop(&mut self) {
+        let mut sequence = self.handler.sequence();
+        let Some(queue) = sequence.queues.get_mut(&self.key) else {
+            return;
+        };
+
+        queue.retain(|waiter| waiter.ticket != self.ticket);
+        match queue.front_mut() {
+            Some(next) => {
+                if let Some(waker) = next.waker.take() {
+                    waker.wake();
+                }
+            }
+            None => {
+                sequence.queues.remove(&self.key);
+            }
+        }
+    }
+}
+
+impl OrderedByKeyHandler {
+    /// Creates a new ordering handler.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The handler to serialize per key.
+    /// * `key` - The function computing the key of a message, or `None` for messages that
+    ///   need no ordering.
+    ///
+    /// # Returns
+    ///
+    /// A new `OrderedByKeyHandler` instance.
+    pub fn new<F>(inner: Arc<dyn ConsumerHandler>, key: F) -> Self
+    where
+        F: Fn(&ConsumerMessage) -> Option<String> + Send + Sync + 'static,
+    {
+        OrderedByKeyHandler {
+            inner,
+            key: Box::new(key),
+            sequence: Mutex::new(Sequence::default()),
+        }
+    }
+
+    /// Creates a new ordering handler keyed by the value of a header.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The handler to serialize per key.
+    /// * `header` - The header holding the key. Messages without it need no ordering.
+    ///
+    /// # Returns
+    ///
+    /// A new `OrderedByKeyHandler` instance.
+    pub fn by_header<T>(inner: Arc<dyn ConsumerHandler>, header: T) -> Self
+    where
+        T: Into<String>,
+    {
+        let header = header.into();
+        OrderedByKeyHandler::new(inner, move |msg| msg.header(&header).map(str::to_owned))
+    }
+
+    fn sequence(&self) -> MutexGuard<'_, Sequence> {
+        self.sequence.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    async fn wait_turn(&self, key: String) -> Turn<'_> {
+        let ticket = {
+            let mut sequence = self.sequence();
+            let ticket = sequence.next_ticket;
+            sequence.next_ticket += 1;
+            sequence
+                .queues
+                .entry(key.clone())
+                .or_default()
+                .push_back(Waiter {
+                    ticket,
+                    waker: None,
+                });
+            ticket
+        };
+
+        let turn = Turn {
+            handler: self,
+            key,
+            ticket,
+        };
+
+        poll_fn(|cx| {
+            let mut sequence = self.sequence();
+            let queue = sequence
+                .queues
+                .get_mut(&turn.key)
+                .expect("the queue of a waiting key is never removed");
+            if queue.front().is_some_and(|waiter| waiter.ticket == ticket) {
+                return Poll::Ready(());
+            }
+            if let Some(waiter) = queue.iter_mut().find(|waiter| waiter.ticket == ticket) {
+                waiter.waker = Some(cx.waker().clone());
+            }
+            Poll::Pending
+        })
+        .await;
+
+        turn
+    }
+}
+
+#[async_trait]
+impl ConsumerHandler for OrderedByKeyHandler {
+    async fn exec(&self, ctx: &Context, msg: &ConsumerMessage) -> Result<(), MessagingError> {
+        let Some(key) = (self.key)(msg) else {
+            return self.inner.exec(ctx, msg).await;
+        };
+
+        let _turn = self.wait_turn(key).await;
+        self.inner.exec(ctx, msg).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        handler::fn_handler,
+        test_support::{block_on, join_all, yield_now},
+    };
+
+    type Events = Arc<Mutex<Vec<String>>>;
+
+    /// A handler recording when each message starts and ends, failing messages with data
+    /// `fail`.
+    fn recording(events: &Events) -> Arc<dyn ConsumerHandler> {
+        let events = events.clone();
+        fn_handler(move |_, msg| {
+            let events = events.clone();
+            let data = msg.data_string().unwrap();
+            async move {
+                events.lock().unwrap().push(format!("start {data}"));
+                for _ in 0..3 {
+                    yield_now().await;
+                }
+                events.lock().unwrap().push(format!("end {data}"));
+                if data == "fail" {
+                    Err(MessagingError::HandlerError)
+                } else {
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    fn keyed(key: Option<&str>, data: &str) -> ConsumerMessage {
+        let headers = key.map(|key| HashMap::from([("key".to_owned(), key.to_owned())]));
+        ConsumerMessage::new("orders", "created", data.as_bytes(), headers)
+    }
+
+    fn run(messages: &[ConsumerMessage]) -> (Vec<Result<(), MessagingError>>, Vec<String>) {
+        let events = Events::default();
+        let handler = OrderedByKeyHandler::by_header(recording(&events), "key");
+        let ctx = Context::new();
+
+        let results = block_on(join_all(
+            messages.iter().map(|msg| handler.exec(&ctx, msg)).collect(),
+        ));
+
+        assert!(handler.sequence().queues.is_empty());
+        let events = events.lock().unwrap().clone();
+        (results, events)
+    }
+
+    #[test]
+    fn messages_with_the_same_key_never_overlap() {
+        let (_, events) = run(&[keyed(Some("a"), "1"), keyed(Some("a"), "2")]);
+
+        assert_eq!(events, ["start 1", "end 1", "start 2", "end 2"]);
+    }
+
+    #[test]
+    fn messages_with_different_keys_or_no_key_overlap() {
+        let (_, events) = run(&[
+            keyed(Some("a"), "1"),
+            keyed(Some("b"), "2"),
+            keyed(None, "3"),
+        ]);
+
+        assert_eq!(
+            events,
+            ["start 1", "start 2", "start 3", "end 1", "end 2", "end 3"]
+        );
+    }
+
+    #[test]
+    fn failing_messages_release_their_key() {
+        let (results, events) = run(&[keyed(Some("a"), "fail"), keyed(Some("a"), "2")]);
+
+        assert_eq!(results, [Err(MessagingError::HandlerError), Ok(())]);
+        assert_eq!(events, ["start fail", "end fail", "start 2", "end 2"]);
+    }
+}
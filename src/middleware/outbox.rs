@@ -0,0 +1,304 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Outbox
+//!
+//! This module provides a publisher middleware implementing the transactional outbox
+//! pattern.
+//!
+//! The [`OutboxPublisher`] does not publish messages directly but saves them to an
+//! [`OutboxStore`], typically a table in the same database the application writes its
+//! state to. [`OutboxPublisher::flush_outbox`] later publishes the pending entries to the
+//! broker and marks them as sent, so a crash between committing the state and publishing
+//! the message cannot lose the message. [`InMemoryOutboxStore`] is a store without
+//! persistence, for tests and for applications that only need the deferred publishing.
+
+use crate::{
+    errors::MessagingError,
+    publisher::{PublishMessage, Publisher, delegate_publisher},
+};
+use async_trait::async_trait;
+use opentelemetry::Context;
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+#[cfg(feature = "mocks")]
+use mockall::*;
+
+/// A message saved to an [`OutboxStore`] that was not sent yet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutboxEntry {
+    /// The store-assigned identifier of the entry.
+    pub id: u64,
+
+    /// The message to publish.
+    pub msg: PublishMessage,
+}
+
+/// Defines the storage of an [`OutboxPublisher`].
+///
+/// Implementations must keep entries until they are marked as sent, so that entries
+/// survive a crash between `save` and `mark_sent`.
+#[cfg_attr(feature = "mocks", automock)]
+#[async_trait]
+pub trait OutboxStore: Send + Sync {
+    /// Saves a message to the outbox.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The message to save.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the identifier of the new entry, or an error if saving fails.
+    async fn save(&self, msg: &PublishMessage) -> Result<u64, MessagingError>;
+
+    /// Lists the entries that were not marked as sent.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the pending entries in the order they were saved, or an error
+    /// if reading the store fails.
+    async fn pending(&self) -> Result<Vec<OutboxEntry>, MessagingError>;
+
+    /// Marks an entry as sent, so it is no longer pending.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The identifier of the entry.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or containing an error if updating the store fails.
+    async fn mark_sent(&self, id: u64) -> Result<(), MessagingError>;
+}
+
+/// An outbox store keeping its entries in memory.
+///
+/// Entries are lost when the process exits, so this store gives none of the crash safety
+/// of a persistent store. Sent entries are removed.
+#[derive(Debug, Default)]
+pub struct InMemoryOutboxStore {
+    entries: Mutex<(u64, BTreeMap<u64, PublishMessage>)>,
+}
+
+impl InMemoryOutboxStore {
+    /// Creates a new, empty in-memory outbox store.
+    ///
+    /// # Returns
+    ///
+    /// A new `InMemoryOutboxStore` instance.
+    pub fn new() -> Self {
+        InMemoryOutboxStore::default()
+    }
+}
+
+#[async_trait]
+impl OutboxStore for InMemoryOutboxStore {
+    async fn save(&self, msg: &PublishMessage) -> Result<u64, MessagingError> {
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        let (next_id, messages) = &mut *entries;
+        *next_id += 1;
+        messages.insert(*next_id, msg.clone());
+        Ok(*next_id)
+    }
+
+    async fn pending(&self) -> Result<Vec<OutboxEntry>, MessagingError> {
+        let entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        Ok(entries
+            .1
+            .iter()
+            .map(|(id, msg)| OutboxEntry {
+                id: *id,
+                msg: msg.clone(),
+            })
+            .collect())
+    }
+
+    async fn mark_sent(&self, id: u64) -> Result<(), MessagingError> {
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        entries.1.remove(&id);
+        Ok(())
+    }
+}
+
+/// A publisher middleware saving messages to an [`OutboxStore`] instead of publishing them.
+///
+/// `publish` succeeds once the message is saved; `publish_confirmed` therefore reports an
+/// unconfirmed receipt. Call [`OutboxPublisher::flush_outbox`] after committing, or
+/// periodically, to publish the saved messages to the inner publisher. As an entry may be
+/// published again when marking it as sent fails, consumers must tolerate duplicates.
+pub struct OutboxPublisher {
+    inner: Arc<dyn Publisher>,
+    store: Arc<dyn OutboxStore>,
+}
+
+impl OutboxPublisher {
+    /// Creates a new outbox publisher.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The publisher to flush the outbox to.
+    /// * `store` - The store to save messages to.
+    ///
+    /// # Returns
+    ///
+    /// A new `OutboxPublisher` instance.
+    pub fn new(inner: Arc<dyn Publisher>, store: Arc<dyn OutboxStore>) -> Self {
+        OutboxPublisher { inner, store }
+    }
+
+    /// Publishes the pending entries of the store to the inner publisher.
+    ///
+    /// Entries are published in the order they were saved and each is marked as sent right
+    /// after its publish succeeded. The flush stops at the first failure, so the remaining
+    /// entries keep their order and are published by the next flush.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The OpenTelemetry context for tracing and monitoring.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the number of entries sent, or the first error.
+    pub async fn flush_outbox(&self, ctx: &Context) -> Result<usize, MessagingError> {
+        let pending = self.store.pending().await?;
+
+        for entry in &pending {
+            self.inner.publish(ctx, &entry.msg).await?;
+            self.store.mark_sent(entry.id).await?;
+        }
+
+        Ok(pending.len())
+    }
+}
+
+#[async_trait]
+impl Publisher for OutboxPublisher {
+    async fn publish(&self, _ctx: &Context, msg: &PublishMessage) -> Result<(), MessagingError> {
+        self.store.save(msg).await.map(|_| ())
+    }
+
+    delegate_publisher!(inner);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{TestPublisher, block_on, message};
+
+    fn outbox() -> (
+        OutboxPublisher,
+        Arc<TestPublisher>,
+        Arc<InMemoryOutboxStore>,
+    ) {
+        let inner = Arc::new(TestPublisher::default());
+        let store = Arc::new(InMemoryOutboxStore::new());
+        (
+            OutboxPublisher::new(inner.clone(), store.clone()),
+            inner,
+            store,
+        )
+    }
+
+    fn pending_data(store: &InMemoryOutboxStore) -> Vec<String> {
+        block_on(store.pending())
+            .unwrap()
+            .into_iter()
+            .map(|entry| String::from_utf8_lossy(&entry.msg.data).into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn saves_messages_instead_of_publishing_them() {
+        let (publisher, inner, store) = outbox();
+
+        block_on(publisher.publish(&Context::new(), &message("orders", "a"))).unwrap();
+        let receipt =
+            block_on(publisher.publish_confirmed(&Context::new(), &message("orders", "b")))
+                .unwrap();
+
+        assert!(!receipt.confirmed);
+        assert!(inner.published().is_empty());
+        assert_eq!(pending_data(&store), ["a", "b"]);
+    }
+
+    #[test]
+    fn in_memory_store_assigns_increasing_ids_and_drops_sent_entries() {
+        let store = InMemoryOutboxStore::new();
+
+        let first = block_on(store.save(&message("orders", "a"))).unwrap();
+        let second = block_on(store.save(&message("orders", "b"))).unwrap();
+        assert!(first < second);
+
+        block_on(store.mark_sent(first)).unwrap();
+        let pending = block_on(store.pending()).unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].id, second);
+    }
+
+    #[test]
+    fn flush_publishes_pending_entries_in_order_and_marks_them_sent() {
+        let (publisher, inner, store) = outbox();
+        for data in ["a", "b", "c"] {
+            block_on(publisher.publish(&Context::new(), &message("orders", data))).unwrap();
+        }
+
+        assert_eq!(block_on(publisher.flush_outbox(&Context::new())), Ok(3));
+        assert_eq!(inner.published_data(), ["a", "b", "c"]);
+        assert!(pending_data(&store).is_empty());
+
+        assert_eq!(block_on(publisher.flush_outbox(&Context::new())), Ok(0));
+        assert_eq!(inner.published().len(), 3);
+    }
+
+    #[test]
+    fn failed_flush_keeps_the_entries_for_the_next_flush() {
+        let (publisher, inner, store) = outbox();
+        for data in ["a", "b"] {
+            block_on(publisher.publish(&Context::new(), &message("orders", data))).unwrap();
+        }
+
+        inner.fail_with(Some(MessagingError::publisher("broker down")));
+        let result = block_on(publisher.flush_outbox(&Context::new()));
+        assert_eq!(result, Err(MessagingError::publisher("broker down")));
+        assert_eq!(pending_data(&store), ["a", "b"]);
+
+        inner.fail_with(None);
+        assert_eq!(block_on(publisher.flush_outbox(&Context::new())), Ok(2));
+        assert_eq!(inner.published_data(), ["a", "b"]);
+    }
+
+    #[cfg(feature = "mocks")]
+    #[test]
+    fn flush_stops_when_marking_an_entry_as_sent_fails() {
+        let inner = Arc::new(TestPublisher::default());
+        let mut store = MockOutboxStore::new();
+        store.expect_pending().returning(|| {
+            Ok(vec![
+                OutboxEntry {
+                    id: 1,
+                    msg: message("orders", "a"),
+                },
+                OutboxEntry {
+                    id: 2,
+                    msg: message("orders", "b"),
+                },
+            ])
+        });
+        store
+            .expect_mark_sent()
+            .withf(|id| *id == 1)
+            .times(1)
+            .returning(|_| Err(MessagingError::InternalError));
+        let publisher = OutboxPublisher::new(inner.clone(), Arc::new(store));
+
+        let result = block_on(publisher.flush_outbox(&Context::new()));
+
+        assert_eq!(result, Err(MessagingError::InternalError));
+        assert_eq!(inner.published_data(), ["a"]);
+    }
+}
@@ -0,0 +1,144 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Pipeline
+//!
+//! This module provides a publisher applying a sequence of synchronous transforms to every
+//! message before publishing it.
+//!
+//! A transform is a plain function, which makes the [`PublishPipeline`] a lightweight
+//! alternative to a dedicated middleware for simple rewrites such as adding a header or
+//! renaming a destination.
+
+use crate::{
+    errors::MessagingError,
+    publisher::{PublishMessage, PublishReceipt, Publisher, delegate_publisher},
+};
+use async_trait::async_trait;
+use opentelemetry::Context;
+use std::sync::Arc;
+
+type Transform =
+    Box<dyn Fn(PublishMessage) -> Result<PublishMessage, MessagingError> + Send + Sync>;
+
+/// A publisher middleware applying an ordered list of transforms to every message.
+///
+/// Transforms run in the order they were added, each receiving the output of the previous
+/// one. If a transform fails, its error is returned, the remaining transforms are skipped
+/// and nothing is published.
+pub struct PublishPipeline {
+    inner: Arc<dyn Publisher>,
+    transforms: Vec<Transform>,
+}
+
+impl PublishPipeline {
+    /// Creates a new pipeline without transforms.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The publisher to send transformed messages to.
+    ///
+    /// # Returns
+    ///
+    /// A new `PublishPipeline` instance.
+    pub fn new(inner: Arc<dyn Publisher>) -> Self {
+        PublishPipeline {
+            inner,
+            transforms: Vec::new(),
+        }
+    }
+
+    /// Appends a transform to the pipeline.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The transform, applied after the ones already in the pipeline.
+    ///
+    /// # Returns
+    ///
+    /// The updated `PublishPipeline`.
+    pub fn stage<F>(mut self, f: F) -> Self
+    where
+        F: Fn(PublishMessage) -> Result<PublishMessage, MessagingError> + Send + Sync + 'static,
+    {
+        self.transforms.push(Box::new(f));
+        self
+    }
+
+    fn apply(&self, msg: &PublishMessage) -> Result<PublishMessage, MessagingError> {
+        self.transforms
+            .iter()
+            .try_fold(msg.clone(), |msg, transform| transform(msg))
+    }
+}
+
+#[async_trait]
+impl Publisher for PublishPipeline {
+    async fn publish(&self, ctx: &Context, msg: &PublishMessage) -> Result<(), MessagingError> {
+        self.inner.publish(ctx, &self.apply(msg)?).await
+    }
+
+    async fn publish_confirmed(
+        &self,
+        ctx: &Context,
+        msg: &PublishMessage,
+    ) -> Result<PublishReceipt, MessagingError> {
+        self.inner.publish_confirmed(ctx, &self.apply(msg)?).await
+    }
+
+    delegate_publisher!(inner);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        publisher::HeaderValues,
+        test_support::{TestPublisher, block_on, message},
+    };
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn chains_transforms_in_order() {
+        let inner = Arc::new(TestPublisher::default());
+        let publisher = PublishPipeline::new(inner.clone())
+            .stage(|mut msg| {
+                msg.to = format!("{}.v2", msg.to);
+                Ok(msg)
+            })
+            .stage(|mut msg| {
+                msg.set_header("routed-to", HeaderValues::from(msg.to.clone()));
+                Ok(msg)
+            });
+
+        block_on(publisher.publish(&Context::new(), &message("orders", "data"))).unwrap();
+
+        let published = inner.published();
+        assert_eq!(published[0].to, "orders.v2");
+        assert_eq!(
+            published[0].headers.as_ref().unwrap()["routed-to"],
+            HeaderValues::from("orders.v2")
+        );
+    }
+
+    #[test]
+    fn failing_transform_skips_the_rest_and_the_publish() {
+        let inner = Arc::new(TestPublisher::default());
+        let later = Arc::new(AtomicUsize::new(0));
+        let counted = later.clone();
+        let publisher = PublishPipeline::new(inner.clone())
+            .stage(|_| Err(MessagingError::publisher("rejected")))
+            .stage(move |msg| {
+                counted.fetch_add(1, Ordering::SeqCst);
+                Ok(msg)
+            });
+
+        let result =
+            block_on(publisher.publish_confirmed(&Context::new(), &message("orders", "data")));
+
+        assert_eq!(result, Err(MessagingError::publisher("rejected")));
+        assert_eq!(later.load(Ordering::SeqCst), 0);
+        assert!(inner.published().is_empty());
+    }
+}
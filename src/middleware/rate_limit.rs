@@ -0,0 +1,201 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Rate Limit
+//!
+//! This module provides a publisher middleware keeping the publish rate under a limit.
+//!
+//! Brokers and downstream services often enforce quotas. The [`RateLimitedPublisher`]
+//! throttles publishing client-side with a token bucket shared by all concurrent callers.
+
+use crate::{
+    errors::MessagingError,
+    publisher::{PublishMessage, PublishReceipt, Publisher, delegate_publisher},
+    timer::Timer,
+};
+use async_trait::async_trait;
+use opentelemetry::Context;
+use std::{
+    sync::{Arc, Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+/// A publisher middleware limiting the publish rate with a token bucket.
+///
+/// The bucket holds up to `burst` tokens and refills at `rate_per_second` tokens per
+/// second; every `publish` and `publish_confirmed` takes one token. When the bucket is
+/// empty, the call waits for its token by default. Waiting callers reserve their tokens in
+/// call order, so the limit holds under concurrency. In rejecting mode, set with
+/// [`RateLimitedPublisher::rejecting`], the call fails with `MessagingError::PublisherError`
+/// instead of waiting.
+pub struct RateLimitedPublisher {
+    inner: Arc<dyn Publisher>,
+    timer: Arc<dyn Timer>,
+    rate_per_second: f64,
+    burst: f64,
+    reject: bool,
+    bucket: Mutex<Bucket>,
+}
+
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl RateLimitedPublisher {
+    /// Creates a new rate-limited publisher with a full bucket.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The publisher to send messages to.
+    /// * `rate_per_second` - The sustained number of messages per second (at least 1).
+    /// * `burst` - The number of messages that may be published at once (at least 1).
+    /// * `timer` - The timer used to wait for tokens.
+    ///
+    /// # Returns
+    ///
+    /// A new `RateLimitedPublisher` instance.
+    pub fn new(
+        inner: Arc<dyn Publisher>,
+        rate_per_second: u32,
+        burst: u32,
+        timer: Arc<dyn Timer>,
+    ) -> Self {
+        let burst = f64::from(burst.max(1));
+
+        RateLimitedPublisher {
+            inner,
+            timer,
+            rate_per_second: f64::from(rate_per_second.max(1)),
+            burst,
+            reject: false,
+            bucket: Mutex::new(Bucket {
+                tokens: burst,
+                refilled_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Makes calls fail instead of waiting when the bucket is empty.
+    ///
+    /// # Returns
+    ///
+    /// The updated `RateLimitedPublisher`.
+    pub fn rejecting(mut self) -> Self {
+        self.reject = true;
+        self
+    }
+
+    async fn acquire(&self) -> Result<(), MessagingError> {
+        let wait = {
+            let mut bucket = self.bucket.lock().unwrap_or_else(PoisonError::into_inner);
+            let now = Instant::now();
+            let refill =
+                now.duration_since(bucket.refilled_at).as_secs_f64() * self.rate_per_second;
+            bucket.tokens = (bucket.tokens + refill).min(self.burst);
+            bucket.refilled_at = now;
+
+            if bucket.tokens < 1.0 && self.reject {
+                return Err(MessagingError::PublisherError { detail: None });
+            }
+
+            bucket.tokens -= 1.0;
+            (bucket.tokens < 0.0)
+                .then(|| Duration::from_secs_f64(-bucket.tokens / self.rate_per_second))
+        };
+
+        if let Some(wait) = wait {
+            self.timer.sleep(wait).await;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Publisher for RateLimitedPublisher {
+    async fn publish(&self, ctx: &Context, msg: &PublishMessage) -> Result<(), MessagingError> {
+        self.acquire().await?;
+        self.inner.publish(ctx, msg).await
+    }
+
+    async fn publish_confirmed(
+        &self,
+        ctx: &Context,
+        msg: &PublishMessage,
+    ) -> Result<PublishReceipt, MessagingError> {
+        self.acquire().await?;
+        self.inner.publish_confirmed(ctx, msg).await
+    }
+
+    delegate_publisher!(inner);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_support::{RecordingTimer, TestPublisher, block_on, message},
+        timer::ThreadTimer,
+    };
+
+    fn publish_n(publisher: &RateLimitedPublisher, n: usize) -> Vec<Result<(), MessagingError>> {
+        (0..n)
+            .map(|_| block_on(publisher.publish(&Context::new(), &message("orders", "data"))))
+            .collect()
+    }
+
+    #[test]
+    fn bursts_beyond_the_limit_wait_for_their_tokens() {
+        let inner = Arc::new(TestPublisher::default());
+        let timer = Arc::new(RecordingTimer::default());
+        let publisher = RateLimitedPublisher::new(inner.clone(), 10, 2, timer.clone());
+
+        assert!(
+            publish_n(&publisher, 4)
+                .into_iter()
+                .all(|result| result.is_ok())
+        );
+
+        let sleeps = timer.sleeps();
+        assert_eq!(sleeps.len(), 2);
+        for (sleep, expected) in sleeps.iter().zip([100, 200]) {
+            let expected = Duration::from_millis(expected);
+            assert!(*sleep <= expected && *sleep > expected - Duration::from_millis(20));
+        }
+        assert_eq!(inner.published().len(), 4);
+    }
+
+    #[test]
+    fn throttles_publishing_in_real_time() {
+        let inner = Arc::new(TestPublisher::default());
+        let publisher = RateLimitedPublisher::new(inner.clone(), 20, 1, Arc::new(ThreadTimer));
+        let start = Instant::now();
+
+        publish_n(&publisher, 4);
+
+        assert!(start.elapsed() >= Duration::from_millis(140));
+        assert_eq!(inner.published().len(), 4);
+    }
+
+    #[test]
+    fn rejecting_mode_fails_instead_of_waiting() {
+        let inner = Arc::new(TestPublisher::default());
+        let timer = Arc::new(RecordingTimer::default());
+        let publisher = RateLimitedPublisher::new(inner.clone(), 1, 2, timer.clone()).rejecting();
+
+        let results = publish_n(&publisher, 3);
+
+        assert_eq!(
+            results,
+            [
+                Ok(()),
+                Ok(()),
+                Err(MessagingError::PublisherError { detail: None })
+            ]
+        );
+        assert!(timer.sleeps().is_empty());
+        assert_eq!(inner.published().len(), 2);
+    }
+}
@@ -0,0 +1,331 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Reconnect
+//!
+//! This module provides a dispatcher wrapper that restarts consumption after connection
+//! failures.
+//!
+//! Without it, `consume_blocking` returns on the first `ConnectionError` and every
+//! application has to implement its own reconnection loop.
+
+use crate::{
+    backoff::Backoff,
+    dispatcher::{Dispatcher, DispatcherDefinition, DispatcherStats, SeekPosition},
+    errors::MessagingError,
+    handler::{ConsumerHandler, ConsumerMessage},
+    timer::Timer,
+};
+use async_trait::async_trait;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// A dispatcher that restarts consumption with exponential backoff on connection errors.
+///
+/// When the inner `consume_blocking` fails with `MessagingError::ConnectionError`, the
+/// wrapper waits and starts it again. The first wait lasts `initial_backoff` and every
+/// following one doubles, up to `max_backoff`. After `max_retries` consecutive connection
+/// errors the last one is returned. A consumption running for at least `max_backoff`
+/// before failing is considered to have reconnected, and resets the backoff. Any other
+/// error is returned immediately.
+///
+/// All other methods delegate to the inner dispatcher.
+pub struct ReconnectingDispatcher<D> {
+    inner: D,
+    timer: Arc<dyn Timer>,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    max_retries: u32,
+}
+
+impl<D> ReconnectingDispatcher<D>
+where
+    D: Dispatcher,
+{
+    /// Creates a new reconnecting dispatcher.
+    ///
+    /// The backoff starts at 100 milliseconds, is capped at 30 seconds, and consumption is
+    /// given up after 5 consecutive connection errors.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The dispatcher to restart.
+    /// * `timer` - The timer used to wait between attempts.
+    ///
+    /// # Returns
+    ///
+    /// A new `ReconnectingDispatcher` instance.
+    pub fn new(inner: D, timer: Arc<dyn Timer>) -> Self {
+        ReconnectingDispatcher {
+            inner,
+            timer,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(30),
+            max_retries: 5,
+        }
+    }
+
+    /// Sets the backoff between attempts.
+    ///
+    /// # Arguments
+    ///
+    /// * `initial_backoff` - The wait after the first connection error.
+    /// * `max_backoff` - The maximum wait between attempts.
+    ///
+    /// # Returns
+    ///
+    /// The updated `ReconnectingDispatcher`.
+    pub fn with_backoff(mut self, initial_backoff: Duration, max_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self.max_backoff = max_backoff.max(initial_backoff);
+        self
+    }
+
+    /// Sets the number of consecutive connection errors after which consumption stops.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_retries` - The maximum number of restarts in a row.
+    ///
+    /// # Returns
+    ///
+    /// The updated `ReconnectingDispatcher`.
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Returns the wrapped dispatcher.
+    pub fn inner(&self) -> &D {
+        &self.inner
+    }
+}
+
+#[async_trait]
+impl<D> Dispatcher for ReconnectingDispatcher<D>
+where
+    D: Dispatcher,
+{
+    fn register(
+        mut self,
+        definition: &DispatcherDefinition,
+        handler: Arc<dyn ConsumerHandler>,
+    ) -> Self {
+        self.inner = self.inner.register(definition, handler);
+        self
+    }
+
+    fn register_ref(
+        &mut self,
+        definition: &DispatcherDefinition,
+        handler: Arc<dyn ConsumerHandler>,
+    ) -> &mut Self {
+        self.inner.register_ref(definition, handler);
+        self
+    }
+
+    fn with_prefetch(mut self, prefetch: u16) -> Self {
+        self.inner = self.inner.with_prefetch(prefetch);
+        self
+    }
+
+    fn register_fallback(mut self, handler: Arc<dyn ConsumerHandler>) -> Self {
+        self.inner = self.inner.register_fallback(handler);
+        self
+    }
+
+    fn freeze(mut self) -> Self {
+        self.inner = self.inner.freeze();
+        self
+    }
+
+    fn is_frozen(&self) -> bool {
+        self.inner.is_frozen()
+    }
+
+    async fn consume_blocking(&self) -> Result<(), MessagingError> {
+        let mut retries = 0;
+        let mut backoff = Backoff::new(self.initial_backoff, self.max_backoff);
+
+        loop {
+            let started = Instant::now();
+            match self.inner.consume_blocking().await {
+                Err(MessagingError::ConnectionError) => {
+                    if started.elapsed() >= self.max_backoff {
+                        retries = 0;
+                        backoff.reset();
+                    }
+                    if retries >= self.max_retries {
+                        return Err(MessagingError::ConnectionError);
+                    }
+
+                    let wait = backoff.next().unwrap_or(self.max_backoff);
+                    self.timer.sleep(wait).await;
+                    retries += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn connect(&self) -> Result<(), MessagingError> {
+        self.inner.connect().await
+    }
+
+    async fn disconnect(&self) -> Result<(), MessagingError> {
+        self.inner.disconnect().await
+    }
+
+    async fn health_check(&self) -> Result<(), MessagingError> {
+        self.inner.health_check().await
+    }
+
+    async fn drain(&self, timeout: Duration) -> Result<(), MessagingError> {
+        self.inner.drain(timeout).await
+    }
+
+    async fn consume_n(&self, n: usize) -> Result<Vec<ConsumerMessage>, MessagingError> {
+        self.inner.consume_n(n).await
+    }
+
+    async fn list_destinations(&self) -> Result<Vec<String>, MessagingError> {
+        self.inner.list_destinations().await
+    }
+
+    async fn seek(
+        &self,
+        definition: &DispatcherDefinition,
+        position: SeekPosition,
+    ) -> Result<(), MessagingError> {
+        self.inner.seek(definition, position).await
+    }
+
+    fn stats(&self) -> DispatcherStats {
+        self.inner.stats()
+    }
+
+    fn definitions(&self) -> Vec<DispatcherDefinition> {
+        self.inner.definitions()
+    }
+
+    fn handler_count(&self) -> usize {
+        self.inner.handler_count()
+    }
+
+    async fn pause(&self) {
+        self.inner.pause().await
+    }
+
+    async fn resume(&self) {
+        self.inner.resume().await
+    }
+
+    fn is_paused(&self) -> bool {
+        self.inner.is_paused()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "mocks")]
+    use crate::dispatcher::MockDispatcher;
+    use crate::test_support::{CapturingDispatcher, RecordingTimer, TestHandler, block_on};
+    #[cfg(feature = "mocks")]
+    use mockall::Sequence;
+
+    #[cfg(feature = "mocks")]
+    fn failing_then(failures: usize, result: Result<(), MessagingError>) -> MockDispatcher {
+        let mut dispatcher = MockDispatcher::new();
+        let mut sequence = Sequence::new();
+        if failures > 0 {
+            dispatcher
+                .expect_consume_blocking()
+                .times(failures)
+                .in_sequence(&mut sequence)
+                .returning(|| Err(MessagingError::ConnectionError));
+        }
+        dispatcher
+            .expect_consume_blocking()
+            .times(1)
+            .in_sequence(&mut sequence)
+            .return_once(move || result);
+        dispatcher
+    }
+
+    #[test]
+    fn delegates_registration_and_clean_consumption() {
+        let inner = CapturingDispatcher::default();
+        let timer = Arc::new(RecordingTimer::default());
+        let dispatcher = ReconnectingDispatcher::new(inner.clone(), timer.clone())
+            .register(
+                &DispatcherDefinition::new("orders", None),
+                Arc::new(TestHandler::default()),
+            )
+            .freeze();
+
+        assert_eq!(block_on(dispatcher.consume_blocking()), Ok(()));
+        assert_eq!(inner.registrations().len(), 1);
+        assert!(timer.sleeps().is_empty());
+    }
+
+    #[cfg(feature = "mocks")]
+    #[test]
+    fn reconnects_with_exponential_backoff() {
+        let timer = Arc::new(RecordingTimer::default());
+        let dispatcher = ReconnectingDispatcher::new(failing_then(2, Ok(())), timer.clone())
+            .with_backoff(Duration::from_millis(100), Duration::from_secs(30));
+
+        assert_eq!(block_on(dispatcher.consume_blocking()), Ok(()));
+        assert_eq!(
+            timer.sleeps(),
+            [Duration::from_millis(100), Duration::from_millis(200)]
+        );
+    }
+
+    #[cfg(feature = "mocks")]
+    #[test]
+    fn gives_up_after_max_retries() {
+        let timer = Arc::new(RecordingTimer::default());
+        let inner = failing_then(2, Err(MessagingError::ConnectionError));
+        let dispatcher = ReconnectingDispatcher::new(inner, timer.clone()).with_max_retries(2);
+
+        assert_eq!(
+            block_on(dispatcher.consume_blocking()),
+            Err(MessagingError::ConnectionError)
+        );
+        assert_eq!(timer.sleeps().len(), 2);
+    }
+
+    #[cfg(feature = "mocks")]
+    #[test]
+    fn returns_other_errors_immediately() {
+        let timer = Arc::new(RecordingTimer::default());
+        let inner = failing_then(0, Err(MessagingError::consumer("bad handler")));
+        let dispatcher = ReconnectingDispatcher::new(inner, timer.clone());
+
+        assert_eq!(
+            block_on(dispatcher.consume_blocking()),
+            Err(MessagingError::consumer("bad handler"))
+        );
+        assert!(timer.sleeps().is_empty());
+    }
+
+    #[cfg(feature = "mocks")]
+    #[test]
+    fn delegates_listing_destinations() {
+        let mut inner = MockDispatcher::new();
+        inner
+            .expect_list_destinations()
+            .returning(|| Ok(vec!["orders".to_owned()]));
+        let dispatcher = ReconnectingDispatcher::new(inner, Arc::new(RecordingTimer::default()));
+
+        assert_eq!(
+            block_on(dispatcher.list_destinations()),
+            Ok(vec!["orders".to_owned()])
+        );
+    }
+}
@@ -0,0 +1,184 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Routing
+//!
+//! This module provides a publisher middleware choosing destinations from message types.
+//!
+//! With a [`RoutingPublisher`], producers only set the `msg_type` of their messages and
+//! the mapping from message types to destinations lives in a single routing table.
+
+use crate::{
+    errors::MessagingError,
+    publisher::{PublishMessage, PublishReceipt, Publisher, delegate_publisher},
+};
+use async_trait::async_trait;
+use opentelemetry::Context;
+use std::{borrow::Cow, collections::HashMap, sync::Arc};
+
+/// A publisher middleware filling in the destination of messages from their type.
+///
+/// Messages with an empty `to` are sent to the destination routed for their `msg_type`.
+/// Messages that already have a destination are published unchanged. A message with an
+/// empty `to` and no route for its `msg_type`, or no `msg_type` at all, is rejected with
+/// `MessagingError::PublisherError`.
+pub struct RoutingPublisher {
+    inner: Arc<dyn Publisher>,
+    routes: HashMap<String, String>,
+}
+
+impl RoutingPublisher {
+    /// Creates a new routing publisher with an empty routing table.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The publisher to send routed messages to.
+    ///
+    /// # Returns
+    ///
+    /// A new `RoutingPublisher` instance.
+    pub fn new(inner: Arc<dyn Publisher>) -> Self {
+        RoutingPublisher {
+            inner,
+            routes: HashMap::new(),
+        }
+    }
+
+    /// Routes a message type to a destination, replacing any previous route for it.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg_type` - The message type to route.
+    /// * `to` - The destination of messages of that type.
+    ///
+    /// # Returns
+    ///
+    /// The updated `RoutingPublisher`.
+    pub fn route<T>(mut self, msg_type: T, to: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.routes.insert(msg_type.into(), to.into());
+        self
+    }
+
+    /// Adds every route of a table, replacing existing routes for the same message types.
+    ///
+    /// # Arguments
+    ///
+    /// * `routes` - The table mapping message types to destinations.
+    ///
+    /// # Returns
+    ///
+    /// The updated `RoutingPublisher`.
+    pub fn with_routes(mut self, routes: HashMap<String, String>) -> Self {
+        self.routes.extend(routes);
+        self
+    }
+
+    fn resolve<'a>(
+        &self,
+        msg: &'a PublishMessage,
+    ) -> Result<Cow<'a, PublishMessage>, MessagingError> {
+        if !msg.to.is_empty() {
+            return Ok(Cow::Borrowed(msg));
+        }
+
+        let to = msg
+            .msg_type
+            .as_ref()
+            .and_then(|msg_type| self.routes.get(msg_type))
+            .ok_or(MessagingError::PublisherError { detail: None })?;
+
+        Ok(Cow::Owned(msg.with_to(to)))
+    }
+}
+
+#[async_trait]
+impl Publisher for RoutingPublisher {
+    async fn publish(&self, ctx: &Context, msg: &PublishMessage) -> Result<(), MessagingError> {
+        self.inner.publish(ctx, &*self.resolve(msg)?).await
+    }
+
+    async fn publish_confirmed(
+        &self,
+        ctx: &Context,
+        msg: &PublishMessage,
+    ) -> Result<PublishReceipt, MessagingError> {
+        self.inner
+            .publish_confirmed(ctx, &*self.resolve(msg)?)
+            .await
+    }
+
+    delegate_publisher!(inner);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{TestPublisher, block_on, message};
+
+    fn routing(inner: &Arc<TestPublisher>) -> RoutingPublisher {
+        RoutingPublisher::new(inner.clone())
+            .route("event", "events")
+            .with_routes(HashMap::from([(
+                "command".to_owned(),
+                "commands".to_owned(),
+            )]))
+    }
+
+    fn publish(publisher: &RoutingPublisher, msg: &PublishMessage) -> Result<(), MessagingError> {
+        block_on(publisher.publish(&Context::new(), msg))
+    }
+
+    #[test]
+    fn routes_messages_without_destination_by_type() {
+        let inner = Arc::new(TestPublisher::default());
+        let publisher = routing(&inner);
+
+        publish(&publisher, &message("", "a")).unwrap();
+        publish(&publisher, &message("", "b").with_msg_type("command")).unwrap();
+
+        let to: Vec<_> = inner.published().into_iter().map(|msg| msg.to).collect();
+        assert_eq!(to, ["events", "commands"]);
+    }
+
+    #[test]
+    fn routed_copies_only_differ_in_the_destination() {
+        let inner = Arc::new(TestPublisher::default());
+        let publisher = routing(&inner);
+        let mut msg = message("", "a");
+        msg.set_header("tenant", "acme");
+
+        publish(&publisher, &msg).unwrap();
+
+        assert_eq!(msg.to, "");
+        assert_eq!(inner.published(), [msg.with_to("events")]);
+    }
+
+    #[test]
+    fn keeps_explicit_destinations() {
+        let inner = Arc::new(TestPublisher::default());
+        let publisher = routing(&inner);
+
+        block_on(publisher.publish_confirmed(&Context::new(), &message("audit", "a"))).unwrap();
+
+        assert_eq!(inner.published()[0].to, "audit");
+    }
+
+    #[test]
+    fn rejects_unroutable_messages() {
+        let inner = Arc::new(TestPublisher::default());
+        let publisher = routing(&inner);
+        let untyped = PublishMessage::new(None, "", None, None, b"data", None);
+
+        for msg in [message("", "a").with_msg_type("query"), untyped] {
+            assert_eq!(
+                publish(&publisher, &msg),
+                Err(MessagingError::PublisherError { detail: None })
+            );
+        }
+        assert!(inner.published().is_empty());
+    }
+}
@@ -0,0 +1,149 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Size Limit
+//!
+//! This module provides a publisher middleware that rejects oversized messages before they
+//! reach the broker client.
+//!
+//! Brokers cap the size of the messages they accept, e.g. Kafka's `message.max.bytes`.
+//! Checking the size client-side fails fast, without a network round-trip.
+
+use crate::{
+    errors::MessagingError,
+    headers,
+    publisher::{PublishMessage, PublishReceipt, Publisher, delegate_publisher},
+};
+use async_trait::async_trait;
+use opentelemetry::Context;
+use std::sync::Arc;
+
+/// A publisher middleware that rejects messages larger than a maximum size.
+///
+/// The size of a message is estimated as the length of its data plus the size of its
+/// headers estimated by [`headers::estimated_size`]. The estimate ignores protocol framing
+/// and the other message fields, so `max_bytes` should leave some headroom below the broker
+/// limit.
+///
+/// Messages whose estimated size exceeds `max_bytes` are rejected with a
+/// `MessagingError::PublisherError` whose detail states the estimated size and the limit,
+/// and never reach the inner publisher.
+pub struct SizeLimitedPublisher {
+    inner: Arc<dyn Publisher>,
+    max_bytes: usize,
+}
+
+impl SizeLimitedPublisher {
+    /// Creates a new size-limited publisher.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The publisher to send messages within the limit to.
+    /// * `max_bytes` - The maximum estimated size of a message in bytes.
+    ///
+    /// # Returns
+    ///
+    /// A new `SizeLimitedPublisher` instance.
+    pub fn new(inner: Arc<dyn Publisher>, max_bytes: usize) -> Self {
+        SizeLimitedPublisher { inner, max_bytes }
+    }
+
+    /// Estimates the size of a message as described on [`SizeLimitedPublisher`].
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The message to measure.
+    ///
+    /// # Returns
+    ///
+    /// The estimated size in bytes.
+    pub fn estimated_size(msg: &PublishMessage) -> usize {
+        msg.data.len() + msg.headers.as_ref().map_or(0, headers::estimated_size)
+    }
+
+    fn check(&self, msg: &PublishMessage) -> Result<(), MessagingError> {
+        let size = Self::estimated_size(msg);
+        if size > self.max_bytes {
+            return Err(MessagingError::publisher(format!(
+                "message to `{}` has an estimated size of {size} bytes, more than the limit of {} bytes",
+                msg.to, self.max_bytes
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Publisher for SizeLimitedPublisher {
+    async fn publish(&self, ctx: &Context, msg: &PublishMessage) -> Result<(), MessagingError> {
+        self.check(msg)?;
+        self.inner.publish(ctx, msg).await
+    }
+
+    async fn publish_confirmed(
+        &self,
+        ctx: &Context,
+        msg: &PublishMessage,
+    ) -> Result<PublishReceipt, MessagingError> {
+        self.check(msg)?;
+        self.inner.publish_confirmed(ctx, msg).await
+    }
+
+    delegate_publisher!(inner);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        publisher::HeaderValues,
+        test_support::{TestPublisher, block_on, message},
+    };
+
+    /// A message of `size` estimated bytes: a 2-byte header and the rest as data.
+    fn sized(size: usize) -> PublishMessage {
+        let mut msg = message("orders", &"x".repeat(size - 2));
+        msg.set_header("k", HeaderValues::from("v"));
+        msg
+    }
+
+    fn publish(limit: usize, msg: &PublishMessage) -> (Result<(), MessagingError>, usize) {
+        let inner = Arc::new(TestPublisher::default());
+        let publisher = SizeLimitedPublisher::new(inner.clone(), limit);
+        let result = block_on(publisher.publish(&Context::new(), msg));
+        (result, inner.published().len())
+    }
+
+    #[test]
+    fn estimates_data_and_headers() {
+        assert_eq!(SizeLimitedPublisher::estimated_size(&sized(10)), 10);
+    }
+
+    #[test]
+    fn publishes_messages_just_under_and_at_the_limit() {
+        assert_eq!(publish(100, &sized(99)), (Ok(()), 1));
+        assert_eq!(publish(100, &sized(100)), (Ok(()), 1));
+    }
+
+    #[test]
+    fn rejects_messages_just_over_the_limit_with_the_sizes() {
+        let (result, published) = publish(100, &sized(101));
+
+        assert_eq!(published, 0);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "failure to publish message: message to `orders` has an estimated size of 101 bytes, \
+             more than the limit of 100 bytes"
+        );
+    }
+
+    #[test]
+    fn checks_publish_confirmed() {
+        let inner = Arc::new(TestPublisher::default());
+        let publisher = SizeLimitedPublisher::new(inner.clone(), 100);
+
+        let result = block_on(publisher.publish_confirmed(&Context::new(), &sized(101)));
+        assert!(result.unwrap_err().detail().is_some());
+    }
+}
@@ -0,0 +1,112 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Timeout
+//!
+//! This module provides a consumer middleware that bounds how long a handler may run.
+//!
+//! A misbehaving handler would otherwise block consumption indefinitely. Only the handler
+//! future is bounded; the broker delivery machinery of the dispatcher is left untouched.
+
+use crate::{
+    errors::MessagingError,
+    handler::{ConsumerHandler, ConsumerMessage},
+    timer::{self, Timer},
+};
+use async_trait::async_trait;
+use opentelemetry::Context;
+use std::{sync::Arc, time::Duration};
+
+/// A consumer middleware that fails messages whose handler exceeds a timeout.
+///
+/// When the timeout elapses the handler future is dropped and
+/// `MessagingError::TimeoutError` is returned. Work the handler already handed off to other
+/// tasks or threads may still be running afterwards.
+pub struct TimeoutHandler {
+    inner: Arc<dyn ConsumerHandler>,
+    timeout: Duration,
+    timer: Arc<dyn Timer>,
+}
+
+impl TimeoutHandler {
+    /// Creates a new timeout handler.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The handler to bound.
+    /// * `timeout` - The maximum time the handler may run.
+    /// * `timer` - The timer used to measure the timeout.
+    ///
+    /// # Returns
+    ///
+    /// A new `TimeoutHandler` instance.
+    pub fn new(inner: Arc<dyn ConsumerHandler>, timeout: Duration, timer: Arc<dyn Timer>) -> Self {
+        TimeoutHandler {
+            inner,
+            timeout,
+            timer,
+        }
+    }
+}
+
+#[async_trait]
+impl ConsumerHandler for TimeoutHandler {
+    async fn exec(&self, ctx: &Context, msg: &ConsumerMessage) -> Result<(), MessagingError> {
+        timer::timeout(&*self.timer, self.timeout, self.inner.exec(ctx, msg))
+            .await
+            .unwrap_or(Err(MessagingError::TimeoutError))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{handler::fn_handler, test_support::block_on, timer::ThreadTimer};
+
+    fn sleeping(
+        duration: Duration,
+        result: Result<(), MessagingError>,
+    ) -> Arc<dyn ConsumerHandler> {
+        fn_handler(move |_, _| {
+            let result = result.clone();
+            async move {
+                ThreadTimer.sleep(duration).await;
+                result
+            }
+        })
+    }
+
+    fn exec(inner: Arc<dyn ConsumerHandler>, timeout: Duration) -> Result<(), MessagingError> {
+        let handler = TimeoutHandler::new(inner, timeout, Arc::new(ThreadTimer));
+        let msg = ConsumerMessage::new("orders", "created", b"data", None);
+        block_on(handler.exec(&Context::new(), &msg))
+    }
+
+    #[test]
+    fn completes_handlers_under_the_limit() {
+        let inner = sleeping(Duration::from_millis(1), Ok(()));
+
+        assert_eq!(exec(inner, Duration::from_secs(10)), Ok(()));
+    }
+
+    #[test]
+    fn fails_handlers_sleeping_past_the_limit() {
+        let inner = sleeping(Duration::from_secs(10), Ok(()));
+
+        assert_eq!(
+            exec(inner, Duration::from_millis(10)),
+            Err(MessagingError::TimeoutError)
+        );
+    }
+
+    #[test]
+    fn returns_handler_errors_under_the_limit() {
+        let inner = sleeping(Duration::from_millis(1), Err(MessagingError::HandlerError));
+
+        assert_eq!(
+            exec(inner, Duration::from_secs(10)),
+            Err(MessagingError::HandlerError)
+        );
+    }
+}
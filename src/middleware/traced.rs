@@ -0,0 +1,241 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Traced
+//!
+//! This module provides a consumer middleware that emits a `tracing` span per message.
+//!
+//! It is only available with the `tracing` feature.
+
+use crate::{
+    errors::MessagingError,
+    handler::{ConsumerHandler, ConsumerMessage},
+};
+use async_trait::async_trait;
+use opentelemetry::{Context, trace::TraceContextExt};
+use std::sync::Arc;
+use tracing::{Instrument, field};
+
+/// A consumer middleware that runs the inner handler inside a `tracing` span.
+///
+/// The span is named `consume` and carries the `from`, `msg_type` and `size` (data length
+/// in bytes) fields, with `otel.name` set to the message type so OpenTelemetry exporters
+/// name the span after it. When the message carries a `traceparent` header, its value is
+/// recorded in the `trace_parent` field so the span can be correlated with the producer.
+///
+/// The span is not parented to the OpenTelemetry span of the context: linking `tracing`
+/// spans to OpenTelemetry spans requires a `tracing-opentelemetry` bridge, which the crate
+/// does not depend on. Instead, when the context carries a valid span context, e.g. one a
+/// dispatcher extracted from the message headers, its ids are recorded in the `trace_id`
+/// and `parent_span_id` fields. Once the handler returns, the `otel.status_code` field is set to `OK` or `ERROR` and
+/// errors are recorded in the `error` field.
+pub struct TracedHandler {
+    inner: Arc<dyn ConsumerHandler>,
+}
+
+impl TracedHandler {
+    /// Creates a new traced handler.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The handler to run inside the span.
+    ///
+    /// # Returns
+    ///
+    /// A new `TracedHandler` instance.
+    pub fn new(inner: Arc<dyn ConsumerHandler>) -> Self {
+        TracedHandler { inner }
+    }
+}
+
+#[async_trait]
+impl ConsumerHandler for TracedHandler {
+    async fn exec(&self, ctx: &Context, msg: &ConsumerMessage) -> Result<(), MessagingError> {
+        let span = tracing::info_span!(
+            "consume",
+            otel.name = msg.msg_type.as_str(),
+            from = msg.from.as_str(),
+            msg_type = msg.msg_type.as_str(),
+            size = msg.data.len(),
+            trace_parent = msg.trace_parent(),
+            trace_id = field::Empty,
+            parent_span_id = field::Empty,
+            otel.status_code = field::Empty,
+            error = field::Empty,
+        );
+
+        let span_context = ctx.span().span_context().clone();
+        if span_context.is_valid() {
+            span.record("trace_id", field::display(span_context.trace_id()));
+            span.record("parent_span_id", field::display(span_context.span_id()));
+        }
+
+        let result = self.inner.exec(ctx, msg).instrument(span.clone()).await;
+
+        match &result {
+            Ok(()) => {
+                span.record("otel.status_code", "OK");
+            }
+            Err(err) => {
+                span.record("otel.status_code", "ERROR");
+                span.record("error", field::display(err));
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        headers,
+        test_support::{TestHandler, block_on},
+    };
+    use opentelemetry::trace::{SpanContext, SpanId, TraceFlags, TraceId, TraceState};
+    use std::{
+        collections::HashMap,
+        fmt,
+        sync::{Mutex, PoisonError},
+    };
+    use tracing::{
+        Event, Metadata, Subscriber,
+        field::{Field, Visit},
+        span::{Attributes, Id, Record},
+        subscriber,
+    };
+
+    type Fields = HashMap<&'static str, String>;
+
+    /// A subscriber collecting the fields of every span it is given.
+    #[derive(Clone, Default)]
+    struct SpanCollector {
+        spans: Arc<Mutex<Vec<(&'static str, Fields)>>>,
+    }
+
+    struct FieldVisitor<'a>(&'a mut Fields);
+
+    impl Visit for FieldVisitor<'_> {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0.insert(field.name(), value.to_owned());
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            self.0.insert(field.name(), format!("{value:?}"));
+        }
+    }
+
+    impl SpanCollector {
+        fn spans(&self) -> Vec<(&'static str, Fields)> {
+            self.spans
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clone()
+        }
+    }
+
+    impl Subscriber for SpanCollector {
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let mut fields = Fields::new();
+            span.record(&mut FieldVisitor(&mut fields));
+
+            let mut spans = self.spans.lock().unwrap_or_else(PoisonError::into_inner);
+            spans.push((span.metadata().name(), fields));
+            Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, span: &Id, values: &Record<'_>) {
+            let mut spans = self.spans.lock().unwrap_or_else(PoisonError::into_inner);
+            let index = usize::try_from(span.into_u64()).unwrap() - 1;
+            values.record(&mut FieldVisitor(&mut spans[index].1));
+        }
+
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+        fn event(&self, _event: &Event<'_>) {}
+
+        fn enter(&self, _span: &Id) {}
+
+        fn exit(&self, _span: &Id) {}
+    }
+
+    fn consume_with(
+        ctx: &Context,
+        handler: &TracedHandler,
+        msgs: &[ConsumerMessage],
+    ) -> Vec<(&'static str, Fields)> {
+        let collector = SpanCollector::default();
+        subscriber::with_default(collector.clone(), || {
+            for msg in msgs {
+                let _ = block_on(handler.exec(ctx, msg));
+            }
+        });
+        collector.spans()
+    }
+
+    fn consume(handler: &TracedHandler, msgs: &[ConsumerMessage]) -> Vec<(&'static str, Fields)> {
+        consume_with(&Context::new(), handler, msgs)
+    }
+
+    #[test]
+    fn emits_a_span_per_message() {
+        let handler = TracedHandler::new(Arc::new(TestHandler::default()));
+        let mut traced = ConsumerMessage::new("orders", "created", b"data", None);
+        traced.set_header(headers::TRACE_PARENT, "00-trace-span-01");
+        let untraced = ConsumerMessage::new("invoices", "paid", b"", None);
+
+        let spans = consume(&handler, &[traced, untraced]);
+
+        assert_eq!(spans.len(), 2);
+        let (name, fields) = &spans[0];
+        assert_eq!(*name, "consume");
+        assert_eq!(fields["otel.name"], "created");
+        assert_eq!(fields["from"], "orders");
+        assert_eq!(fields["msg_type"], "created");
+        assert_eq!(fields["size"], "4");
+        assert_eq!(fields["trace_parent"], "00-trace-span-01");
+        assert_eq!(fields["otel.status_code"], "OK");
+        assert!(!spans[1].1.contains_key("trace_parent"));
+        assert!(!spans[0].1.contains_key("trace_id"));
+        assert!(!spans[0].1.contains_key("parent_span_id"));
+    }
+
+    #[test]
+    fn records_the_span_context_of_the_context() {
+        let handler = TracedHandler::new(Arc::new(TestHandler::default()));
+        let ctx = Context::new().with_remote_span_context(SpanContext::new(
+            TraceId::from_hex("4bf92f3577b34da6a3ce929d0e0e4736").unwrap(),
+            SpanId::from_hex("00f067aa0ba902b7").unwrap(),
+            TraceFlags::SAMPLED,
+            true,
+            TraceState::default(),
+        ));
+        let msg = ConsumerMessage::new("orders", "created", b"data", None);
+
+        let spans = consume_with(&ctx, &handler, &[msg]);
+
+        assert_eq!(spans[0].1["trace_id"], "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(spans[0].1["parent_span_id"], "00f067aa0ba902b7");
+    }
+
+    #[test]
+    fn records_handler_errors_on_the_span() {
+        let handler =
+            TracedHandler::new(Arc::new(TestHandler::failing(MessagingError::HandlerError)));
+        let msg = ConsumerMessage::new("orders", "created", b"data", None);
+
+        let spans = consume(&handler, &[msg]);
+
+        assert_eq!(spans[0].1["otel.status_code"], "ERROR");
+        assert_eq!(
+            spans[0].1["error"],
+            MessagingError::HandlerError.to_string()
+        );
+    }
+}
@@ -0,0 +1,136 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Validation
+//!
+//! This module provides a consumer middleware that validates messages before they reach
+//! the inner handler.
+//!
+//! Validation is pluggable through the [`MessageValidator`] trait, e.g. to check `data`
+//! against a JSON Schema on schema-governed topics. Wrapping the [`ValidatingHandler`] in a
+//! `DeadLetterHandler` routes rejected messages to a dead-letter destination.
+
+use crate::{
+    errors::MessagingError,
+    handler::{ConsumerHandler, ConsumerMessage},
+};
+use async_trait::async_trait;
+use opentelemetry::Context;
+use std::sync::Arc;
+
+#[cfg(feature = "mocks")]
+use mockall::*;
+
+/// Defines a check a message must pass before it is handled.
+#[cfg_attr(feature = "mocks", automock)]
+pub trait MessageValidator: Send + Sync {
+    /// Validates a message.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The message to validate.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the message is valid, or an error describing why it is not.
+    fn validate(&self, msg: &ConsumerMessage) -> Result<(), MessagingError>;
+}
+
+/// A validator requiring messages to carry a `content-type` header.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RequireContentType;
+
+impl MessageValidator for RequireContentType {
+    fn validate(&self, msg: &ConsumerMessage) -> Result<(), MessagingError> {
+        msg.content_type()
+            .map(|_| ())
+            .ok_or(MessagingError::DeserializingError)
+    }
+}
+
+/// A consumer middleware that runs a validator before invoking the inner handler.
+///
+/// Messages failing validation are rejected with `MessagingError::DeserializingError`,
+/// whatever the error returned by the validator, and the inner handler is not invoked.
+pub struct ValidatingHandler {
+    inner: Arc<dyn ConsumerHandler>,
+    validator: Arc<dyn MessageValidator>,
+}
+
+impl ValidatingHandler {
+    /// Creates a new validating handler.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The handler to invoke with valid messages.
+    /// * `validator` - The validator every message must pass.
+    ///
+    /// # Returns
+    ///
+    /// A new `ValidatingHandler` instance.
+    pub fn new(inner: Arc<dyn ConsumerHandler>, validator: Arc<dyn MessageValidator>) -> Self {
+        ValidatingHandler { inner, validator }
+    }
+}
+
+#[async_trait]
+impl ConsumerHandler for ValidatingHandler {
+    async fn exec(&self, ctx: &Context, msg: &ConsumerMessage) -> Result<(), MessagingError> {
+        self.validator
+            .validate(msg)
+            .map_err(|_| MessagingError::DeserializingError)?;
+
+        self.inner.exec(ctx, msg).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        headers,
+        test_support::{TestHandler, block_on},
+    };
+
+    fn validate(msg: &ConsumerMessage) -> (Result<(), MessagingError>, usize) {
+        let inner = Arc::new(TestHandler::default());
+        let handler = ValidatingHandler::new(inner.clone(), Arc::new(RequireContentType));
+        let result = block_on(handler.exec(&Context::new(), msg));
+        (result, inner.received().len())
+    }
+
+    #[test]
+    fn handles_valid_messages() {
+        let mut msg = ConsumerMessage::new("orders", "created", b"{}", None);
+        msg.set_header(headers::CONTENT_TYPE, "application/json");
+
+        assert_eq!(validate(&msg), (Ok(()), 1));
+    }
+
+    #[test]
+    fn rejects_invalid_messages_without_handling_them() {
+        let msg = ConsumerMessage::new("orders", "created", b"{}", None);
+
+        assert_eq!(validate(&msg), (Err(MessagingError::DeserializingError), 0));
+    }
+
+    #[test]
+    fn maps_validator_errors_to_deserializing_errors() {
+        struct Rejecting;
+
+        impl MessageValidator for Rejecting {
+            fn validate(&self, _msg: &ConsumerMessage) -> Result<(), MessagingError> {
+                Err(MessagingError::consumer("schema mismatch"))
+            }
+        }
+
+        let handler = ValidatingHandler::new(Arc::new(TestHandler::default()), Arc::new(Rejecting));
+        let msg = ConsumerMessage::new("orders", "created", b"{}", None);
+
+        assert_eq!(
+            block_on(handler.exec(&Context::new(), &msg)),
+            Err(MessagingError::DeserializingError)
+        );
+    }
+}
@@ -0,0 +1,200 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Version Guard
+//!
+//! This module provides a consumer middleware that only handles supported schema versions.
+//!
+//! While a message format evolves, consumers may receive versions they cannot read yet or
+//! no longer read. The [`VersionGuardHandler`] checks the `schema-version` header against
+//! the range the handler supports and rejects, or dead-letters, the other messages.
+
+use crate::{
+    errors::MessagingError,
+    handler::{ConsumerHandler, ConsumerMessage},
+    middleware::dead_letter::dead_letter,
+    publisher::Publisher,
+};
+use async_trait::async_trait;
+use opentelemetry::Context;
+use std::{ops::RangeInclusive, sync::Arc};
+
+/// A consumer middleware passing only messages with a supported schema version to the
+/// inner handler.
+///
+/// The version is read with [`ConsumerMessage::schema_version`]. Messages without a
+/// version are unsupported unless [`VersionGuardHandler::allow_unversioned`] is set.
+/// Unsupported messages fail with `MessagingError::DeserializingError` by default. With a
+/// dead-letter target, they are published there with the `x-death-reason` header and
+/// `Ok(())` is returned so they are acknowledged; if the dead-letter publish fails, its
+/// error is returned instead.
+pub struct VersionGuardHandler {
+    inner: Arc<dyn ConsumerHandler>,
+    supported: RangeInclusive<u32>,
+    allow_unversioned: bool,
+    dead_letter: Option<(Arc<dyn Publisher>, String)>,
+}
+
+impl VersionGuardHandler {
+    /// Creates a new version guard.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The handler to process messages with a supported version.
+    /// * `supported` - The supported schema versions, e.g. `2..=3`.
+    ///
+    /// # Returns
+    ///
+    /// A new `VersionGuardHandler` instance.
+    pub fn new(inner: Arc<dyn ConsumerHandler>, supported: RangeInclusive<u32>) -> Self {
+        VersionGuardHandler {
+            inner,
+            supported,
+            allow_unversioned: false,
+            dead_letter: None,
+        }
+    }
+
+    /// Passes messages without a schema version to the inner handler.
+    ///
+    /// # Returns
+    ///
+    /// The updated `VersionGuardHandler`.
+    pub fn allow_unversioned(mut self) -> Self {
+        self.allow_unversioned = true;
+        self
+    }
+
+    /// Dead-letters unsupported messages instead of failing them.
+    ///
+    /// # Arguments
+    ///
+    /// * `publisher` - The publisher used to send dead-lettered messages.
+    /// * `target` - The dead-letter destination.
+    ///
+    /// # Returns
+    ///
+    /// The updated `VersionGuardHandler`.
+    pub fn with_dead_letter<T>(mut self, publisher: Arc<dyn Publisher>, target: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.dead_letter = Some((publisher, target.into()));
+        self
+    }
+
+    fn is_supported(&self, msg: &ConsumerMessage) -> bool {
+        match msg.schema_version() {
+            Some(version) => self.supported.contains(&version),
+            None => self.allow_unversioned,
+        }
+    }
+}
+
+#[async_trait]
+impl ConsumerHandler for VersionGuardHandler {
+    async fn exec(&self, ctx: &Context, msg: &ConsumerMessage) -> Result<(), MessagingError> {
+        if self.is_supported(msg) {
+            return self.inner.exec(ctx, msg).await;
+        }
+
+        let err = MessagingError::DeserializingError;
+        match &self.dead_letter {
+            Some((publisher, target)) => {
+                publisher
+                    .publish(ctx, &dead_letter(msg, target, &err))
+                    .await
+            }
+            None => Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        middleware::dead_letter::DEATH_REASON_HEADER,
+        publisher::HeaderValues,
+        test_support::{TestHandler, TestPublisher, block_on, delivered, message},
+    };
+
+    fn versioned(version: Option<u32>) -> ConsumerMessage {
+        let mut msg = message("orders", "data");
+        if let Some(version) = version {
+            msg = msg.with_schema_version(version);
+        }
+        delivered(&msg)
+    }
+
+    fn exec(guard: &VersionGuardHandler, version: Option<u32>) -> Result<(), MessagingError> {
+        block_on(guard.exec(&Context::new(), &versioned(version)))
+    }
+
+    #[test]
+    fn handles_versions_in_range() {
+        let inner = Arc::new(TestHandler::default());
+        let guard = VersionGuardHandler::new(inner.clone(), 2..=3);
+
+        assert_eq!(exec(&guard, Some(2)), Ok(()));
+        assert_eq!(exec(&guard, Some(3)), Ok(()));
+        assert_eq!(inner.received().len(), 2);
+    }
+
+    #[test]
+    fn rejects_too_old_and_too_new_versions() {
+        let inner = Arc::new(TestHandler::default());
+        let guard = VersionGuardHandler::new(inner.clone(), 2..=3);
+
+        assert_eq!(
+            exec(&guard, Some(1)),
+            Err(MessagingError::DeserializingError)
+        );
+        assert_eq!(
+            exec(&guard, Some(4)),
+            Err(MessagingError::DeserializingError)
+        );
+        assert!(inner.received().is_empty());
+    }
+
+    #[test]
+    fn unversioned_messages_are_rejected_unless_allowed() {
+        let inner = Arc::new(TestHandler::default());
+        let strict = VersionGuardHandler::new(inner.clone(), 2..=3);
+        let lenient = VersionGuardHandler::new(inner.clone(), 2..=3).allow_unversioned();
+
+        assert_eq!(exec(&strict, None), Err(MessagingError::DeserializingError));
+        assert_eq!(exec(&lenient, None), Ok(()));
+        assert_eq!(inner.received().len(), 1);
+    }
+
+    #[test]
+    fn dead_letters_unsupported_versions() {
+        let inner = Arc::new(TestHandler::default());
+        let dlq = Arc::new(TestPublisher::default());
+        let guard = VersionGuardHandler::new(inner.clone(), 2..=3)
+            .with_dead_letter(dlq.clone(), "orders.dlq");
+
+        assert_eq!(exec(&guard, Some(4)), Ok(()));
+
+        let published = dlq.published();
+        assert_eq!(published.len(), 1);
+        assert_eq!(published[0].to, "orders.dlq");
+        assert_eq!(
+            published[0].headers.as_ref().unwrap()[DEATH_REASON_HEADER],
+            HeaderValues::from(MessagingError::DeserializingError.to_string())
+        );
+        assert!(inner.received().is_empty());
+    }
+
+    #[test]
+    fn failed_dead_letter_publishes_fail_the_message() {
+        let dlq = Arc::new(TestPublisher::default());
+        dlq.fail_with(Some(MessagingError::ConnectionError));
+        let guard = VersionGuardHandler::new(Arc::new(TestHandler::default()), 2..=3)
+            .with_dead_letter(dlq, "orders.dlq");
+
+        assert_eq!(exec(&guard, Some(1)), Err(MessagingError::ConnectionError));
+    }
+}
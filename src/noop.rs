@@ -0,0 +1,194 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Noop
+//!
+//! This module provides null implementations of the messaging traits.
+//!
+//! Deployments with messaging disabled can swap in a [`NoopPublisher`] and a
+//! [`NoopDispatcher`] behind a configuration flag, instead of making every call site
+//! conditional.
+
+use crate::{
+    dispatcher::{Dispatcher, DispatcherDefinition},
+    errors::MessagingError,
+    handler::{ConsumerHandler, ConsumerMessage},
+    publisher::{PublishMessage, Publisher},
+};
+use async_trait::async_trait;
+use opentelemetry::Context;
+use std::{
+    future::poll_fn,
+    sync::{Arc, Mutex, PoisonError},
+    task::{Poll, Waker},
+    time::Duration,
+};
+
+/// A publisher that accepts and drops every message.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopPublisher;
+
+#[async_trait]
+impl Publisher for NoopPublisher {
+    async fn publish(&self, _ctx: &Context, _msg: &PublishMessage) -> Result<(), MessagingError> {
+        Ok(())
+    }
+}
+
+/// A dispatcher that never delivers messages.
+///
+/// Registering handlers has no effect and the dispatcher reports no definitions. By
+/// default, `consume_blocking` parks until `disconnect` or `drain` is called, like a
+/// dispatcher waiting on an idle broker; with [`NoopDispatcher::returning_immediately`] it
+/// returns `Ok(())` right away instead.
+#[derive(Default)]
+pub struct NoopDispatcher {
+    return_immediately: bool,
+    state: Mutex<ParkState>,
+}
+
+#[derive(Default)]
+struct ParkState {
+    stopped: bool,
+    waiters: Vec<Waker>,
+}
+
+impl NoopDispatcher {
+    /// Creates a new noop dispatcher whose `consume_blocking` parks.
+    ///
+    /// # Returns
+    ///
+    /// A new `NoopDispatcher` instance.
+    pub fn new() -> Self {
+        NoopDispatcher::default()
+    }
+
+    /// Makes `consume_blocking` return `Ok(())` immediately instead of parking.
+    ///
+    /// # Returns
+    ///
+    /// The updated `NoopDispatcher`.
+    pub fn returning_immediately(mut self) -> Self {
+        self.return_immediately = true;
+        self
+    }
+
+    fn stop(&self) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.stopped = true;
+        state.waiters.drain(..).for_each(Waker::wake);
+    }
+}
+
+#[async_trait]
+impl Dispatcher for NoopDispatcher {
+    fn register(
+        self,
+        _definition: &DispatcherDefinition,
+        _handler: Arc<dyn ConsumerHandler>,
+    ) -> Self {
+        self
+    }
+
+    fn register_ref(
+        &mut self,
+        _definition: &DispatcherDefinition,
+        _handler: Arc<dyn ConsumerHandler>,
+    ) -> &mut Self {
+        self
+    }
+
+    async fn consume_blocking(&self) -> Result<(), MessagingError> {
+        if self.return_immediately {
+            return Ok(());
+        }
+
+        poll_fn(|cx| {
+            let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+            if state.stopped {
+                Poll::Ready(Ok(()))
+            } else {
+                state.waiters.push(cx.waker().clone());
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    /// Returns no messages immediately.
+    async fn consume_n(&self, _n: usize) -> Result<Vec<ConsumerMessage>, MessagingError> {
+        Ok(Vec::new())
+    }
+
+    async fn list_destinations(&self) -> Result<Vec<String>, MessagingError> {
+        Ok(Vec::new())
+    }
+
+    async fn disconnect(&self) -> Result<(), MessagingError> {
+        self.stop();
+        Ok(())
+    }
+
+    async fn drain(&self, _timeout: Duration) -> Result<(), MessagingError> {
+        self.stop();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{TestHandler, block_on, message, poll_once};
+    use std::pin::pin;
+
+    #[test]
+    fn publishing_succeeds() {
+        let publisher = NoopPublisher;
+
+        assert_eq!(
+            block_on(publisher.publish(&Context::new(), &message("orders", "data"))),
+            Ok(())
+        );
+        assert_eq!(
+            block_on(publisher.publish_to(&Context::new(), "orders", b"data", None)),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn registering_has_no_effect() {
+        let handler = Arc::new(TestHandler::default());
+        let dispatcher = NoopDispatcher::new()
+            .returning_immediately()
+            .register(&DispatcherDefinition::new("orders", None), handler.clone())
+            .freeze();
+
+        assert_eq!(dispatcher.handler_count(), 0);
+        assert!(dispatcher.definitions().is_empty());
+        assert_eq!(block_on(dispatcher.consume_blocking()), Ok(()));
+        assert_eq!(block_on(dispatcher.consume_n(3)), Ok(Vec::new()));
+        assert_eq!(block_on(dispatcher.list_destinations()), Ok(Vec::new()));
+        assert!(handler.received().is_empty());
+    }
+
+    #[test]
+    fn consumption_parks_until_disconnect() {
+        let dispatcher = NoopDispatcher::new();
+        let mut consume = pin!(dispatcher.consume_blocking());
+
+        assert!(poll_once(consume.as_mut()).is_pending());
+        assert_eq!(block_on(dispatcher.disconnect()), Ok(()));
+        assert_eq!(poll_once(consume.as_mut()), Poll::Ready(Ok(())));
+    }
+
+    #[test]
+    fn consumption_parks_until_drain() {
+        let dispatcher = NoopDispatcher::new();
+        let mut consume = pin!(dispatcher.consume_blocking());
+
+        assert!(poll_once(consume.as_mut()).is_pending());
+        assert_eq!(block_on(dispatcher.drain(Duration::ZERO)), Ok(()));
+        assert_eq!(poll_once(consume.as_mut()), Poll::Ready(Ok(())));
+    }
+}
@@ -0,0 +1,83 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Partition
+//!
+//! This module provides deterministic partition selection for partitioned backends.
+//!
+//! Messages sharing a key must land on the same partition in every process and across
+//! restarts, which rules out the randomly seeded `DefaultHasher`. The 32-bit FNV-1a hash
+//! is used instead: it is stable, fast on short keys and spreads them evenly.
+
+const FNV_OFFSET_BASIS: u32 = 0x811c_9dc5;
+const FNV_PRIME: u32 = 0x0100_0193;
+
+/// Computes the 32-bit FNV-1a hash of a key.
+///
+/// # Arguments
+///
+/// * `key` - The key to hash.
+///
+/// # Returns
+///
+/// The hash, identical for the same key on every platform and in every process.
+pub fn fnv1a(key: &str) -> u32 {
+    key.bytes().fold(FNV_OFFSET_BASIS, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// Maps a key to one of a fixed number of partitions.
+///
+/// # Arguments
+///
+/// * `key` - The partition key of the message.
+/// * `partitions` - The number of partitions (at least 1).
+///
+/// # Returns
+///
+/// The partition index, in `0..partitions`.
+pub fn partition_for(key: &str, partitions: u32) -> u32 {
+    fnv1a(key) % partitions.max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_the_reference_fnv1a_hashes() {
+        assert_eq!(fnv1a(""), 0x811c_9dc5);
+        assert_eq!(fnv1a("a"), 0xe40c_292c);
+        assert_eq!(fnv1a("foobar"), 0xbf9c_f968);
+    }
+
+    #[test]
+    fn same_key_maps_to_the_same_partition() {
+        for key in ["order-1", "order-2", "customer-42"] {
+            let partition = partition_for(key, 12);
+            assert!(partition < 12);
+            assert!((0..100).all(|_| partition_for(key, 12) == partition));
+        }
+    }
+
+    #[test]
+    fn zero_partitions_map_to_the_first_one() {
+        assert_eq!(partition_for("order-1", 0), 0);
+        assert_eq!(partition_for("order-1", 1), 0);
+    }
+
+    #[test]
+    fn spreads_keys_roughly_evenly() {
+        let mut counts = [0usize; 8];
+        for n in 0..8000 {
+            counts[partition_for(&format!("order-{n}"), 8) as usize] += 1;
+        }
+
+        assert!(
+            counts.iter().all(|&count| (800..=1200).contains(&count)),
+            "uneven distribution: {counts:?}"
+        );
+    }
+}
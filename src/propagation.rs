@@ -0,0 +1,170 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Propagation
+//!
+//! This module carries OpenTelemetry baggage across the broker in message headers.
+//!
+//! Every baggage entry, e.g. a tenant or request id, is stored in its own header named
+//! after the entry key with the [`BAGGAGE_PREFIX`]. Publishers inject the baggage of the
+//! current context with [`inject_baggage_into_headers`] and consumers restore it with
+//! [`extract_baggage_from_headers`] before handling the message.
+
+use crate::publisher::HeaderValues;
+use opentelemetry::{
+    Context, Key, StringValue,
+    baggage::{Baggage, BaggageExt, BaggageMetadata},
+};
+use std::collections::HashMap;
+
+/// The prefix of the headers carrying baggage entries, e.g. `baggage-tenant`.
+pub const BAGGAGE_PREFIX: &str = "baggage-";
+
+/// Stores the baggage of a context in message headers.
+///
+/// Each entry becomes a `HeaderValues::LongString` header named after its key with the
+/// [`BAGGAGE_PREFIX`], replacing any previous value. Baggage metadata is not propagated.
+///
+/// # Arguments
+///
+/// * `ctx` - The context whose baggage to inject.
+/// * `headers` - The headers of the message to publish.
+pub fn inject_baggage_into_headers(ctx: &Context, headers: &mut HashMap<String, HeaderValues>) {
+    for (key, (value, _)) in ctx.baggage() {
+        headers.insert(
+            format!("{BAGGAGE_PREFIX}{}", key.as_str()),
+            HeaderValues::LongString(value.as_str().to_owned()),
+        );
+    }
+}
+
+/// Restores baggage stored by [`inject_baggage_into_headers`] into a context.
+///
+/// Headers with the [`BAGGAGE_PREFIX`] are added to the baggage already in the context,
+/// replacing entries with the same key. Entries that are not valid baggage, e.g. because
+/// the key contains a separator or the W3C size limits are exceeded, are skipped.
+///
+/// # Arguments
+///
+/// * `ctx` - The context to add the baggage to.
+/// * `headers` - The headers of the received message.
+///
+/// # Returns
+///
+/// A copy of the context with the extracted baggage.
+pub fn extract_baggage_from_headers(ctx: &Context, headers: &HashMap<String, String>) -> Context {
+    let extracted = headers.iter().filter_map(|(key, value)| {
+        let key = key.strip_prefix(BAGGAGE_PREFIX)?;
+        Some((
+            Key::from(key.to_owned()),
+            (StringValue::from(value.clone()), BaggageMetadata::default()),
+        ))
+    });
+
+    let baggage = ctx
+        .baggage()
+        .iter()
+        .map(|(key, entry)| (key.clone(), entry.clone()))
+        .chain(extracted)
+        .collect::<Baggage>();
+
+    ctx.with_baggage(baggage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::publisher::headers_to_string_map;
+    use opentelemetry::KeyValue;
+
+    fn with_baggage(entries: &[(&'static str, &'static str)]) -> Context {
+        Context::new().with_baggage(
+            entries
+                .iter()
+                .map(|(key, value)| KeyValue::new(*key, *value))
+                .collect::<Baggage>(),
+        )
+    }
+
+    fn entries(ctx: &Context) -> Vec<(String, String)> {
+        let mut entries: Vec<_> = ctx
+            .baggage()
+            .iter()
+            .map(|(key, (value, _))| (key.to_string(), value.to_string()))
+            .collect();
+        entries.sort();
+        entries
+    }
+
+    #[test]
+    fn injects_every_entry_as_a_prefixed_header() {
+        let ctx = with_baggage(&[("tenant", "acme"), ("request", "r-1")]);
+        let mut headers = HashMap::from([(
+            "baggage-tenant".to_owned(),
+            HeaderValues::LongString("stale".to_owned()),
+        )]);
+
+        inject_baggage_into_headers(&ctx, &mut headers);
+
+        assert_eq!(
+            headers,
+            HashMap::from([
+                (
+                    "baggage-tenant".to_owned(),
+                    HeaderValues::LongString("acme".to_owned())
+                ),
+                (
+                    "baggage-request".to_owned(),
+                    HeaderValues::LongString("r-1".to_owned())
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn baggage_round_trips_through_headers() {
+        let ctx = with_baggage(&[("tenant", "acme"), ("request", "r-1")]);
+        let mut headers = HashMap::new();
+        inject_baggage_into_headers(&ctx, &mut headers);
+
+        let extracted =
+            extract_baggage_from_headers(&Context::new(), &headers_to_string_map(headers));
+
+        assert_eq!(entries(&extracted), entries(&ctx));
+    }
+
+    #[test]
+    fn extraction_ignores_other_headers_and_merges_with_existing_baggage() {
+        let ctx = with_baggage(&[("tenant", "old"), ("region", "eu")]);
+        let headers = HashMap::from([
+            ("baggage-tenant".to_owned(), "acme".to_owned()),
+            ("content-type".to_owned(), "application/json".to_owned()),
+        ]);
+
+        let extracted = extract_baggage_from_headers(&ctx, &headers);
+
+        assert_eq!(
+            entries(&extracted),
+            [
+                ("region".to_owned(), "eu".to_owned()),
+                ("tenant".to_owned(), "acme".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn extraction_skips_invalid_entries() {
+        let headers = HashMap::from([
+            ("baggage-tenant".to_owned(), "acme".to_owned()),
+            ("baggage-bad,key".to_owned(), "value".to_owned()),
+        ]);
+
+        let extracted = extract_baggage_from_headers(&Context::new(), &headers);
+
+        assert_eq!(
+            entries(&extracted),
+            [("tenant".to_owned(), "acme".to_owned())]
+        );
+    }
+}
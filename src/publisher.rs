@@ -10,10 +10,23 @@
 //! while the `PublishMessage` struct represents a message to be sent with its metadata.
 //! The module also includes `HeaderValues` which provides type-safe header values for messages.
 
-use crate::errors::MessagingError;
+use crate::{
+    codec::CodecRegistry,
+    destination::Destination,
+    errors::MessagingError,
+    handler::{ConsumerMessage, DataPreview},
+    headers,
+    partition::partition_for,
+    spawn::Spawner,
+};
 use async_trait::async_trait;
 use opentelemetry::Context;
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 #[cfg(feature = "mocks")]
 use mockall::*;
@@ -22,7 +35,7 @@ use mockall::*;
 ///
 /// This enum allows for strongly-typed header values of different types,
 /// which can be useful for protocols that support different data types in headers.
-#[derive(Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum HeaderValues {
     /// A short string value.
     ShortString(String),
@@ -47,11 +60,111 @@ pub enum HeaderValues {
 
     /// A 64-bit unsigned integer value.
     LongLongUint(u64),
+
+    /// A raw byte array value, e.g. a binary token, which need not be valid UTF-8.
+    Bytes(Vec<u8>),
+}
+
+impl HeaderValues {
+    /// Converts the header value into a string without losing data.
+    ///
+    /// # Returns
+    ///
+    /// The string representation of the value, or `MessagingError::DeserializingError` if
+    /// the value is a byte array that is not valid UTF-8.
+    pub fn try_into_string(self) -> Result<String, MessagingError> {
+        match self {
+            HeaderValues::Bytes(v) => Ok(String::from_utf8(v)?),
+            other => Ok(other.into()),
+        }
+    }
+
+    /// Converts the header value into bytes without losing data.
+    ///
+    /// Byte arrays are returned as they are, strings as their UTF-8 bytes and integers as
+    /// the bytes of their decimal representation.
+    ///
+    /// # Returns
+    ///
+    /// The bytes of the value.
+    pub fn into_bytes(self) -> Vec<u8> {
+        match self {
+            HeaderValues::Bytes(v) => v,
+            other => String::from(other).into_bytes(),
+        }
+    }
+}
+
+impl From<i8> for HeaderValues {
+    /// Converts an 8-bit signed integer into a `HeaderValues::Int`.
+    fn from(val: i8) -> Self {
+        HeaderValues::Int(val)
+    }
+}
+
+impl From<i32> for HeaderValues {
+    /// Converts a 32-bit signed integer into a `HeaderValues::LongInt`.
+    fn from(val: i32) -> Self {
+        HeaderValues::LongInt(val)
+    }
+}
+
+impl From<i64> for HeaderValues {
+    /// Converts a 64-bit signed integer into a `HeaderValues::LongLongInt`.
+    fn from(val: i64) -> Self {
+        HeaderValues::LongLongInt(val)
+    }
+}
+
+impl From<u8> for HeaderValues {
+    /// Converts an 8-bit unsigned integer into a `HeaderValues::Uint`.
+    fn from(val: u8) -> Self {
+        HeaderValues::Uint(val)
+    }
+}
+
+impl From<u32> for HeaderValues {
+    /// Converts a 32-bit unsigned integer into a `HeaderValues::LongUint`.
+    fn from(val: u32) -> Self {
+        HeaderValues::LongUint(val)
+    }
+}
+
+impl From<u64> for HeaderValues {
+    /// Converts a 64-bit unsigned integer into a `HeaderValues::LongLongUint`.
+    fn from(val: u64) -> Self {
+        HeaderValues::LongLongUint(val)
+    }
+}
+
+impl From<Vec<u8>> for HeaderValues {
+    /// Converts a byte vector into a `HeaderValues::Bytes`.
+    fn from(val: Vec<u8>) -> Self {
+        HeaderValues::Bytes(val)
+    }
+}
+
+impl From<String> for HeaderValues {
+    /// Converts a string into a `HeaderValues::LongString`, which has no length limit.
+    fn from(val: String) -> Self {
+        HeaderValues::LongString(val)
+    }
+}
+
+impl From<&str> for HeaderValues {
+    /// Converts a string into a `HeaderValues::LongString`, which has no length limit.
+    fn from(val: &str) -> Self {
+        HeaderValues::LongString(val.to_owned())
+    }
 }
 
 impl From<HeaderValues> for String {
     /// Converts a header value to its string representation.
     ///
+    /// The conversion is lossy for byte arrays: invalid UTF-8 sequences are replaced with
+    /// `U+FFFD`. Use [`HeaderValues::try_into_string`] or [`HeaderValues::into_bytes`] to
+    /// keep binary values intact.
+    ///
     /// # Arguments
     ///
     /// * `val` - The header value to convert.
@@ -69,15 +182,52 @@ impl From<HeaderValues> for String {
             HeaderValues::Uint(v) => v.to_string(),
             HeaderValues::LongUint(v) => v.to_string(),
             HeaderValues::LongLongUint(v) => v.to_string(),
+            HeaderValues::Bytes(v) => String::from_utf8(v)
+                .unwrap_or_else(|err| String::from_utf8_lossy(err.as_bytes()).into_owned()),
         }
     }
 }
 
+/// Lifts a string header map into typed headers, e.g. the headers of a `ConsumerMessage`.
+///
+/// # Arguments
+///
+/// * `headers` - The string headers.
+///
+/// # Returns
+///
+/// The headers with every value as a `HeaderValues::LongString`.
+pub fn headers_from_string_map(headers: HashMap<String, String>) -> HashMap<String, HeaderValues> {
+    headers
+        .into_iter()
+        .map(|(key, value)| (key, HeaderValues::LongString(value)))
+        .collect()
+}
+
+/// Lowers typed headers into a string header map, for systems that only carry strings.
+///
+/// Values are converted with `From<HeaderValues> for String`, so byte arrays are converted
+/// lossily.
+///
+/// # Arguments
+///
+/// * `headers` - The typed headers.
+///
+/// # Returns
+///
+/// The headers with every value as its string representation.
+pub fn headers_to_string_map(headers: HashMap<String, HeaderValues>) -> HashMap<String, String> {
+    headers
+        .into_iter()
+        .map(|(key, value)| (key, String::from(value)))
+        .collect()
+}
+
 /// Represents a message to be published to a messaging broker.
 ///
 /// This struct contains the message content along with metadata such as the destination,
 /// routing key, message type, and headers.
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub struct PublishMessage {
     /// Optional source identifier for the message.
     pub from: Option<String>,
@@ -86,8 +236,19 @@ pub struct PublishMessage {
     pub to: String,
 
     /// Optional routing key for the message.
+    ///
+    /// The routing key selects which bindings of the destination receive the message, e.g.
+    /// the AMQP routing key matched against exchange bindings.
     pub key: Option<String>,
 
+    /// Optional partition key for the message.
+    ///
+    /// Unlike the routing key, the partition key does not decide which consumers receive
+    /// the message: it is hashed to pick a partition of the destination, so that messages
+    /// sharing a partition key are stored and consumed in order, e.g. the Kafka record key.
+    /// Implementations without partitions ignore it.
+    pub partition_key: Option<String>,
+
     /// Optional message type identifier.
     pub msg_type: Option<String>,
 
@@ -96,6 +257,51 @@ pub struct PublishMessage {
 
     /// Optional headers associated with the message.
     pub headers: Option<HashMap<String, HeaderValues>>,
+
+    /// Optional delay before the message is delivered to consumers.
+    ///
+    /// Unlike a time-to-live, which discards a message that was not consumed in time, a
+    /// delay holds the message back and only makes it available once the delay elapsed.
+    /// Implementations without native delay support should reject delayed messages with
+    /// `MessagingError::PublisherError` rather than deliver them immediately.
+    pub delay: Option<Duration>,
+
+    /// Optional time the message was sent at.
+    ///
+    /// Consumers read it back with `ConsumerMessage::age` to measure end-to-end latency.
+    /// Implementations store it in the `x-sent-at` header rendered by
+    /// [`PublishMessage::timestamp_header`].
+    pub timestamp: Option<SystemTime>,
+
+    /// Optional group the message belongs to, e.g. the Azure Service Bus session id.
+    ///
+    /// Messages of a group are expected to be delivered in order to a single consumer at a
+    /// time. Implementations without message groups ignore it.
+    pub group_id: Option<String>,
+
+    /// Optional delivery priority of the message, where higher values are delivered first,
+    /// e.g. the AMQP priority from 0 to 9.
+    ///
+    /// Implementations without priority queues ignore it.
+    pub priority: Option<u8>,
+}
+
+impl fmt::Debug for PublishMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PublishMessage")
+            .field("from", &self.from)
+            .field("to", &self.to)
+            .field("key", &self.key)
+            .field("partition_key", &self.partition_key)
+            .field("msg_type", &self.msg_type)
+            .field("data", &DataPreview(&self.data))
+            .field("headers", &self.headers)
+            .field("delay", &self.delay)
+            .field("timestamp", &self.timestamp)
+            .field("group_id", &self.group_id)
+            .field("priority", &self.priority)
+            .finish()
+    }
 }
 
 impl PublishMessage {
@@ -124,54 +330,1419 @@ impl PublishMessage {
     where
         T: Into<String>,
     {
-        let from = if from.is_some() {
-            let f = from.unwrap();
-            Some(f.into())
-        } else {
-            None
-        };
-
-        let key = if key.is_some() {
-            let k = key.unwrap();
-            Some(k.into())
-        } else {
-            None
-        };
-
-        let msg_type = if msg_type.is_some() {
-            let m = msg_type.unwrap();
-            Some(m.into())
-        } else {
-            None
-        };
-
         PublishMessage {
-            from,
+            from: from.map(Into::into),
             to: to.into(),
-            key,
-            msg_type,
+            key: key.map(Into::into),
+            partition_key: None,
+            msg_type: msg_type.map(Into::into),
             data: data.into(),
             headers,
+            delay: None,
+            timestamp: None,
+            group_id: None,
+            priority: None,
         }
     }
-}
 
-/// Defines the interface for publishing messages to a messaging broker.
-///
-/// Implementations of this trait provide the logic for sending messages
-/// to specific messaging systems like RabbitMQ, Kafka, MQTT, etc.
-#[cfg_attr(feature = "mocks", automock)]
-#[async_trait]
-pub trait Publisher: Send + Sync {
-    /// Publishes a message to the messaging broker.
+    /// Creates a new publish message holding a value encoded with a registry codec.
+    ///
+    /// The content type is stored in the `content-type` header.
     ///
     /// # Arguments
     ///
-    /// * `ctx` - The OpenTelemetry context for tracing and monitoring.
-    /// * `msg` - The message to publish.
+    /// * `to` - The destination for the message.
+    /// * `value` - The value to encode.
+    /// * `content_type` - The content type to encode the value as.
+    /// * `registry` - The registry to pick the codec from.
     ///
     /// # Returns
     ///
-    /// A `Result` indicating success or containing an error if publishing fails.
-    async fn publish(&self, ctx: &Context, msg: &PublishMessage) -> Result<(), MessagingError>;
+    /// A new `PublishMessage` instance, or `MessagingError::SerializingError` if no codec
+    /// is registered for the content type or encoding fails.
+    pub fn encode<T, V>(
+        to: T,
+        value: &V,
+        content_type: &str,
+        registry: &CodecRegistry,
+    ) -> Result<Self, MessagingError>
+    where
+        T: Into<String>,
+        V: 'static,
+    {
+        let data = registry.encode(content_type, value)?;
+        let mut msg = PublishMessage::new(None, to.into(), None, None, &data, None);
+        msg.set_header(
+            headers::CONTENT_TYPE,
+            HeaderValues::ShortString(content_type.to_owned()),
+        );
+        Ok(msg)
+    }
+
+    /// Returns a copy of the message with a different destination, e.g. to fan it out.
+    ///
+    /// Unlike the consuming `with_*` builders, this borrows the message, so the original is
+    /// left unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - The destination of the copy.
+    ///
+    /// # Returns
+    ///
+    /// A copy of the message with only `to` changed.
+    pub fn with_to<T>(&self, to: T) -> Self
+    where
+        T: Into<String>,
+    {
+        PublishMessage {
+            to: to.into(),
+            ..self.clone()
+        }
+    }
+
+    /// Returns a copy of the message with a different routing key.
+    ///
+    /// The original message is left unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The routing key of the copy.
+    ///
+    /// # Returns
+    ///
+    /// A copy of the message with only `key` changed.
+    pub fn with_key<T>(&self, key: T) -> Self
+    where
+        T: Into<String>,
+    {
+        PublishMessage {
+            key: Some(key.into()),
+            ..self.clone()
+        }
+    }
+
+    /// Returns a copy of the message with a different message type.
+    ///
+    /// The original message is left unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg_type` - The message type of the copy.
+    ///
+    /// # Returns
+    ///
+    /// A copy of the message with only `msg_type` changed.
+    pub fn with_msg_type<T>(&self, msg_type: T) -> Self
+    where
+        T: Into<String>,
+    {
+        PublishMessage {
+            msg_type: Some(msg_type.into()),
+            ..self.clone()
+        }
+    }
+
+    /// Sets the destination of the message.
+    ///
+    /// The destination name becomes `to`. For an exchange destination, `key` is set to its
+    /// routing key, replacing any previous routing key.
+    ///
+    /// # Arguments
+    ///
+    /// * `destination` - The destination of the message.
+    ///
+    /// # Returns
+    ///
+    /// The updated `PublishMessage`.
+    pub fn with_destination(mut self, destination: Destination) -> Self {
+        if let Destination::Exchange { routing_key, .. } = &destination {
+            self.key = routing_key.clone();
+        }
+        self.to = destination.into();
+        self
+    }
+
+    /// Sets the partition key of the message.
+    ///
+    /// # Arguments
+    ///
+    /// * `partition_key` - The key hashed to pick the partition of the destination.
+    ///
+    /// # Returns
+    ///
+    /// The updated `PublishMessage`.
+    pub fn with_partition_key<T>(mut self, partition_key: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.partition_key = Some(partition_key.into());
+        self
+    }
+
+    /// Selects the partition of the message with [`partition_for`].
+    ///
+    /// The partition key is hashed if set, otherwise the routing key.
+    ///
+    /// # Arguments
+    ///
+    /// * `partitions` - The number of partitions of the destination.
+    ///
+    /// # Returns
+    ///
+    /// The partition index, or `None` if the message has neither a partition key nor a
+    /// routing key.
+    pub fn partition(&self, partitions: u32) -> Option<u32> {
+        self.partition_key
+            .as_deref()
+            .or(self.key.as_deref())
+            .map(|key| partition_for(key, partitions))
+    }
+
+    /// Sets the group the message belongs to.
+    ///
+    /// # Arguments
+    ///
+    /// * `group_id` - The group identifier, e.g. a session id.
+    ///
+    /// # Returns
+    ///
+    /// The updated `PublishMessage`.
+    pub fn with_group_id<T>(mut self, group_id: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.group_id = Some(group_id.into());
+        self
+    }
+
+    /// Sets the delivery priority of the message.
+    ///
+    /// # Arguments
+    ///
+    /// * `priority` - The priority, where higher values are delivered first.
+    ///
+    /// # Returns
+    ///
+    /// The updated `PublishMessage`.
+    pub fn with_priority(mut self, priority: u8) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Sets the delay before the message is delivered.
+    ///
+    /// # Arguments
+    ///
+    /// * `delay` - How long to hold the message back.
+    ///
+    /// # Returns
+    ///
+    /// The updated `PublishMessage`.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+
+    /// Renders the delay as the `x-delay` header used by delayed-message exchanges.
+    ///
+    /// # Returns
+    ///
+    /// The header name and the delay in milliseconds, or `None` if no delay is set.
+    pub fn delay_header(&self) -> Option<(&'static str, HeaderValues)> {
+        self.delay.map(|delay| {
+            let millis = i64::try_from(delay.as_millis()).unwrap_or(i64::MAX);
+            (headers::DELAY, HeaderValues::LongLongInt(millis))
+        })
+    }
+
+    /// Stamps the message with the current time as the time it was sent at.
+    ///
+    /// # Returns
+    ///
+    /// The updated `PublishMessage`.
+    pub fn now(self) -> Self {
+        self.with_timestamp(SystemTime::now())
+    }
+
+    /// Sets the time the message was sent at.
+    ///
+    /// # Arguments
+    ///
+    /// * `timestamp` - The time the message was sent at.
+    ///
+    /// # Returns
+    ///
+    /// The updated `PublishMessage`.
+    pub fn with_timestamp(mut self, timestamp: SystemTime) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    /// Renders the timestamp as the `x-sent-at` header.
+    ///
+    /// # Returns
+    ///
+    /// The header name and the timestamp in milliseconds since the Unix epoch, or `None` if
+    /// no timestamp is set. Timestamps before the epoch are rendered as `0`.
+    pub fn timestamp_header(&self) -> Option<(&'static str, HeaderValues)> {
+        self.timestamp.map(|timestamp| {
+            let millis = timestamp.duration_since(UNIX_EPOCH).map_or(0, |since| {
+                i64::try_from(since.as_millis()).unwrap_or(i64::MAX)
+            });
+            (headers::SENT_AT, HeaderValues::LongLongInt(millis))
+        })
+    }
+
+    /// Sets the version of the schema the message data conforms to, stored in the
+    /// `schema-version` header as a `HeaderValues::LongUint`.
+    ///
+    /// # Arguments
+    ///
+    /// * `version` - The schema version.
+    ///
+    /// # Returns
+    ///
+    /// The updated `PublishMessage`.
+    pub fn with_schema_version(mut self, version: u32) -> Self {
+        self.set_header(headers::SCHEMA_VERSION, HeaderValues::LongUint(version));
+        self
+    }
+
+    /// Returns the schema version carried in the `schema-version` header.
+    ///
+    /// Values of other header types are accepted if they represent an unsigned integer, as
+    /// after a relay; other values are treated as absent.
+    pub fn schema_version(&self) -> Option<u32> {
+        match self.headers.as_ref()?.get(headers::SCHEMA_VERSION)? {
+            HeaderValues::LongUint(version) => Some(*version),
+            other => String::from(other.clone()).parse().ok(),
+        }
+    }
+
+    /// Returns the headers of the message, initializing them if the message has none.
+    pub fn ensure_headers(&mut self) -> &mut HashMap<String, HeaderValues> {
+        self.headers.get_or_insert_with(Default::default)
+    }
+
+    /// Sets a header, initializing the headers if the message has none.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The header key.
+    /// * `value` - The header value.
+    ///
+    /// # Returns
+    ///
+    /// The previous value of the header, if any.
+    pub fn set_header<K, V>(&mut self, key: K, value: V) -> Option<HeaderValues>
+    where
+        K: Into<String>,
+        V: Into<HeaderValues>,
+    {
+        self.ensure_headers().insert(key.into(), value.into())
+    }
+
+    /// Removes a header.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The header key.
+    ///
+    /// # Returns
+    ///
+    /// The removed value, or `None` if the message had no such header.
+    pub fn remove_header(&mut self, key: &str) -> Option<HeaderValues> {
+        self.headers.as_mut()?.remove(key)
+    }
+}
+
+impl From<ConsumerMessage> for PublishMessage {
+    /// Converts a received message into a message that can be published again.
+    ///
+    /// This is useful when relaying messages, e.g. to a dead-letter destination or to
+    /// another broker. The fields are mapped as follows:
+    ///
+    /// * `from`, `msg_type` and `group_id` are kept as they are.
+    /// * `data` is moved without copying.
+    /// * Each string header becomes a `HeaderValues::LongString`, since consumer headers
+    ///   carry no type information.
+    /// * `message_id` is stored in the `message-id` header, replacing a header value, so
+    ///   relays, dead letters and deduplication keep the id.
+    /// * `redelivered` and `delivery_count` are dropped, as the republished message starts
+    ///   a new delivery.
+    /// * `to` is left empty and `key` and `partition_key` are `None`, so callers are
+    ///   expected to set the destination before publishing.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The received message to convert.
+    ///
+    /// # Returns
+    ///
+    /// A new `PublishMessage` instance.
+    fn from(msg: ConsumerMessage) -> Self {
+        let mut converted = PublishMessage {
+            from: Some(msg.from),
+            to: String::new(),
+            key: None,
+            partition_key: None,
+            msg_type: Some(msg.msg_type),
+            data: msg.data,
+            headers: msg.headers.map(headers_from_string_map),
+            delay: None,
+            timestamp: None,
+            group_id: msg.group_id,
+            priority: None,
+        };
+
+        if let Some(id) = msg.message_id {
+            converted.set_header(headers::MESSAGE_ID, HeaderValues::ShortString(id));
+        }
+
+        converted
+    }
+}
+
+impl From<&ConsumerMessage> for PublishMessage {
+    /// Converts a borrowed received message into a message that can be published again.
+    ///
+    /// See the owned conversion for the field mapping.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The received message to convert.
+    ///
+    /// # Returns
+    ///
+    /// A new `PublishMessage` instance.
+    fn from(msg: &ConsumerMessage) -> Self {
+        PublishMessage::from(msg.clone())
+    }
+}
+
+/// Describes the outcome of a confirmed publish.
+///
+/// Fields a broker does not report are left `None`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PublishReceipt {
+    /// Optional broker-assigned sequence number or delivery tag of the message.
+    pub delivery_tag: Option<u64>,
+
+    /// Whether the broker confirmed that the message was persisted, as opposed to only
+    /// being accepted into a buffer.
+    pub confirmed: bool,
+
+    /// Optional partition the message was written to, for partitioned brokers.
+    pub partition: Option<u32>,
+
+    /// Optional offset of the message within its partition, for log-based brokers.
+    pub offset: Option<u64>,
+
+    /// Optional time the broker stored the message at, in milliseconds since the Unix
+    /// epoch.
+    pub broker_timestamp: Option<i64>,
+}
+
+/// Defines the interface for publishing messages to a messaging broker.
+///
+/// Implementations of this trait provide the logic for sending messages
+/// to specific messaging systems like RabbitMQ, Kafka, MQTT, etc.
+#[cfg_attr(feature = "mocks", automock)]
+#[async_trait]
+pub trait Publisher: Send + Sync {
+    /// Publishes a message to the messaging broker.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The OpenTelemetry context for tracing and monitoring.
+    /// * `msg` - The message to publish.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or containing an error if publishing fails.
+    async fn publish(&self, ctx: &Context, msg: &PublishMessage) -> Result<(), MessagingError>;
+
+    /// Publishes data to a destination without building a `PublishMessage`.
+    ///
+    /// The message is built with `PublishMessage::new`, without source, routing key or
+    /// message type, and passed to `publish`.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The OpenTelemetry context for tracing and monitoring.
+    /// * `to` - The destination for the message.
+    /// * `data` - The raw binary data of the message.
+    /// * `headers` - Optional headers associated with the message.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or containing an error if publishing fails.
+    async fn publish_to(
+        &self,
+        ctx: &Context,
+        to: &str,
+        data: &[u8],
+        headers: Option<HashMap<String, HeaderValues>>,
+    ) -> Result<(), MessagingError> {
+        let msg = PublishMessage::new(None, to, None, None, data, headers);
+        self.publish(ctx, &msg).await
+    }
+
+    /// Waits until every message published so far has been durably acknowledged.
+    ///
+    /// Publishers that buffer, batch or confirm asynchronously must only resolve once all
+    /// prior `publish` calls are confirmed by the broker, so callers can order messaging
+    /// with other side effects, e.g. committing a database transaction afterwards. The
+    /// default implementation returns immediately, which is correct for publishers whose
+    /// `publish` only returns after the broker acknowledged the message.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or containing an error if any prior publish failed.
+    async fn barrier(&self) -> Result<(), MessagingError> {
+        Ok(())
+    }
+
+    /// Publishes a message and reports whether the broker confirmed it.
+    ///
+    /// The default implementation calls `publish` and returns a receipt with
+    /// `confirmed: false`, since a plain publish gives no persistence guarantee.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The OpenTelemetry context for tracing and monitoring.
+    /// * `msg` - The message to publish.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the publish receipt or an error if publishing fails.
+    async fn publish_confirmed(
+        &self,
+        ctx: &Context,
+        msg: &PublishMessage,
+    ) -> Result<PublishReceipt, MessagingError> {
+        self.publish(ctx, msg).await?;
+        Ok(PublishReceipt::default())
+    }
+
+    /// Eagerly establishes the connection to the messaging broker.
+    ///
+    /// Calling it at startup lets an application fail fast on a bad configuration instead
+    /// of on its first publish. The default implementation does nothing, which suits
+    /// publishers that connect lazily or on construction.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or containing an error if connecting fails.
+    async fn connect(&self) -> Result<(), MessagingError> {
+        Ok(())
+    }
+
+    /// Cleanly closes the connection to the messaging broker.
+    ///
+    /// Implementations should flush pending messages before closing, so that shutting down
+    /// loses nothing. The default implementation does nothing.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or containing an error if disconnecting fails.
+    async fn disconnect(&self) -> Result<(), MessagingError> {
+        Ok(())
+    }
+
+    /// Checks whether the publisher is connected and able to publish.
+    ///
+    /// Intended for readiness and liveness probes. Implementations can ping the broker and
+    /// should report a lost connection with `MessagingError::ConnectionError`. The default
+    /// implementation always reports the publisher as healthy.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the publisher is healthy, or the error describing why it is not.
+    async fn health_check(&self) -> Result<(), MessagingError> {
+        Ok(())
+    }
+
+    /// Makes sure a destination exists before messages are published to it.
+    ///
+    /// Implementations can declare the exchange or topic, or verify that it exists, so that
+    /// a misconfigured destination fails fast instead of silently dropping messages. Wrap
+    /// the publisher in a `DeclaringPublisher` to call it once per destination. The default
+    /// implementation does nothing.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - The name of the destination.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or containing an error if the destination is missing
+    /// or cannot be declared.
+    async fn ensure_destination(&self, _to: &str) -> Result<(), MessagingError> {
+        Ok(())
+    }
+}
+
+/// Implements the connection management methods of a publisher middleware by forwarding
+/// them to the publisher in the given field.
+///
+/// Invoke it inside an `#[async_trait] impl Publisher` block after the methods the
+/// middleware implements itself. It forwards `barrier`, `connect`, `disconnect`,
+/// `health_check` and `ensure_destination`; methods implemented by hand are listed in
+/// `except = [..]` and left out. `publish`, `publish_to` and `publish_confirmed` are never
+/// forwarded, since a middleware must route them through its own logic.
+///
+/// # Arguments
+///
+/// * `$inner` - The name of the field holding the inner publisher.
+/// * `except = [..]` - Optional methods the middleware implements itself.
+macro_rules! delegate_publisher {
+    ($inner:ident) => {
+        $crate::publisher::delegate_publisher!($inner, except = []);
+    };
+    ($inner:ident, except = [$($except:ident),* $(,)?]) => {
+        $crate::publisher::delegate_publisher!(@unless barrier [$($except)*] $inner);
+        $crate::publisher::delegate_publisher!(@unless connect [$($except)*] $inner);
+        $crate::publisher::delegate_publisher!(@unless disconnect [$($except)*] $inner);
+        $crate::publisher::delegate_publisher!(@unless health_check [$($except)*] $inner);
+        $crate::publisher::delegate_publisher!(@unless ensure_destination [$($except)*] $inner);
+    };
+    (@unless barrier [barrier $($rest:ident)*] $inner:ident) => {};
+    (@unless connect [connect $($rest:ident)*] $inner:ident) => {};
+    (@unless disconnect [disconnect $($rest:ident)*] $inner:ident) => {};
+    (@unless health_check [health_check $($rest:ident)*] $inner:ident) => {};
+    (@unless ensure_destination [ensure_destination $($rest:ident)*] $inner:ident) => {};
+    (@unless $method:ident [$other:ident $($rest:ident)*] $inner:ident) => {
+        $crate::publisher::delegate_publisher!(@unless $method [$($rest)*] $inner);
+    };
+    (@unless ensure_destination [] $inner:ident) => {
+        fn ensure_destination<'life0, 'life1, 'async_trait>(
+            &'life0 self,
+            to: &'life1 str,
+        ) -> ::std::pin::Pin<
+            Box<
+                dyn ::std::future::Future<Output = Result<(), $crate::errors::MessagingError>>
+                    + Send
+                    + 'async_trait,
+            >,
+        >
+        where
+            'life0: 'async_trait,
+            'life1: 'async_trait,
+            Self: 'async_trait,
+        {
+            Box::pin(async move { self.$inner.ensure_destination(to).await })
+        }
+    };
+    (@unless $method:ident [] $inner:ident) => {
+        fn $method<'life0, 'async_trait>(
+            &'life0 self,
+        ) -> ::std::pin::Pin<
+            Box<
+                dyn ::std::future::Future<Output = Result<(), $crate::errors::MessagingError>>
+                    + Send
+                    + 'async_trait,
+            >,
+        >
+        where
+            'life0: 'async_trait,
+            Self: 'async_trait,
+        {
+            Box::pin(async move { self.$inner.$method().await })
+        }
+    };
+}
+
+pub(crate) use delegate_publisher;
+
+#[async_trait]
+impl<P> Publisher for Arc<P>
+where
+    P: Publisher + ?Sized,
+{
+    async fn publish(&self, ctx: &Context, msg: &PublishMessage) -> Result<(), MessagingError> {
+        (**self).publish(ctx, msg).await
+    }
+
+    async fn publish_to(
+        &self,
+        ctx: &Context,
+        to: &str,
+        data: &[u8],
+        headers: Option<HashMap<String, HeaderValues>>,
+    ) -> Result<(), MessagingError> {
+        (**self).publish_to(ctx, to, data, headers).await
+    }
+
+    async fn barrier(&self) -> Result<(), MessagingError> {
+        (**self).barrier().await
+    }
+
+    async fn publish_confirmed(
+        &self,
+        ctx: &Context,
+        msg: &PublishMessage,
+    ) -> Result<PublishReceipt, MessagingError> {
+        (**self).publish_confirmed(ctx, msg).await
+    }
+
+    async fn connect(&self) -> Result<(), MessagingError> {
+        (**self).connect().await
+    }
+
+    async fn disconnect(&self) -> Result<(), MessagingError> {
+        (**self).disconnect().await
+    }
+
+    async fn health_check(&self) -> Result<(), MessagingError> {
+        (**self).health_check().await
+    }
+
+    async fn ensure_destination(&self, to: &str) -> Result<(), MessagingError> {
+        (**self).ensure_destination(to).await
+    }
+}
+
+#[async_trait]
+impl<P> Publisher for Box<P>
+where
+    P: Publisher + ?Sized,
+{
+    async fn publish(&self, ctx: &Context, msg: &PublishMessage) -> Result<(), MessagingError> {
+        (**self).publish(ctx, msg).await
+    }
+
+    async fn publish_to(
+        &self,
+        ctx: &Context,
+        to: &str,
+        data: &[u8],
+        headers: Option<HashMap<String, HeaderValues>>,
+    ) -> Result<(), MessagingError> {
+        (**self).publish_to(ctx, to, data, headers).await
+    }
+
+    async fn barrier(&self) -> Result<(), MessagingError> {
+        (**self).barrier().await
+    }
+
+    async fn publish_confirmed(
+        &self,
+        ctx: &Context,
+        msg: &PublishMessage,
+    ) -> Result<PublishReceipt, MessagingError> {
+        (**self).publish_confirmed(ctx, msg).await
+    }
+
+    async fn connect(&self) -> Result<(), MessagingError> {
+        (**self).connect().await
+    }
+
+    async fn disconnect(&self) -> Result<(), MessagingError> {
+        (**self).disconnect().await
+    }
+
+    async fn health_check(&self) -> Result<(), MessagingError> {
+        (**self).health_check().await
+    }
+
+    async fn ensure_destination(&self, to: &str) -> Result<(), MessagingError> {
+        (**self).ensure_destination(to).await
+    }
+}
+
+/// Publishes a message in the background without waiting for the outcome.
+///
+/// The publish is best-effort and at most once: it is not retried, and it is lost if the
+/// process exits before it completes. Use it for non-critical messages such as telemetry.
+///
+/// # Arguments
+///
+/// * `spawner` - The spawner running the publish.
+/// * `publisher` - The publisher to publish with.
+/// * `ctx` - The OpenTelemetry context for tracing and monitoring.
+/// * `msg` - The message to publish.
+/// * `on_error` - The callback invoked with the error if the publish fails.
+pub fn publish_detached<F>(
+    spawner: &dyn Spawner,
+    publisher: Arc<dyn Publisher>,
+    ctx: Context,
+    msg: PublishMessage,
+    on_error: F,
+) where
+    F: FnOnce(MessagingError) + Send + 'static,
+{
+    spawner.spawn(Box::pin(async move {
+        if let Err(err) = publisher.publish(&ctx, &msg).await {
+            on_error(err);
+        }
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        spawn::{BackgroundTask, ThreadSpawner},
+        test_support::{TestPublisher, block_on, message, poll_once},
+    };
+    use std::{pin::pin, sync::Mutex, task::Poll};
+
+    fn received() -> ConsumerMessage {
+        let mut msg = ConsumerMessage::new(
+            "orders",
+            "created",
+            b"data",
+            Some(HashMap::from([("tenant".to_owned(), "acme".to_owned())])),
+        );
+        msg.message_id = Some("42".to_owned());
+        msg.redelivered = true;
+        msg.delivery_count = Some(3);
+        msg.group_id = Some("customer-1".to_owned());
+        msg
+    }
+
+    #[test]
+    fn converts_a_consumer_message() {
+        let converted = PublishMessage::from(received());
+
+        assert_eq!(converted.from.as_deref(), Some("orders"));
+        assert_eq!(converted.to, "");
+        assert_eq!(converted.key, None);
+        assert_eq!(converted.msg_type.as_deref(), Some("created"));
+        assert_eq!(&*converted.data, b"data");
+        assert_eq!(converted.group_id.as_deref(), Some("customer-1"));
+
+        let headers = converted.headers.unwrap();
+        assert_eq!(
+            headers["tenant"],
+            HeaderValues::LongString("acme".to_owned())
+        );
+        assert_eq!(
+            headers[headers::MESSAGE_ID],
+            HeaderValues::ShortString("42".to_owned())
+        );
+    }
+
+    #[test]
+    fn message_id_replaces_a_message_id_header() {
+        let mut msg = received();
+        msg.set_header(headers::MESSAGE_ID, "stale");
+
+        let converted = PublishMessage::from(&msg);
+
+        assert_eq!(
+            converted.headers.unwrap()[headers::MESSAGE_ID],
+            HeaderValues::ShortString("42".to_owned())
+        );
+    }
+
+    #[test]
+    fn converts_a_message_without_headers_or_id() {
+        let msg = ConsumerMessage::new("orders", "created", b"data", None);
+
+        assert_eq!(PublishMessage::from(&msg).headers, None);
+    }
+
+    #[test]
+    fn borrowed_and_owned_conversions_agree() {
+        let msg = received();
+
+        assert_eq!(PublishMessage::from(&msg), PublishMessage::from(msg));
+    }
+
+    #[test]
+    fn default_barrier_resolves_immediately() {
+        let publisher = TestPublisher::default();
+
+        assert_eq!(poll_once(pin!(publisher.barrier())), Poll::Ready(Ok(())));
+    }
+
+    #[test]
+    fn delegated_barrier_reaches_the_inner_publisher() {
+        struct Failing;
+
+        #[async_trait]
+        impl Publisher for Failing {
+            async fn publish(
+                &self,
+                _ctx: &Context,
+                _msg: &PublishMessage,
+            ) -> Result<(), MessagingError> {
+                Ok(())
+            }
+
+            async fn barrier(&self) -> Result<(), MessagingError> {
+                Err(MessagingError::publisher("unconfirmed message"))
+            }
+        }
+
+        struct Wrapper {
+            inner: Failing,
+        }
+
+        #[async_trait]
+        impl Publisher for Wrapper {
+            async fn publish(
+                &self,
+                ctx: &Context,
+                msg: &PublishMessage,
+            ) -> Result<(), MessagingError> {
+                self.inner.publish(ctx, msg).await
+            }
+
+            delegate_publisher!(inner);
+        }
+
+        let wrapper = Wrapper { inner: Failing };
+
+        assert_eq!(
+            block_on(wrapper.barrier()),
+            Err(MessagingError::publisher("unconfirmed message"))
+        );
+        assert_eq!(block_on(wrapper.connect()), Ok(()));
+    }
+
+    #[test]
+    fn arc_dyn_publisher_is_a_publisher() {
+        fn publish(publisher: &impl Publisher) -> Result<(), MessagingError> {
+            block_on(publisher.publish_to(&Context::new(), "orders", b"data", None))
+        }
+
+        let inner = Arc::new(TestPublisher::default());
+        let publisher: Arc<dyn Publisher> = inner.clone();
+
+        assert_eq!(publish(&publisher), Ok(()));
+        assert_eq!(block_on(publisher.health_check()), Ok(()));
+        assert_eq!(inner.published()[0].to, "orders");
+    }
+
+    #[test]
+    fn default_publish_to_builds_the_message() {
+        let publisher = TestPublisher::default();
+        let headers = HashMap::from([("tenant".to_owned(), HeaderValues::from("acme"))]);
+
+        block_on(publisher.publish_to(&Context::new(), "orders", b"data", Some(headers.clone())))
+            .unwrap();
+
+        let published = publisher.published();
+        assert_eq!(published.len(), 1);
+        assert_eq!(published[0].to, "orders");
+        assert_eq!(published[0].msg_type, None);
+        assert_eq!(&*published[0].data, b"data");
+        assert_eq!(published[0].headers, Some(headers));
+    }
+
+    #[cfg(feature = "mocks")]
+    #[test]
+    fn mock_can_override_publish_to_behind_a_pointer() {
+        let mut inner = MockPublisher::new();
+        inner
+            .expect_publish_to()
+            .withf(|_, to, data, headers| to == "orders" && data == b"data" && headers.is_none())
+            .times(1)
+            .returning(|_, _, _, _| Ok(()));
+        inner.expect_publish().never();
+        let publisher: Arc<dyn Publisher> = Arc::new(inner);
+
+        assert_eq!(
+            block_on(publisher.publish_to(&Context::new(), "orders", b"data", None)),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn boxed_publisher_is_a_publisher() {
+        let publisher: Box<dyn Publisher> = Box::new(TestPublisher::default());
+
+        let receipt = block_on(publisher.publish_confirmed(&Context::new(), &received().into()));
+
+        assert_eq!(receipt, Ok(PublishReceipt::default()));
+    }
+
+    #[test]
+    fn default_publish_confirmed_publishes_without_confirmation() {
+        let publisher = TestPublisher::default();
+
+        let receipt = block_on(publisher.publish_confirmed(&Context::new(), &received().into()));
+
+        let receipt = receipt.unwrap();
+        assert!(!receipt.confirmed);
+        assert_eq!(receipt.delivery_tag, None);
+        assert_eq!(publisher.published().len(), 1);
+    }
+
+    #[test]
+    fn default_publish_confirmed_returns_publish_errors() {
+        let publisher = TestPublisher::default();
+        publisher.fail_with(Some(MessagingError::ConnectionError));
+
+        let receipt = block_on(publisher.publish_confirmed(&Context::new(), &received().into()));
+
+        assert_eq!(receipt, Err(MessagingError::ConnectionError));
+    }
+
+    #[test]
+    fn receipts_carry_every_broker_field() {
+        let receipt = PublishReceipt {
+            delivery_tag: Some(7),
+            confirmed: true,
+            partition: Some(3),
+            offset: Some(1024),
+            broker_timestamp: Some(1_700_000_000_123),
+        };
+
+        let cloned = receipt.clone();
+
+        assert_eq!(cloned, receipt);
+        assert_eq!(cloned.delivery_tag, Some(7));
+        assert!(cloned.confirmed);
+        assert_eq!(cloned.partition, Some(3));
+        assert_eq!(cloned.offset, Some(1024));
+        assert_eq!(cloned.broker_timestamp, Some(1_700_000_000_123));
+        assert_ne!(cloned, PublishReceipt::default());
+    }
+
+    #[cfg(feature = "mocks")]
+    #[test]
+    fn mock_can_return_a_populated_receipt() {
+        let receipt = PublishReceipt {
+            confirmed: true,
+            partition: Some(3),
+            offset: Some(1024),
+            ..PublishReceipt::default()
+        };
+        let mut publisher = MockPublisher::new();
+        let returned = receipt.clone();
+        publisher
+            .expect_publish_confirmed()
+            .times(1)
+            .returning(move |_, _| Ok(returned.clone()));
+
+        assert_eq!(
+            block_on(publisher.publish_confirmed(&Context::new(), &message("orders", "data"))),
+            Ok(receipt)
+        );
+    }
+
+    #[test]
+    fn debug_previews_the_data() {
+        let msg = PublishMessage::new(None, "orders", None, None, b"\x01\xab", None);
+
+        let debug = format!("{msg:?}");
+
+        assert!(debug.starts_with("PublishMessage { from: None, to: \"orders\""));
+        assert!(debug.contains("data: 2 bytes 0x01ab,"));
+    }
+
+    #[test]
+    fn messages_have_no_delay_by_default() {
+        let msg = PublishMessage::new(None, "orders", None, None, b"data", None);
+
+        assert_eq!(msg.delay, None);
+        assert_eq!(msg.delay_header(), None);
+    }
+
+    #[test]
+    fn renders_the_delay_in_milliseconds() {
+        let msg = PublishMessage::new(None, "orders", None, None, b"data", None)
+            .with_delay(Duration::from_millis(1500));
+
+        assert_eq!(msg.delay, Some(Duration::from_millis(1500)));
+        assert_eq!(
+            msg.delay_header(),
+            Some((headers::DELAY, HeaderValues::LongLongInt(1500)))
+        );
+    }
+
+    #[test]
+    fn saturates_delays_too_long_for_the_header() {
+        let msg = PublishMessage::new(None, "orders", None, None, b"data", None)
+            .with_delay(Duration::MAX);
+
+        assert_eq!(
+            msg.delay_header(),
+            Some((headers::DELAY, HeaderValues::LongLongInt(i64::MAX)))
+        );
+    }
+
+    #[test]
+    fn stamps_the_send_time() {
+        let before = SystemTime::now();
+        let msg = message("orders", "data").now();
+
+        let timestamp = msg.timestamp.unwrap();
+        assert!(timestamp >= before && timestamp <= SystemTime::now());
+        assert_eq!(message("orders", "data").timestamp_header(), None);
+    }
+
+    #[test]
+    fn renders_the_timestamp_in_milliseconds_since_the_epoch() {
+        let sent_at = UNIX_EPOCH + Duration::from_millis(1_700_000_000_123);
+        let before_epoch = UNIX_EPOCH - Duration::from_secs(1);
+
+        assert_eq!(
+            message("orders", "data")
+                .with_timestamp(sent_at)
+                .timestamp_header(),
+            Some((
+                headers::SENT_AT,
+                HeaderValues::LongLongInt(1_700_000_000_123)
+            ))
+        );
+        assert_eq!(
+            message("orders", "data")
+                .with_timestamp(before_epoch)
+                .timestamp_header(),
+            Some((headers::SENT_AT, HeaderValues::LongLongInt(0)))
+        );
+    }
+
+    #[test]
+    fn set_header_initializes_missing_headers() {
+        let mut msg = PublishMessage::new(None, "orders", None, None, b"data", None);
+
+        assert_eq!(msg.set_header("tenant", "acme"), None);
+        assert_eq!(
+            msg.set_header("tenant", "globex"),
+            Some(HeaderValues::from("acme"))
+        );
+        assert_eq!(
+            msg.remove_header("tenant"),
+            Some(HeaderValues::from("globex"))
+        );
+        assert_eq!(msg.headers, Some(HashMap::new()));
+    }
+
+    #[test]
+    fn remove_header_without_headers_leaves_them_absent() {
+        let mut msg = PublishMessage::new(None, "orders", None, None, b"data", None);
+
+        assert_eq!(msg.remove_header("tenant"), None);
+        assert_eq!(msg.headers, None);
+    }
+
+    #[test]
+    fn schema_version_round_trips_through_the_header() {
+        let msg = message("orders", "data").with_schema_version(3);
+
+        assert_eq!(
+            msg.headers.as_ref().unwrap()[headers::SCHEMA_VERSION],
+            HeaderValues::LongUint(3)
+        );
+        assert_eq!(msg.schema_version(), Some(3));
+        assert_eq!(message("orders", "data").schema_version(), None);
+    }
+
+    #[test]
+    fn schema_version_accepts_relayed_string_values() {
+        let mut msg = message("orders", "data");
+        msg.set_header(headers::SCHEMA_VERSION, "4");
+        assert_eq!(msg.schema_version(), Some(4));
+
+        msg.set_header(headers::SCHEMA_VERSION, "v4");
+        assert_eq!(msg.schema_version(), None);
+    }
+
+    #[test]
+    fn group_id_defaults_to_none_and_round_trips() {
+        assert_eq!(message("orders", "data").group_id, None);
+
+        let msg = message("orders", "data").with_group_id("session-1");
+
+        assert_eq!(msg.clone().group_id.as_deref(), Some("session-1"));
+    }
+
+    #[test]
+    fn string_header_maps_round_trip_through_typed_headers() {
+        let strings = HashMap::from([
+            ("tenant".to_owned(), "acme".to_owned()),
+            ("attempt".to_owned(), "3".to_owned()),
+        ]);
+
+        let typed = headers_from_string_map(strings.clone());
+
+        assert_eq!(
+            typed.get("tenant"),
+            Some(&HeaderValues::LongString("acme".to_owned()))
+        );
+        assert_eq!(headers_to_string_map(typed), strings);
+    }
+
+    #[test]
+    fn typed_headers_are_lowered_to_their_string_representation() {
+        let typed = HashMap::from([
+            ("attempt".to_owned(), HeaderValues::from(3u32)),
+            ("offset".to_owned(), HeaderValues::from(-7i64)),
+            ("raw".to_owned(), HeaderValues::from(b"bytes".to_vec())),
+            ("invalid".to_owned(), HeaderValues::from(vec![0xff])),
+        ]);
+
+        let strings = headers_to_string_map(typed);
+
+        assert_eq!(strings["attempt"], "3");
+        assert_eq!(strings["offset"], "-7");
+        assert_eq!(strings["raw"], "bytes");
+        assert_eq!(strings["invalid"], "\u{fffd}");
+    }
+
+    #[test]
+    fn with_to_key_and_msg_type_only_change_their_field_of_a_copy() {
+        let mut original = message("orders", "data").with_priority(3);
+        original.set_header("tenant", "acme");
+        let snapshot = original.clone();
+
+        let to = original.with_to("orders.audit");
+        let key = original.with_key("eu.orders");
+        let msg_type = original.with_msg_type("created");
+
+        assert_eq!(original, snapshot);
+        assert_eq!(
+            to,
+            PublishMessage {
+                to: "orders.audit".to_owned(),
+                ..snapshot.clone()
+            }
+        );
+        assert_eq!(
+            key,
+            PublishMessage {
+                key: Some("eu.orders".to_owned()),
+                ..snapshot.clone()
+            }
+        );
+        assert_eq!(
+            msg_type,
+            PublishMessage {
+                msg_type: Some("created".to_owned()),
+                ..snapshot
+            }
+        );
+    }
+
+    #[test]
+    fn priority_defaults_to_none_and_is_set_by_the_builder() {
+        assert_eq!(message("orders", "data").priority, None);
+        assert_eq!(message("orders", "data").with_priority(9).priority, Some(9));
+    }
+
+    #[test]
+    fn routing_and_partition_keys_coexist() {
+        let msg = PublishMessage::new(None, "orders", Some("eu.orders"), None, b"data", None)
+            .with_partition_key("customer-1");
+
+        let cloned = msg.clone();
+
+        assert_eq!(cloned.key.as_deref(), Some("eu.orders"));
+        assert_eq!(cloned.partition_key.as_deref(), Some("customer-1"));
+        assert_eq!(cloned, msg);
+    }
+
+    #[test]
+    fn partition_prefers_the_partition_key_over_the_routing_key() {
+        let msg = PublishMessage::new(None, "orders", Some("eu.orders"), None, b"data", None);
+
+        assert_eq!(msg.partition(8), Some(partition_for("eu.orders", 8)));
+        assert_eq!(
+            msg.with_partition_key("customer-42").partition(8),
+            Some(partition_for("customer-42", 8))
+        );
+        assert_eq!(message("orders", "data").partition(8), None);
+    }
+
+    #[test]
+    fn messages_have_no_partition_key_by_default() {
+        let msg = PublishMessage::new(None, "orders", Some("eu.orders"), None, b"data", None);
+
+        assert_eq!(msg.partition_key, None);
+    }
+
+    #[test]
+    fn with_destination_sets_to_and_the_exchange_routing_key() {
+        let msg = PublishMessage::new(None, "", Some("stale"), None, b"data", None);
+
+        let queued = msg.clone().with_destination(Destination::queue("orders"));
+        assert_eq!(queued.to, "orders");
+        assert_eq!(queued.key.as_deref(), Some("stale"));
+
+        let exchanged = msg.with_destination(Destination::exchange("orders", Some("eu.created")));
+        assert_eq!(exchanged.to, "orders");
+        assert_eq!(exchanged.key.as_deref(), Some("eu.created"));
+    }
+
+    #[test]
+    fn default_lifecycle_succeeds() {
+        let publisher = TestPublisher::default();
+
+        assert_eq!(block_on(publisher.connect()), Ok(()));
+        assert_eq!(block_on(publisher.disconnect()), Ok(()));
+    }
+
+    #[cfg(feature = "mocks")]
+    #[test]
+    fn mock_can_override_the_lifecycle_through_middleware() {
+        let mut inner = MockPublisher::new();
+        inner
+            .expect_connect()
+            .times(1)
+            .returning(|| Err(MessagingError::ConnectionError));
+        inner.expect_disconnect().times(1).returning(|| Ok(()));
+        let publisher =
+            crate::middleware::size_limit::SizeLimitedPublisher::new(Arc::new(inner), usize::MAX);
+
+        assert_eq!(
+            block_on(publisher.connect()),
+            Err(MessagingError::ConnectionError)
+        );
+        assert_eq!(block_on(publisher.disconnect()), Ok(()));
+    }
+
+    #[test]
+    fn default_health_check_reports_healthy() {
+        assert_eq!(block_on(TestPublisher::default().health_check()), Ok(()));
+    }
+
+    #[cfg(feature = "mocks")]
+    #[test]
+    fn mock_can_report_an_unhealthy_publisher() {
+        let mut inner = MockPublisher::new();
+        inner
+            .expect_health_check()
+            .returning(|| Err(MessagingError::ConnectionError));
+        let publisher: Arc<dyn Publisher> = Arc::new(inner);
+
+        assert_eq!(
+            block_on(publisher.health_check()),
+            Err(MessagingError::ConnectionError)
+        );
+    }
+
+    #[test]
+    fn header_values_convert_from_rust_types() {
+        assert_eq!(HeaderValues::from(-1i8), HeaderValues::Int(-1));
+        assert_eq!(HeaderValues::from(-2i32), HeaderValues::LongInt(-2));
+        assert_eq!(HeaderValues::from(-3i64), HeaderValues::LongLongInt(-3));
+        assert_eq!(HeaderValues::from(4u8), HeaderValues::Uint(4));
+        assert_eq!(HeaderValues::from(5u32), HeaderValues::LongUint(5));
+        assert_eq!(HeaderValues::from(6u64), HeaderValues::LongLongUint(6));
+        assert_eq!(HeaderValues::from(vec![7u8]), HeaderValues::Bytes(vec![7]));
+        assert_eq!(
+            HeaderValues::from("str"),
+            HeaderValues::LongString("str".to_owned())
+        );
+        assert_eq!(
+            HeaderValues::from("string".to_owned()),
+            HeaderValues::LongString("string".to_owned())
+        );
+    }
+
+    #[test]
+    fn header_values_convert_to_strings() {
+        assert_eq!(String::from(HeaderValues::ShortString("a".to_owned())), "a");
+        assert_eq!(String::from(HeaderValues::LongLongInt(-3)), "-3");
+        assert_eq!(String::from(HeaderValues::LongLongUint(6)), "6");
+        assert_eq!(
+            String::from(HeaderValues::Bytes(b"bytes".to_vec())),
+            "bytes"
+        );
+        assert_eq!(
+            String::from(HeaderValues::Bytes(vec![b'a', 0xff])),
+            "a\u{fffd}"
+        );
+    }
+
+    #[test]
+    fn header_values_convert_to_bytes() {
+        assert_eq!(HeaderValues::Bytes(vec![0xff]).into_bytes(), [0xff]);
+        assert_eq!(HeaderValues::LongUint(42).into_bytes(), b"42");
+        assert_eq!(
+            HeaderValues::Bytes(vec![0xff]).try_into_string(),
+            Err(MessagingError::DeserializingError)
+        );
+        assert_eq!(HeaderValues::Uint(1).try_into_string(), Ok("1".to_owned()));
+    }
+
+    #[test]
+    fn byte_path_preserves_unusual_string_content() {
+        let content = "\u{0}\u{fffd}\u{7f}é";
+        let value = HeaderValues::LongString(content.to_owned());
+
+        assert_eq!(value.clone().into_bytes(), content.as_bytes());
+        assert_eq!(value.try_into_string(), Ok(content.to_owned()));
+    }
+
+    #[test]
+    fn byte_path_preserves_invalid_utf8_that_the_string_path_replaces() {
+        let bytes = vec![b'a', 0xff, 0xfe, b'b'];
+        let value = HeaderValues::from(bytes.clone());
+
+        assert_eq!(value.clone().into_bytes(), bytes);
+        assert_eq!(String::from(value), "a\u{fffd}\u{fffd}b");
+        assert_eq!(
+            HeaderValues::from(b"valid".to_vec()).try_into_string(),
+            Ok("valid".to_owned())
+        );
+    }
+
+    /// A spawner running every task to completion before returning.
+    struct InlineSpawner;
+
+    impl Spawner for InlineSpawner {
+        fn spawn(&self, task: BackgroundTask) {
+            block_on(task);
+        }
+    }
+
+    fn detached(publisher: &Arc<TestPublisher>) -> Vec<MessagingError> {
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        let recorded = errors.clone();
+
+        publish_detached(
+            &InlineSpawner,
+            publisher.clone(),
+            Context::new(),
+            message("orders", "data"),
+            move |err| recorded.lock().unwrap().push(err),
+        );
+
+        errors.lock().unwrap().clone()
+    }
+
+    #[test]
+    fn detached_publish_completes_without_calling_back() {
+        let publisher = Arc::new(TestPublisher::default());
+
+        assert!(detached(&publisher).is_empty());
+        assert_eq!(publisher.published_data(), ["data"]);
+    }
+
+    #[test]
+    fn detached_publish_reports_failures_to_the_callback() {
+        let publisher = Arc::new(TestPublisher::default());
+        publisher.fail_with(Some(MessagingError::ConnectionError));
+
+        assert_eq!(detached(&publisher), [MessagingError::ConnectionError]);
+        assert!(publisher.published().is_empty());
+    }
+
+    #[test]
+    fn detached_publish_runs_on_a_thread_spawner() {
+        let publisher = Arc::new(TestPublisher::default());
+        let (sender, receiver) = std::sync::mpsc::channel();
+        publisher.fail_with(Some(MessagingError::ConnectionError));
+
+        publish_detached(
+            &ThreadSpawner,
+            publisher,
+            Context::new(),
+            message("orders", "data"),
+            move |err| sender.send(err).unwrap(),
+        );
+
+        assert_eq!(
+            receiver.recv_timeout(Duration::from_secs(5)),
+            Ok(MessagingError::ConnectionError)
+        );
+    }
 }
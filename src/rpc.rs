@@ -0,0 +1,457 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # RPC
+//!
+//! This module provides a request-response helper on top of a publisher and a dispatcher.
+//!
+//! The [`RpcClient`] publishes a request carrying `reply-to` and `correlation-id` headers
+//! and waits for the reply with the same correlation id on its reply destination.
+
+use crate::{
+    dispatcher::{Dispatcher, DispatcherDefinition},
+    errors::MessagingError,
+    handler::{ConsumerHandler, ConsumerMessage},
+    headers,
+    publisher::{HeaderValues, PublishMessage, Publisher},
+    timer::{self, ThreadTimer, Timer},
+};
+use async_trait::async_trait;
+use opentelemetry::Context;
+use std::{
+    collections::HashMap,
+    future::poll_fn,
+    sync::{
+        Arc, Mutex, MutexGuard, PoisonError,
+        atomic::{AtomicU64, Ordering},
+    },
+    task::{Poll, Waker},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// A client performing request-response calls.
+///
+/// Each call generates a correlation id, publishes the request and waits for a single
+/// reply. Replies arriving for an unknown correlation id, such as duplicates or replies
+/// arriving after their call timed out, are ignored.
+pub struct RpcClient {
+    publisher: Arc<dyn Publisher>,
+    reply_to: String,
+    timer: Arc<dyn Timer>,
+    pending: Arc<PendingReplies>,
+    prefix: String,
+    sequence: AtomicU64,
+}
+
+#[derive(Default)]
+struct PendingReplies {
+    slots: Mutex<HashMap<String, ReplySlot>>,
+}
+
+#[derive(Default)]
+struct ReplySlot {
+    reply: Option<ConsumerMessage>,
+    waker: Option<Waker>,
+}
+
+impl PendingReplies {
+    fn slots(&self) -> MutexGuard<'_, HashMap<String, ReplySlot>> {
+        self.slots.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Removes the reply slot of a call when the call completes or is cancelled.
+struct PendingGuard<'a> {
+    pending: &'a PendingReplies,
+    correlation_id: &'a str,
+}
+
+impl Drop for PendingGuard<'_> {
+    fn drop(&mut self) {
+        self.pending.slots().remove(self.correlation_id);
+    }
+}
+
+struct ReplyHandler {
+    pending: Arc<PendingReplies>,
+}
+
+#[async_trait]
+impl ConsumerHandler for ReplyHandler {
+    async fn exec(&self, _ctx: &Context, msg: &ConsumerMessage) -> Result<(), MessagingError> {
+        let Some(correlation_id) = msg.correlation_id() else {
+            return Ok(());
+        };
+
+        let mut slots = self.pending.slots();
+        if let Some(slot) = slots.get_mut(correlation_id)
+            && slot.reply.is_none()
+        {
+            slot.reply = Some(msg.clone());
+            if let Some(waker) = slot.waker.take() {
+                waker.wake();
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl RpcClient {
+    /// Creates a new RPC client with a generated reply destination and registers its reply
+    /// handler on the dispatcher.
+    ///
+    /// The reply destination is named `rpc-reply-` followed by a unique suffix; use
+    /// [`RpcClient::with_reply_to`] to choose it. Call timeouts are measured with a
+    /// [`ThreadTimer`], which sleeps on a thread of its own for every call; use
+    /// [`RpcClient::with_timer`] to measure them with the timer of the runtime instead.
+    ///
+    /// The returned dispatcher must be consumed for replies to be received.
+    ///
+    /// # Arguments
+    ///
+    /// * `publisher` - The publisher used to send requests.
+    /// * `reply_dispatcher` - The dispatcher consuming the reply destination.
+    ///
+    /// # Returns
+    ///
+    /// The new `RpcClient` and the dispatcher with the reply handler registered, to freeze
+    /// once the application registered its own handlers.
+    pub fn new<D>(publisher: Arc<dyn Publisher>, reply_dispatcher: D) -> (Self, D)
+    where
+        D: Dispatcher,
+    {
+        let started = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+
+        RpcClient::with_reply_to(
+            publisher,
+            reply_dispatcher,
+            format!("rpc-reply-{started:x}"),
+        )
+    }
+
+    /// Creates a new RPC client receiving its replies on a given destination and registers
+    /// its reply handler on the dispatcher.
+    ///
+    /// Like [`RpcClient::new`], call timeouts are measured with a [`ThreadTimer`] until
+    /// [`RpcClient::with_timer`] sets another timer.
+    ///
+    /// # Arguments
+    ///
+    /// * `publisher` - The publisher used to send requests.
+    /// * `reply_dispatcher` - The dispatcher consuming the reply destination.
+    /// * `reply_to` - The destination replies are published to.
+    ///
+    /// # Returns
+    ///
+    /// The new `RpcClient` and the dispatcher with the reply handler registered, to freeze
+    /// once the application registered its own handlers.
+    pub fn with_reply_to<D, T>(
+        publisher: Arc<dyn Publisher>,
+        reply_dispatcher: D,
+        reply_to: T,
+    ) -> (Self, D)
+    where
+        D: Dispatcher,
+        T: Into<String>,
+    {
+        let reply_to = reply_to.into();
+        let pending = Arc::new(PendingReplies::default());
+
+        let dispatcher = reply_dispatcher.register(
+            &DispatcherDefinition::new(reply_to.as_str(), None),
+            Arc::new(ReplyHandler {
+                pending: pending.clone(),
+            }),
+        );
+
+        let started = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+
+        let client = RpcClient {
+            publisher,
+            reply_to,
+            timer: Arc::new(ThreadTimer),
+            pending,
+            prefix: format!("{started:x}"),
+            sequence: AtomicU64::new(0),
+        };
+
+        (client, dispatcher)
+    }
+
+    /// Sets the timer used to measure call timeouts.
+    ///
+    /// # Arguments
+    ///
+    /// * `timer` - The timer used to measure call timeouts.
+    ///
+    /// # Returns
+    ///
+    /// The updated `RpcClient`.
+    pub fn with_timer(mut self, timer: Arc<dyn Timer>) -> Self {
+        self.timer = timer;
+        self
+    }
+
+    /// Returns the destination replies are published to.
+    pub fn reply_to(&self) -> &str {
+        &self.reply_to
+    }
+
+    /// Publishes a request and waits for its reply.
+    ///
+    /// The `reply-to` and `correlation-id` headers of the request are set by the client.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The OpenTelemetry context for tracing and monitoring.
+    /// * `msg` - The request to publish.
+    /// * `timeout` - The maximum time to wait for the reply.
+    ///
+    /// # Returns
+    ///
+    /// The reply, the publish error, or `MessagingError::TimeoutError` if no reply arrived
+    /// within the timeout. Timeouts are reported as `TimeoutError` rather than as a
+    /// `ConsumerError`, like every other timeout of the crate, so callers can tell them
+    /// apart from failures of the reply consumer.
+    pub async fn call(
+        &self,
+        ctx: &Context,
+        msg: PublishMessage,
+        timeout: Duration,
+    ) -> Result<ConsumerMessage, MessagingError> {
+        let correlation_id = format!(
+            "{}-{:x}",
+            self.prefix,
+            self.sequence.fetch_add(1, Ordering::Relaxed)
+        );
+
+        let mut request = msg;
+        request.set_header(
+            headers::REPLY_TO,
+            HeaderValues::ShortString(self.reply_to.clone()),
+        );
+        request.set_header(
+            headers::CORRELATION_ID,
+            HeaderValues::ShortString(correlation_id.clone()),
+        );
+
+        self.pending
+            .slots()
+            .insert(correlation_id.clone(), ReplySlot::default());
+        let _guard = PendingGuard {
+            pending: &self.pending,
+            correlation_id: &correlation_id,
+        };
+
+        self.publisher.publish(ctx, &request).await?;
+
+        timer::timeout(&*self.timer, timeout, self.reply(&correlation_id))
+            .await
+            .ok_or(MessagingError::TimeoutError)
+    }
+
+    async fn reply(&self, correlation_id: &str) -> ConsumerMessage {
+        poll_fn(|cx| {
+            let mut slots = self.pending.slots();
+            let slot = slots.entry(correlation_id.to_owned()).or_default();
+            match slot.reply.take() {
+                Some(reply) => Poll::Ready(reply),
+                None => {
+                    slot.waker = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+            }
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{
+        CapturingDispatcher, NeverTimer, TestPublisher, block_on, delivered, message, poll_once,
+    };
+    use std::pin::pin;
+
+    fn client(timer: Arc<dyn Timer>) -> (RpcClient, Arc<TestPublisher>, Arc<dyn ConsumerHandler>) {
+        let publisher = Arc::new(TestPublisher::default());
+        let (client, dispatcher) =
+            RpcClient::with_reply_to(publisher.clone(), CapturingDispatcher::default(), "replies");
+        let client = client.with_timer(timer);
+        let replies = dispatcher.handler("replies");
+        (client, publisher, replies)
+    }
+
+    /// Builds the reply to the last request published, as a responder would.
+    fn reply_to_last(publisher: &TestPublisher, data: &str) -> ConsumerMessage {
+        let request = delivered(&publisher.published().pop().unwrap());
+        delivered(&request.reply(data.as_bytes()).unwrap())
+    }
+
+    fn deliver(replies: &Arc<dyn ConsumerHandler>, reply: &ConsumerMessage) {
+        assert_eq!(block_on(replies.exec(&Context::new(), reply)), Ok(()));
+    }
+
+    #[test]
+    fn publishes_requests_with_reply_headers() {
+        let (client, publisher, _) = client(Arc::new(NeverTimer));
+        let ctx = Context::new();
+        let mut call = pin!(client.call(&ctx, message("requests", "ping"), Duration::from_secs(1)));
+
+        assert!(poll_once(call.as_mut()).is_pending());
+
+        let request = &publisher.published()[0];
+        let headers = request.headers.as_ref().unwrap();
+        assert_eq!(
+            headers[headers::REPLY_TO],
+            HeaderValues::ShortString("replies".to_owned())
+        );
+        assert!(headers.contains_key(headers::CORRELATION_ID));
+        assert_eq!(client.reply_to(), "replies");
+    }
+
+    #[test]
+    fn returns_the_reply_with_the_matching_correlation_id() {
+        let (client, publisher, replies) = client(Arc::new(NeverTimer));
+        let ctx = Context::new();
+        let mut first = pin!(client.call(&ctx, message("requests", "1"), Duration::from_secs(1)));
+        assert!(poll_once(first.as_mut()).is_pending());
+        let first_reply = reply_to_last(&publisher, "one");
+        let mut second = pin!(client.call(&ctx, message("requests", "2"), Duration::from_secs(1)));
+        assert!(poll_once(second.as_mut()).is_pending());
+        let second_reply = reply_to_last(&publisher, "two");
+
+        deliver(&replies, &second_reply);
+        deliver(&replies, &first_reply);
+
+        assert_eq!(&*block_on(first).unwrap().data, b"one");
+        assert_eq!(&*block_on(second).unwrap().data, b"two");
+    }
+
+    #[test]
+    fn ignores_duplicate_replies() {
+        let (client, publisher, replies) = client(Arc::new(NeverTimer));
+        let ctx = Context::new();
+        let mut call = pin!(client.call(&ctx, message("requests", "ping"), Duration::from_secs(1)));
+        assert!(poll_once(call.as_mut()).is_pending());
+        let reply = reply_to_last(&publisher, "pong");
+        let mut duplicate = reply.clone();
+        duplicate.data = b"duplicate".as_slice().into();
+
+        deliver(&replies, &reply);
+        deliver(&replies, &duplicate);
+        assert_eq!(&*block_on(call).unwrap().data, b"pong");
+
+        deliver(&replies, &duplicate);
+        assert!(client.pending.slots().is_empty());
+    }
+
+    #[test]
+    fn times_out_and_ignores_late_replies() {
+        let (client, publisher, replies) = client(Arc::new(ThreadTimer));
+
+        let result = block_on(client.call(
+            &Context::new(),
+            message("requests", "ping"),
+            Duration::from_millis(10),
+        ));
+        assert_eq!(result.unwrap_err(), MessagingError::TimeoutError);
+
+        deliver(&replies, &reply_to_last(&publisher, "late"));
+        assert!(client.pending.slots().is_empty());
+    }
+
+    #[test]
+    fn ignores_replies_without_correlation_id() {
+        let (_, _, replies) = client(Arc::new(NeverTimer));
+
+        deliver(
+            &replies,
+            &ConsumerMessage::new("replies", "", b"pong", None),
+        );
+    }
+
+    #[test]
+    fn returns_publish_errors_without_waiting() {
+        let (client, publisher, _) = client(Arc::new(NeverTimer));
+        publisher.fail_with(Some(MessagingError::ConnectionError));
+
+        let result = block_on(client.call(
+            &Context::new(),
+            message("requests", "ping"),
+            Duration::from_secs(1),
+        ));
+
+        assert_eq!(result.unwrap_err(), MessagingError::ConnectionError);
+        assert!(client.pending.slots().is_empty());
+    }
+
+    #[test]
+    fn generates_a_reply_destination() {
+        let dispatcher = CapturingDispatcher::default();
+        let (client, _) = RpcClient::new(Arc::new(TestPublisher::default()), dispatcher.clone());
+
+        assert!(client.reply_to().starts_with("rpc-reply-"));
+        let registrations = dispatcher.registrations();
+        assert_eq!(registrations.len(), 1);
+        assert_eq!(registrations[0].name, client.reply_to());
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn calls_a_responder_through_the_in_memory_broker() {
+        use crate::{
+            handler::fn_handler, test_support::join_all, testing::in_memory::InMemoryBroker,
+        };
+        use std::{future::Future, pin::Pin};
+
+        let broker = InMemoryBroker::new();
+        let responder = broker.clone();
+        let requests = broker.clone().register(
+            &DispatcherDefinition::new("requests", None),
+            fn_handler(move |_, msg| {
+                let reply = msg.reply(format!("re: {}", msg.data_str().unwrap()).as_bytes());
+                let broker = responder.clone();
+                async move { broker.publish(&Context::new(), &reply?).await }
+            }),
+        );
+        let (client, dispatcher) = RpcClient::new(Arc::new(broker.clone()), requests);
+        let client = client.with_timer(Arc::new(NeverTimer));
+        let dispatcher = dispatcher.freeze();
+
+        type Step<'a> = Pin<Box<dyn Future<Output = Option<ConsumerMessage>> + 'a>>;
+        let ctx = Context::new();
+        let calling: Step = Box::pin(async {
+            let reply = client
+                .call(&ctx, message("requests", "ping"), Duration::from_secs(1))
+                .await;
+            broker.close();
+            Some(reply.unwrap())
+        });
+        let consuming: Step = Box::pin(async {
+            dispatcher.consume_blocking().await.unwrap();
+            None
+        });
+
+        let reply = block_on(join_all(vec![calling, consuming]))
+            .into_iter()
+            .flatten()
+            .next()
+            .unwrap();
+
+        assert_eq!(reply.from, client.reply_to());
+        assert_eq!(reply.data_str(), Ok("re: ping"));
+        assert!(reply.correlation_id().is_some());
+        assert!(client.pending.slots().is_empty());
+        assert_eq!(broker.pending(client.reply_to()), 0);
+    }
+}
@@ -0,0 +1,89 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Spawn
+//!
+//! This module provides the task spawning abstraction used by background helpers.
+//!
+//! Like the [`Timer`](crate::timer::Timer), spawning is left to the application's
+//! runtime: helpers running work in the background take a [`Spawner`], which applications
+//! can implement by delegating to e.g. `tokio::spawn`. [`ThreadSpawner`] is a
+//! runtime-independent fallback.
+
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Wake, Waker},
+    thread::{self, Thread},
+};
+
+#[cfg(feature = "mocks")]
+use mockall::*;
+
+/// A boxed future run in the background.
+pub type BackgroundTask = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+/// Defines the interface for running a future in the background.
+#[cfg_attr(feature = "mocks", automock)]
+pub trait Spawner: Send + Sync {
+    /// Starts running a future without waiting for it to complete.
+    ///
+    /// # Arguments
+    ///
+    /// * `task` - The future to run.
+    fn spawn(&self, task: BackgroundTask);
+}
+
+/// A spawner that runs each future to completion on a dedicated OS thread.
+///
+/// The thread blocks on the future, so futures relying on runtime-specific timers or I/O
+/// must use a runtime-backed `Spawner` instead. Like the `ThreadTimer`, it is more expensive
+/// than a runtime spawner and suits low volumes.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ThreadSpawner;
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+impl Spawner for ThreadSpawner {
+    fn spawn(&self, mut task: BackgroundTask) {
+        thread::spawn(move || {
+            let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+            let mut cx = Context::from_waker(&waker);
+            while task.as_mut().poll(&mut cx).is_pending() {
+                thread::park();
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::yield_now;
+    use std::{sync::mpsc, time::Duration};
+
+    #[test]
+    fn thread_spawner_runs_the_task_to_completion() {
+        let (sender, receiver) = mpsc::channel();
+
+        ThreadSpawner.spawn(Box::pin(async move {
+            for step in 0..3 {
+                yield_now().await;
+                sender.send(step).unwrap();
+            }
+        }));
+
+        let steps: Vec<_> = (0..3)
+            .map(|_| receiver.recv_timeout(Duration::from_secs(5)).unwrap())
+            .collect();
+        assert_eq!(steps, [0, 1, 2]);
+    }
+}
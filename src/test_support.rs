@@ -0,0 +1,331 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Test Support
+//!
+//! This module provides helpers shared by the unit tests of the crate.
+
+use crate::{
+    dispatcher::{Dispatcher, DispatcherDefinition},
+    errors::MessagingError,
+    handler::{ConsumerHandler, ConsumerMessage},
+    publisher::{self, PublishMessage, Publisher},
+    timer::Timer,
+};
+use async_trait::async_trait;
+use opentelemetry::Context;
+use std::{
+    future::{Future, pending, poll_fn},
+    pin::{Pin, pin},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    task::{self, Poll, Wake, Waker},
+    thread::{self, Thread},
+    time::Duration,
+};
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Runs a future to completion on the current thread.
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = task::Context::from_waker(&waker);
+
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        thread::park();
+    }
+}
+
+/// Runs futures concurrently on the current task until all of them completed.
+pub(crate) async fn join_all<F: Future>(futures: Vec<F>) -> Vec<F::Output> {
+    let mut futures: Vec<_> = futures.into_iter().map(Box::pin).collect();
+    let mut outputs: Vec<_> = futures.iter().map(|_| None).collect();
+
+    poll_fn(|cx| {
+        for (future, output) in futures.iter_mut().zip(outputs.iter_mut()) {
+            if output.is_none()
+                && let Poll::Ready(value) = future.as_mut().poll(cx)
+            {
+                *output = Some(value);
+            }
+        }
+
+        if outputs.iter().all(Option::is_some) {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    })
+    .await;
+
+    outputs.into_iter().flatten().collect()
+}
+
+/// Yields to the executor once, letting the other futures of a [`join_all`] run.
+pub(crate) async fn yield_now() {
+    let mut yielded = false;
+    poll_fn(|cx| {
+        if yielded {
+            return Poll::Ready(());
+        }
+        yielded = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    })
+    .await;
+}
+
+/// Polls a future once without a waker, to drive it up to its first suspension point.
+pub(crate) fn poll_once<F: Future + ?Sized>(future: Pin<&mut F>) -> Poll<F::Output> {
+    future.poll(&mut task::Context::from_waker(Waker::noop()))
+}
+
+/// Creates a message of type `event` to a destination.
+pub(crate) fn message(to: &str, data: &str) -> PublishMessage {
+    PublishMessage::new(None, to, None, Some("event"), data.as_bytes(), None)
+}
+
+/// Converts a published message into the message a consumer of its destination receives.
+pub(crate) fn delivered(msg: &PublishMessage) -> ConsumerMessage {
+    let headers = msg.headers.clone().map(publisher::headers_to_string_map);
+    let mut delivered = ConsumerMessage::new(
+        msg.to.as_str(),
+        msg.msg_type.as_deref().unwrap_or_default(),
+        &msg.data,
+        headers,
+    );
+    delivered.group_id = msg.group_id.clone();
+    delivered
+}
+
+/// A publisher recording the messages it publishes, whose publishes can be held back or
+/// failed.
+#[derive(Default)]
+pub(crate) struct TestPublisher {
+    state: Mutex<TestPublisherState>,
+}
+
+#[derive(Default)]
+struct TestPublisherState {
+    published: Vec<PublishMessage>,
+    held: bool,
+    failure: Option<MessagingError>,
+    waiters: Vec<Waker>,
+}
+
+impl TestPublisher {
+    /// Creates a publisher whose publishes wait until [`TestPublisher::release`].
+    pub(crate) fn held() -> Self {
+        let publisher = TestPublisher::default();
+        publisher.state().held = true;
+        publisher
+    }
+
+    /// Lets the held publishes complete.
+    pub(crate) fn release(&self) {
+        let mut state = self.state();
+        state.held = false;
+        state.waiters.drain(..).for_each(Waker::wake);
+    }
+
+    /// Makes the following publishes fail with `failure`, or succeed again with `None`.
+    pub(crate) fn fail_with(&self, failure: Option<MessagingError>) {
+        self.state().failure = failure;
+    }
+
+    /// Returns the published messages in publish order.
+    pub(crate) fn published(&self) -> Vec<PublishMessage> {
+        self.state().published.clone()
+    }
+
+    /// Returns the data of the published messages as strings, in publish order.
+    pub(crate) fn published_data(&self) -> Vec<String> {
+        self.state()
+            .published
+            .iter()
+            .map(|msg| String::from_utf8_lossy(&msg.data).into_owned())
+            .collect()
+    }
+
+    fn state(&self) -> MutexGuard<'_, TestPublisherState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[async_trait]
+impl Publisher for TestPublisher {
+    async fn publish(&self, _ctx: &Context, msg: &PublishMessage) -> Result<(), MessagingError> {
+        poll_fn(|cx| {
+            let mut state = self.state();
+            if state.held {
+                state.waiters.push(cx.waker().clone());
+                return Poll::Pending;
+            }
+            if let Some(failure) = state.failure.clone() {
+                return Poll::Ready(Err(failure));
+            }
+
+            state.published.push(msg.clone());
+            Poll::Ready(Ok(()))
+        })
+        .await
+    }
+}
+
+/// A handler recording the messages it receives, which succeeds unless told to fail.
+#[derive(Default)]
+pub(crate) struct TestHandler {
+    state: Mutex<TestHandlerState>,
+}
+
+#[derive(Default)]
+struct TestHandlerState {
+    received: Vec<ConsumerMessage>,
+    failure: Option<MessagingError>,
+}
+
+impl TestHandler {
+    /// Creates a handler failing every message with `failure`.
+    pub(crate) fn failing(failure: MessagingError) -> Self {
+        let handler = TestHandler::default();
+        handler.state().failure = Some(failure);
+        handler
+    }
+
+    /// Returns the received messages in delivery order.
+    pub(crate) fn received(&self) -> Vec<ConsumerMessage> {
+        self.state().received.clone()
+    }
+
+    fn state(&self) -> MutexGuard<'_, TestHandlerState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[async_trait]
+impl ConsumerHandler for TestHandler {
+    async fn exec(&self, _ctx: &Context, msg: &ConsumerMessage) -> Result<(), MessagingError> {
+        let mut state = self.state();
+        state.received.push(msg.clone());
+        state.failure.clone().map_or(Ok(()), Err)
+    }
+}
+
+/// A timer whose sleeps never complete.
+pub(crate) struct NeverTimer;
+
+#[async_trait]
+impl Timer for NeverTimer {
+    async fn sleep(&self, _duration: Duration) {
+        pending().await
+    }
+}
+
+/// A timer recording the requested sleeps, which complete immediately unless the timer was
+/// created with [`RecordingTimer::completing`].
+#[derive(Default)]
+pub(crate) struct RecordingTimer {
+    sleeps: Mutex<Vec<Duration>>,
+    completing: Option<usize>,
+}
+
+impl RecordingTimer {
+    /// Creates a timer completing only its first `count` sleeps; later ones never complete.
+    pub(crate) fn completing(count: usize) -> Self {
+        RecordingTimer {
+            completing: Some(count),
+            ..RecordingTimer::default()
+        }
+    }
+
+    /// Returns the requested sleeps in request order.
+    pub(crate) fn sleeps(&self) -> Vec<Duration> {
+        self.sleeps
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+#[async_trait]
+impl Timer for RecordingTimer {
+    async fn sleep(&self, duration: Duration) {
+        let slept = {
+            let mut sleeps = self.sleeps.lock().unwrap_or_else(PoisonError::into_inner);
+            sleeps.push(duration);
+            sleeps.len()
+        };
+
+        if self.completing.is_some_and(|count| slept > count) {
+            pending::<()>().await;
+        }
+    }
+}
+
+type Registrations = Vec<(DispatcherDefinition, Arc<dyn ConsumerHandler>)>;
+
+/// A dispatcher capturing the handlers registered on it, so a test can invoke them
+/// directly. Clones share the captured handlers.
+#[derive(Clone, Default)]
+pub(crate) struct CapturingDispatcher {
+    handlers: Arc<Mutex<Registrations>>,
+}
+
+impl CapturingDispatcher {
+    /// Returns the registered definitions in registration order.
+    pub(crate) fn registrations(&self) -> Vec<DispatcherDefinition> {
+        self.handlers()
+            .iter()
+            .map(|(definition, _)| definition.clone())
+            .collect()
+    }
+
+    /// Returns the handler registered last for a definition name.
+    pub(crate) fn handler(&self, name: &str) -> Arc<dyn ConsumerHandler> {
+        self.handlers()
+            .iter()
+            .rev()
+            .find(|(definition, _)| definition.name == name)
+            .map(|(_, handler)| handler.clone())
+            .unwrap_or_else(|| panic!("no handler registered for `{name}`"))
+    }
+
+    fn handlers(&self) -> MutexGuard<'_, Registrations> {
+        self.handlers.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[async_trait]
+impl Dispatcher for CapturingDispatcher {
+    fn register(
+        mut self,
+        definition: &DispatcherDefinition,
+        handler: Arc<dyn ConsumerHandler>,
+    ) -> Self {
+        self.register_ref(definition, handler);
+        self
+    }
+
+    fn register_ref(
+        &mut self,
+        definition: &DispatcherDefinition,
+        handler: Arc<dyn ConsumerHandler>,
+    ) -> &mut Self {
+        self.handlers().push((definition.clone(), handler));
+        self
+    }
+
+    async fn consume_blocking(&self) -> Result<(), MessagingError> {
+        Ok(())
+    }
+}
@@ -0,0 +1,272 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Test Dispatcher
+//!
+//! This module provides a dispatcher delivering messages pushed by the test.
+//!
+//! Unlike the expectation-based `MockDispatcher`, the `TestDispatcher` really routes
+//! messages to the registered handlers and records how each handler was invoked, so a
+//! test can assert on registration and routing behavior.
+
+use crate::{
+    dispatcher::{Dispatcher, DispatcherDefinition, HandlerRegistry},
+    errors::MessagingError,
+    handler::{ConsumerHandler, ConsumerMessage},
+};
+use async_trait::async_trait;
+use opentelemetry::Context;
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+/// A dispatcher that delivers pushed messages to the registered handlers.
+///
+/// Messages are routed like by a [`HandlerRegistry`]: by their source, matching the
+/// definition name, and their `msg_type`, falling back to the fallback handler.
+/// `consume_blocking` delivers the pushed messages one at a time, in push order, and
+/// returns once none is left; it can be called again after pushing more.
+///
+/// For every registered definition, the number of invocations of its handler and the
+/// last error it returned are recorded. Messages matching no handler are counted by
+/// [`TestDispatcher::unrouted`].
+#[derive(Default)]
+pub struct TestDispatcher {
+    handlers: HandlerRegistry,
+    records: Vec<(DispatcherDefinition, Arc<Record>)>,
+    queue: Mutex<VecDeque<ConsumerMessage>>,
+    unrouted: Mutex<usize>,
+}
+
+#[derive(Default)]
+struct Record {
+    state: Mutex<RecordState>,
+}
+
+#[derive(Default)]
+struct RecordState {
+    invocations: usize,
+    last_error: Option<MessagingError>,
+}
+
+struct RecordingHandler {
+    inner: Arc<dyn ConsumerHandler>,
+    record: Arc<Record>,
+}
+
+#[async_trait]
+impl ConsumerHandler for RecordingHandler {
+    async fn exec(&self, ctx: &Context, msg: &ConsumerMessage) -> Result<(), MessagingError> {
+        let result = self.inner.exec(ctx, msg).await;
+
+        let mut state = lock(&self.record.state);
+        state.invocations += 1;
+        if let Err(err) = &result {
+            state.last_error = Some(err.clone());
+        }
+
+        result
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl TestDispatcher {
+    /// Creates a new test dispatcher without handlers or messages.
+    ///
+    /// # Returns
+    ///
+    /// A new `TestDispatcher` instance.
+    pub fn new() -> Self {
+        TestDispatcher::default()
+    }
+
+    /// Queues a message for the next `consume_blocking` call.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The message to deliver.
+    pub fn push(&self, msg: ConsumerMessage) {
+        lock(&self.queue).push_back(msg);
+    }
+
+    /// Returns the number of pushed messages not delivered yet.
+    pub fn pending(&self) -> usize {
+        lock(&self.queue).len()
+    }
+
+    /// Returns how many times the handler of a definition was invoked.
+    ///
+    /// # Arguments
+    ///
+    /// * `definition` - The registered definition, matched by name and message type.
+    ///
+    /// # Returns
+    ///
+    /// The number of invocations, or 0 if no such definition is registered.
+    pub fn invocations(&self, definition: &DispatcherDefinition) -> usize {
+        self.record(definition)
+            .map_or(0, |record| lock(&record.state).invocations)
+    }
+
+    /// Returns the last error returned by the handler of a definition.
+    ///
+    /// # Arguments
+    ///
+    /// * `definition` - The registered definition, matched by name and message type.
+    ///
+    /// # Returns
+    ///
+    /// The last error, or `None` if the handler never failed or no such definition is
+    /// registered.
+    pub fn last_error(&self, definition: &DispatcherDefinition) -> Option<MessagingError> {
+        self.record(definition)
+            .and_then(|record| lock(&record.state).last_error.clone())
+    }
+
+    /// Returns the number of delivered messages that matched no handler.
+    pub fn unrouted(&self) -> usize {
+        *lock(&self.unrouted)
+    }
+
+    fn record(&self, definition: &DispatcherDefinition) -> Option<&Arc<Record>> {
+        self.records
+            .iter()
+            .find(|(registered, _)| {
+                registered.name == definition.name && registered.msg_type == definition.msg_type
+            })
+            .map(|(_, record)| record)
+    }
+}
+
+#[async_trait]
+impl Dispatcher for TestDispatcher {
+    fn register(
+        mut self,
+        definition: &DispatcherDefinition,
+        handler: Arc<dyn ConsumerHandler>,
+    ) -> Self {
+        self.register_ref(definition, handler);
+        self
+    }
+
+    fn register_ref(
+        &mut self,
+        definition: &DispatcherDefinition,
+        handler: Arc<dyn ConsumerHandler>,
+    ) -> &mut Self {
+        let record = Arc::new(Record::default());
+        self.handlers.register(
+            definition,
+            Arc::new(RecordingHandler {
+                inner: handler,
+                record: record.clone(),
+            }),
+        );
+        self.records.push((definition.clone(), record));
+        self
+    }
+
+    fn register_fallback(mut self, handler: Arc<dyn ConsumerHandler>) -> Self {
+        self.handlers.register_fallback(handler);
+        self
+    }
+
+    async fn consume_blocking(&self) -> Result<(), MessagingError> {
+        loop {
+            let Some(msg) = lock(&self.queue).pop_front() else {
+                return Ok(());
+            };
+
+            match self.handlers.resolve_message(&msg) {
+                Some(handler) => {
+                    let _ = handler.exec(&Context::current(), &msg).await;
+                }
+                None => *lock(&self.unrouted) += 1,
+            }
+        }
+    }
+
+    fn definitions(&self) -> Vec<DispatcherDefinition> {
+        self.handlers.definitions().cloned().collect()
+    }
+
+    fn handler_count(&self) -> usize {
+        self.handlers.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{handler::fn_handler, test_support::block_on};
+
+    fn delivery(from: &str, msg_type: &str) -> ConsumerMessage {
+        ConsumerMessage::new(from, msg_type, b"data", None)
+    }
+
+    #[test]
+    fn routes_pushed_messages_to_the_matching_handler() {
+        let created = DispatcherDefinition::new("orders", Some("created"));
+        let cancelled = DispatcherDefinition::new("orders", Some("cancelled"));
+        let dispatcher = TestDispatcher::new()
+            .register(&created, fn_handler(|_, _| async { Ok(()) }))
+            .register(
+                &cancelled,
+                fn_handler(|_, _| async { Err(MessagingError::HandlerError) }),
+            );
+
+        dispatcher.push(delivery("orders", "created"));
+        dispatcher.push(delivery("orders", "created"));
+        dispatcher.push(delivery("orders", "cancelled"));
+        dispatcher.push(delivery("orders", "shipped"));
+        assert_eq!(dispatcher.pending(), 4);
+
+        assert_eq!(block_on(dispatcher.consume_blocking()), Ok(()));
+        assert_eq!(dispatcher.pending(), 0);
+        assert_eq!(dispatcher.invocations(&created), 2);
+        assert_eq!(dispatcher.last_error(&created), None);
+        assert_eq!(dispatcher.invocations(&cancelled), 1);
+        assert_eq!(
+            dispatcher.last_error(&cancelled),
+            Some(MessagingError::HandlerError)
+        );
+        assert_eq!(dispatcher.unrouted(), 1);
+    }
+
+    #[test]
+    fn delivers_unmatched_messages_to_the_fallback() {
+        let dispatcher =
+            TestDispatcher::new().register_fallback(fn_handler(|_, _| async { Ok(()) }));
+
+        dispatcher.push(delivery("orders", "created"));
+        block_on(dispatcher.consume_blocking()).unwrap();
+        assert_eq!(dispatcher.unrouted(), 0);
+        assert_eq!(
+            dispatcher.invocations(&DispatcherDefinition::new("orders", Some("created"))),
+            0
+        );
+    }
+
+    #[test]
+    fn leaves_messages_not_matching_the_header_filter_unrouted() {
+        let acme = DispatcherDefinition::new("orders", None).with_header_filter("tenant", "acme");
+        let dispatcher = TestDispatcher::new().register(&acme, fn_handler(|_, _| async { Ok(()) }));
+
+        let mut matching = delivery("orders", "created");
+        matching.set_header("tenant", "acme");
+        let mut other = delivery("orders", "created");
+        other.set_header("tenant", "globex");
+        dispatcher.push(matching);
+        dispatcher.push(other);
+        dispatcher.push(delivery("orders", "created"));
+
+        block_on(dispatcher.consume_blocking()).unwrap();
+        assert_eq!(dispatcher.invocations(&acme), 1);
+        assert_eq!(dispatcher.unrouted(), 2);
+    }
+}
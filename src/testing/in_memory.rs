@@ -0,0 +1,758 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # In-Memory Broker
+//!
+//! This module provides an in-process broker implementing both `Publisher` and `Dispatcher`.
+//!
+//! Published messages are queued per destination name and delivered to the handlers
+//! registered for that destination, so a test can publish through one clone of the broker
+//! and consume through another.
+
+use crate::{
+    dispatcher::{Dispatcher, DispatcherDefinition, HandlerRegistry},
+    errors::MessagingError,
+    handler::{ConsumerHandler, ConsumerMessage},
+    publisher::{self, PublishMessage, Publisher},
+    timer::{self, ThreadTimer},
+};
+use async_trait::async_trait;
+use opentelemetry::Context;
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap},
+    future::poll_fn,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    task::{Poll, Waker},
+    time::Duration,
+};
+
+/// An in-process broker for tests.
+///
+/// Clones share the same queues. Messages published to a destination nobody consumes yet
+/// stay queued until a dispatcher registered for that destination picks them up.
+///
+/// `consume_blocking` delivers one message at a time to the handler resolved by a
+/// [`HandlerRegistry`] from the destination and `msg_type`, or to the fallback handler.
+/// Messages matching no handler and messages whose handler fails are dropped.
+/// Each destination delivers messages with a higher [`PublishMessage::priority`] first and
+/// messages of equal priority in publish order; messages without a priority count as
+/// priority 0. Consumption returns once
+/// [`InMemoryBroker::close`] has been called and all registered destinations are drained.
+///
+/// Registration is per clone and ends with [`Dispatcher::freeze`]: `consume_blocking` and
+/// `consume_n` fail with `MessagingError::InternalError` on a clone that registered a
+/// handler, or was created, since it was last frozen.
+///
+/// Pausing holds back deliveries on every clone; published messages stay queued until
+/// consumption is resumed. Draining makes `consume_blocking` return after the message
+/// being handled, if any, leaving the remaining messages queued.
+#[derive(Clone, Default)]
+pub struct InMemoryBroker {
+    shared: Arc<Mutex<State>>,
+    handlers: HandlerRegistry,
+    frozen: bool,
+}
+
+#[derive(Default)]
+struct State {
+    queues: HashMap<String, BinaryHeap<Queued>>,
+    sequence: u64,
+    wakers: Vec<Waker>,
+    paused: bool,
+    closed: bool,
+    draining: bool,
+    in_flight: usize,
+}
+
+/// A queued message, ordered by priority and then by publish order.
+struct Queued {
+    priority: u8,
+    sequence: u64,
+    msg: ConsumerMessage,
+}
+
+impl Ord for Queued {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
+impl PartialOrd for Queued {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Queued {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Queued {}
+
+impl State {
+    fn wake_all(&mut self) {
+        self.wakers.drain(..).for_each(Waker::wake);
+    }
+}
+
+impl InMemoryBroker {
+    /// Creates a new, empty in-memory broker.
+    ///
+    /// # Returns
+    ///
+    /// A new `InMemoryBroker` instance.
+    pub fn new() -> Self {
+        InMemoryBroker::default()
+    }
+
+    /// Closes the broker, making `consume_blocking` return once its queues are drained.
+    pub fn close(&self) {
+        let mut state = self.state();
+        state.closed = true;
+        state.wake_all();
+    }
+
+    /// Returns the number of messages waiting to be consumed for a destination.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The destination name.
+    ///
+    /// # Returns
+    ///
+    /// The number of queued messages.
+    pub fn pending(&self, name: &str) -> usize {
+        self.state().queues.get(name).map_or(0, BinaryHeap::len)
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.shared.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    async fn next_message(&self) -> Option<ConsumerMessage> {
+        poll_fn(|cx| {
+            let mut state = self.state();
+
+            if state.draining {
+                return Poll::Ready(None);
+            }
+
+            if state.paused {
+                if state.closed {
+                    return Poll::Ready(None);
+                }
+                state.wakers.push(cx.waker().clone());
+                return Poll::Pending;
+            }
+
+            for definition in self.handlers.definitions() {
+                if let Some(msg) = state
+                    .queues
+                    .get_mut(&definition.name)
+                    .and_then(BinaryHeap::pop)
+                    .map(|queued| queued.msg)
+                {
+                    state.in_flight += 1;
+                    return Poll::Ready(Some(msg));
+                }
+            }
+
+            if state.closed {
+                return Poll::Ready(None);
+            }
+
+            state.wakers.push(cx.waker().clone());
+            Poll::Pending
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl Publisher for InMemoryBroker {
+    async fn publish(&self, _ctx: &Context, msg: &PublishMessage) -> Result<(), MessagingError> {
+        let headers = msg.headers.clone().map(publisher::headers_to_string_map);
+
+        let mut consumed = ConsumerMessage::new(
+            msg.to.as_str(),
+            msg.msg_type.as_deref().unwrap_or_default(),
+            &msg.data,
+            headers,
+        );
+        consumed.group_id = msg.group_id.clone();
+
+        let mut state = self.state();
+        state.sequence += 1;
+        let queued = Queued {
+            priority: msg.priority.unwrap_or_default(),
+            sequence: state.sequence,
+            msg: consumed,
+        };
+        state.queues.entry(msg.to.clone()).or_default().push(queued);
+        state.wake_all();
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Dispatcher for InMemoryBroker {
+    fn register(
+        mut self,
+        definition: &DispatcherDefinition,
+        handler: Arc<dyn ConsumerHandler>,
+    ) -> Self {
+        self.register_ref(definition, handler);
+        self
+    }
+
+    fn register_ref(
+        &mut self,
+        definition: &DispatcherDefinition,
+        handler: Arc<dyn ConsumerHandler>,
+    ) -> &mut Self {
+        self.handlers.register(definition, handler);
+        self.frozen = false;
+        self
+    }
+
+    fn register_fallback(mut self, handler: Arc<dyn ConsumerHandler>) -> Self {
+        self.handlers.register_fallback(handler);
+        self.frozen = false;
+        self
+    }
+
+    fn freeze(mut self) -> Self {
+        self.frozen = true;
+        self
+    }
+
+    fn is_frozen(&self) -> bool {
+        self.frozen
+    }
+
+    async fn consume_blocking(&self) -> Result<(), MessagingError> {
+        if !self.frozen {
+            return Err(MessagingError::InternalError);
+        }
+
+        while let Some(msg) = self.next_message().await {
+            let _ = self.handlers.dispatch(&Context::current(), &msg).await;
+
+            let mut state = self.state();
+            state.in_flight -= 1;
+            state.wake_all();
+        }
+
+        Ok(())
+    }
+
+    async fn consume_n(&self, n: usize) -> Result<Vec<ConsumerMessage>, MessagingError> {
+        if !self.frozen {
+            return Err(MessagingError::InternalError);
+        }
+
+        let mut msgs = Vec::with_capacity(n);
+        while msgs.len() < n
+            && let Some(msg) = self.next_message().await
+        {
+            msgs.push(msg);
+
+            let mut state = self.state();
+            state.in_flight -= 1;
+            state.wake_all();
+        }
+
+        Ok(msgs)
+    }
+
+    /// Lists the destinations messages were published to, sorted by name.
+    async fn list_destinations(&self) -> Result<Vec<String>, MessagingError> {
+        let mut names: Vec<String> = self.state().queues.keys().cloned().collect();
+        names.sort();
+        Ok(names)
+    }
+
+    async fn drain(&self, timeout: Duration) -> Result<(), MessagingError> {
+        {
+            let mut state = self.state();
+            state.draining = true;
+            state.wake_all();
+        }
+
+        let drained = poll_fn(|cx| {
+            let mut state = self.state();
+            if state.in_flight == 0 {
+                return Poll::Ready(());
+            }
+            state.wakers.push(cx.waker().clone());
+            Poll::Pending
+        });
+
+        timer::timeout(&ThreadTimer, timeout, drained)
+            .await
+            .ok_or(MessagingError::TimeoutError)
+    }
+
+    fn definitions(&self) -> Vec<DispatcherDefinition> {
+        self.handlers.definitions().cloned().collect()
+    }
+
+    fn handler_count(&self) -> usize {
+        self.handlers.len()
+    }
+
+    async fn pause(&self) {
+        self.state().paused = true;
+    }
+
+    async fn resume(&self) {
+        let mut state = self.state();
+        state.paused = false;
+        state.wake_all();
+    }
+
+    fn is_paused(&self) -> bool {
+        self.state().paused
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        handler::fn_handler,
+        test_support::{block_on, poll_once, yield_now},
+    };
+    use std::{
+        future::pending,
+        pin::pin,
+        sync::atomic::{AtomicUsize, Ordering as AtomicOrdering},
+    };
+
+    fn counting_handler(count: &Arc<AtomicUsize>) -> Arc<dyn ConsumerHandler> {
+        let count = count.clone();
+        fn_handler(move |_, _| {
+            count.fetch_add(1, AtomicOrdering::SeqCst);
+            async { Ok(()) }
+        })
+    }
+
+    fn publish(broker: &InMemoryBroker, to: &str) {
+        let msg = PublishMessage::new(None, to, None, Some("event"), b"data", None);
+        block_on(broker.publish(&Context::new(), &msg)).unwrap();
+    }
+
+    fn recording_handler(received: &Arc<Mutex<Vec<ConsumerMessage>>>) -> Arc<dyn ConsumerHandler> {
+        let received = received.clone();
+        fn_handler(move |_, msg| {
+            received.lock().unwrap().push(msg.clone());
+            async { Ok(()) }
+        })
+    }
+
+    #[test]
+    fn delivers_published_messages_to_the_registered_handler() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let consumer = InMemoryBroker::new();
+        let publisher = consumer.clone();
+        let consumer = consumer
+            .register(
+                &DispatcherDefinition::new("orders", None),
+                recording_handler(&received),
+            )
+            .freeze();
+
+        let mut msg = PublishMessage::new(None, "orders", None, Some("created"), b"data", None);
+        msg.set_header("tenant", "acme");
+        block_on(publisher.publish(&Context::new(), &msg)).unwrap();
+        publisher.close();
+        block_on(consumer.consume_blocking()).unwrap();
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].from, "orders");
+        assert_eq!(received[0].msg_type, "created");
+        assert_eq!(&*received[0].data, b"data");
+        assert_eq!(received[0].header("tenant"), Some("acme"));
+    }
+
+    #[test]
+    fn filters_messages_by_msg_type() {
+        let created = Arc::new(Mutex::new(Vec::new()));
+        let broker = InMemoryBroker::new()
+            .register(
+                &DispatcherDefinition::new("orders", Some("created")),
+                recording_handler(&created),
+            )
+            .freeze();
+
+        for msg_type in ["created", "cancelled"] {
+            let msg = PublishMessage::new(None, "orders", None, Some(msg_type), b"data", None);
+            block_on(broker.publish(&Context::new(), &msg)).unwrap();
+        }
+        broker.close();
+        block_on(broker.consume_blocking()).unwrap();
+
+        let created = created.lock().unwrap();
+        assert_eq!(created.len(), 1);
+        assert_eq!(created[0].msg_type, "created");
+        assert_eq!(broker.pending("orders"), 0);
+    }
+
+    #[test]
+    fn keeps_messages_for_unconsumed_destinations_queued() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let broker = InMemoryBroker::new()
+            .register(
+                &DispatcherDefinition::new("orders", None),
+                counting_handler(&count),
+            )
+            .freeze();
+        publish(&broker, "invoices");
+        broker.close();
+
+        block_on(broker.consume_blocking()).unwrap();
+
+        assert_eq!(count.load(AtomicOrdering::SeqCst), 0);
+        assert_eq!(broker.pending("invoices"), 1);
+    }
+
+    #[test]
+    fn consume_fails_before_freeze() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let broker = InMemoryBroker::new().register(
+            &DispatcherDefinition::new("orders", None),
+            counting_handler(&count),
+        );
+        publish(&broker, "orders");
+        broker.close();
+
+        assert!(!broker.is_frozen());
+        assert_eq!(
+            block_on(broker.consume_blocking()),
+            Err(MessagingError::InternalError)
+        );
+        assert_eq!(
+            block_on(broker.consume_n(1)),
+            Err(MessagingError::InternalError)
+        );
+        assert_eq!(count.load(AtomicOrdering::SeqCst), 0);
+        assert_eq!(broker.pending("orders"), 1);
+    }
+
+    #[test]
+    fn registering_after_freeze_reopens_registration() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let broker = InMemoryBroker::new()
+            .register(
+                &DispatcherDefinition::new("orders", None),
+                counting_handler(&count),
+            )
+            .freeze()
+            .register(
+                &DispatcherDefinition::new("invoices", None),
+                counting_handler(&count),
+            );
+
+        assert!(!broker.is_frozen());
+        broker.close();
+        assert_eq!(
+            block_on(broker.consume_blocking()),
+            Err(MessagingError::InternalError)
+        );
+    }
+
+    #[test]
+    fn consume_after_freeze_delivers_to_every_registration() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let broker = InMemoryBroker::new()
+            .register(
+                &DispatcherDefinition::new("orders", None),
+                counting_handler(&count),
+            )
+            .register(
+                &DispatcherDefinition::new("invoices", None),
+                counting_handler(&count),
+            )
+            .freeze();
+        publish(&broker, "orders");
+        publish(&broker, "invoices");
+        broker.close();
+
+        assert!(broker.is_frozen());
+        assert_eq!(block_on(broker.consume_blocking()), Ok(()));
+        assert_eq!(count.load(AtomicOrdering::SeqCst), 2);
+    }
+
+    #[test]
+    fn reports_the_registered_handlers() {
+        let created = DispatcherDefinition::new("orders", Some("created"));
+        let invoices = DispatcherDefinition::new("invoices", None);
+        let broker = InMemoryBroker::new()
+            .register(&created, fn_handler(|_, _| async { Ok(()) }))
+            .register(&invoices, fn_handler(|_, _| async { Ok(()) }));
+
+        assert_eq!(broker.handler_count(), 2);
+        let definitions: Vec<_> = broker
+            .definitions()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(definitions, [created.to_string(), invoices.to_string()]);
+    }
+
+    #[test]
+    fn holds_back_deliveries_while_paused() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let broker = InMemoryBroker::new()
+            .register(
+                &DispatcherDefinition::new("orders", None),
+                counting_handler(&count),
+            )
+            .freeze();
+        block_on(broker.pause());
+        publish(&broker, "orders");
+
+        let mut consume = pin!(broker.consume_blocking());
+        assert!(poll_once(consume.as_mut()).is_pending());
+        assert!(broker.is_paused());
+        assert_eq!(count.load(AtomicOrdering::SeqCst), 0);
+        assert_eq!(broker.pending("orders"), 1);
+
+        block_on(broker.resume());
+        broker.close();
+        assert_eq!(block_on(consume), Ok(()));
+        assert!(!broker.is_paused());
+        assert_eq!(count.load(AtomicOrdering::SeqCst), 1);
+    }
+
+    #[test]
+    fn sends_unknown_types_to_the_fallback() {
+        let created = Arc::new(AtomicUsize::new(0));
+        let fallback = Arc::new(AtomicUsize::new(0));
+        let broker = InMemoryBroker::new()
+            .register(
+                &DispatcherDefinition::new("orders", Some("created")),
+                counting_handler(&created),
+            )
+            .register_fallback(counting_handler(&fallback))
+            .freeze();
+
+        for msg_type in ["created", "cancelled", "created"] {
+            let msg = PublishMessage::new(None, "orders", None, Some(msg_type), b"data", None);
+            block_on(broker.publish(&Context::new(), &msg)).unwrap();
+        }
+        broker.close();
+        block_on(broker.consume_blocking()).unwrap();
+
+        assert_eq!(created.load(AtomicOrdering::SeqCst), 2);
+        assert_eq!(fallback.load(AtomicOrdering::SeqCst), 1);
+    }
+
+    #[test]
+    fn freeze_is_per_clone() {
+        let frozen = InMemoryBroker::new().freeze();
+        let registering = frozen.clone().register(
+            &DispatcherDefinition::new("orders", None),
+            fn_handler(|_, _| async { Ok(()) }),
+        );
+
+        assert!(frozen.is_frozen());
+        assert!(!registering.is_frozen());
+    }
+
+    fn draining_broker(handler: Arc<dyn ConsumerHandler>) -> InMemoryBroker {
+        let broker = InMemoryBroker::new()
+            .register(&DispatcherDefinition::new("orders", None), handler)
+            .freeze();
+        publish(&broker, "orders");
+        publish(&broker, "orders");
+        broker
+    }
+
+    #[test]
+    fn drain_finishes_in_flight_messages_and_leaves_the_rest_queued() {
+        let broker = draining_broker(fn_handler(|_, _| async {
+            yield_now().await;
+            Ok(())
+        }));
+
+        let mut consume = pin!(broker.consume_blocking());
+        assert!(poll_once(consume.as_mut()).is_pending());
+
+        let mut drain = pin!(broker.drain(Duration::from_secs(5)));
+        assert!(poll_once(drain.as_mut()).is_pending());
+
+        assert_eq!(poll_once(consume.as_mut()), Poll::Ready(Ok(())));
+        assert_eq!(poll_once(drain.as_mut()), Poll::Ready(Ok(())));
+        assert_eq!(broker.pending("orders"), 1);
+    }
+
+    #[test]
+    fn drain_times_out_when_a_handler_does_not_finish() {
+        let broker = draining_broker(fn_handler(|_, _| pending()));
+
+        let mut consume = pin!(broker.consume_blocking());
+        assert!(poll_once(consume.as_mut()).is_pending());
+
+        assert_eq!(
+            block_on(broker.drain(Duration::from_millis(10))),
+            Err(MessagingError::TimeoutError)
+        );
+    }
+
+    #[test]
+    fn drain_without_in_flight_messages_stops_consumption() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let broker = draining_broker(counting_handler(&count));
+
+        assert_eq!(block_on(broker.drain(Duration::from_secs(5))), Ok(()));
+        assert_eq!(block_on(broker.consume_blocking()), Ok(()));
+        assert_eq!(count.load(AtomicOrdering::SeqCst), 0);
+        assert_eq!(broker.pending("orders"), 2);
+    }
+
+    #[test]
+    fn consume_n_takes_a_known_count_without_dispatching() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let broker = InMemoryBroker::new()
+            .register(
+                &DispatcherDefinition::new("orders", None),
+                counting_handler(&count),
+            )
+            .freeze();
+        for data in ["1", "2", "3"] {
+            let msg =
+                PublishMessage::new(None, "orders", None, Some("event"), data.as_bytes(), None);
+            block_on(broker.publish(&Context::new(), &msg)).unwrap();
+        }
+
+        let msgs = block_on(broker.consume_n(2)).unwrap();
+
+        let data: Vec<_> = msgs.iter().map(|msg| msg.data_string().unwrap()).collect();
+        assert_eq!(data, ["1", "2"]);
+        assert_eq!(broker.pending("orders"), 1);
+        assert_eq!(count.load(AtomicOrdering::SeqCst), 0);
+    }
+
+    #[test]
+    fn consume_n_returns_fewer_messages_once_closed() {
+        let broker = InMemoryBroker::new()
+            .register(
+                &DispatcherDefinition::new("orders", None),
+                fn_handler(|_, _| async { Ok(()) }),
+            )
+            .freeze();
+        publish(&broker, "orders");
+        broker.close();
+
+        assert_eq!(block_on(broker.consume_n(5)).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn consume_n_fails_before_freeze() {
+        let broker = InMemoryBroker::new();
+
+        assert_eq!(
+            block_on(broker.consume_n(1)),
+            Err(MessagingError::InternalError)
+        );
+    }
+
+    #[test]
+    fn delivers_the_group_id() {
+        let broker = InMemoryBroker::new()
+            .register(
+                &DispatcherDefinition::new("orders", None),
+                fn_handler(|_, _| async { Ok(()) }),
+            )
+            .freeze();
+        let grouped = PublishMessage::new(None, "orders", None, None, b"data", None)
+            .with_group_id("session-1");
+        block_on(broker.publish(&Context::new(), &grouped)).unwrap();
+        publish(&broker, "orders");
+
+        let groups: Vec<_> = block_on(broker.consume_n(2))
+            .unwrap()
+            .into_iter()
+            .map(|msg| msg.group_id)
+            .collect();
+
+        assert_eq!(groups, [Some("session-1".to_owned()), None]);
+    }
+
+    #[test]
+    fn lists_the_destinations_published_to_sorted_by_name() {
+        let broker = InMemoryBroker::new();
+        assert_eq!(block_on(broker.list_destinations()), Ok(Vec::new()));
+
+        publish(&broker, "payments");
+        publish(&broker, "orders");
+        publish(&broker, "payments");
+
+        assert_eq!(
+            block_on(broker.list_destinations()),
+            Ok(vec!["orders".to_owned(), "payments".to_owned()])
+        );
+    }
+
+    fn publish_with_priority(broker: &InMemoryBroker, data: &str, priority: Option<u8>) {
+        let mut msg =
+            PublishMessage::new(None, "orders", None, Some("event"), data.as_bytes(), None);
+        msg.priority = priority;
+        block_on(broker.publish(&Context::new(), &msg)).unwrap();
+    }
+
+    #[test]
+    fn delivers_higher_priority_messages_published_later_first() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let broker = InMemoryBroker::new()
+            .register(
+                &DispatcherDefinition::new("orders", None),
+                recording_handler(&received),
+            )
+            .freeze();
+        publish_with_priority(&broker, "low", Some(1));
+        publish_with_priority(&broker, "high", Some(9));
+        broker.close();
+
+        block_on(broker.consume_blocking()).unwrap();
+
+        let data: Vec<_> = received
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|msg| msg.data_string().unwrap())
+            .collect();
+        assert_eq!(data, ["high", "low"]);
+    }
+
+    #[test]
+    fn keeps_publish_order_within_a_priority() {
+        let broker = InMemoryBroker::new()
+            .register(
+                &DispatcherDefinition::new("orders", None),
+                fn_handler(|_, _| async { Ok(()) }),
+            )
+            .freeze();
+        publish_with_priority(&broker, "first", None);
+        publish_with_priority(&broker, "urgent", Some(5));
+        publish_with_priority(&broker, "second", Some(0));
+        publish_with_priority(&broker, "third", None);
+
+        let data: Vec<_> = block_on(broker.consume_n(4))
+            .unwrap()
+            .iter()
+            .map(|msg| msg.data_string().unwrap())
+            .collect();
+
+        assert_eq!(data, ["urgent", "first", "second", "third"]);
+    }
+}
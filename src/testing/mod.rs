@@ -0,0 +1,19 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Testing
+//!
+//! This module provides in-process implementations of the messaging traits for tests.
+//!
+//! They require no running broker, which makes integration-style tests of code depending
+//! on [`Publisher`](crate::publisher::Publisher) and
+//! [`Dispatcher`](crate::dispatcher::Dispatcher) cheap and deterministic. This module is
+//! only available with the `test-util` feature; the `TestDispatcher`, which complements
+//! the mocks, is also available with the `mocks` feature.
+
+pub mod dispatcher;
+#[cfg(feature = "test-util")]
+pub mod in_memory;
+#[cfg(feature = "test-util")]
+pub mod recording;
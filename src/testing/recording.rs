@@ -0,0 +1,131 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Recording Publisher
+//!
+//! This module provides a publisher that records every message it is given.
+//!
+//! Unlike the expectation-based `MockPublisher`, the `RecordingPublisher` lets a test
+//! publish freely and assert on everything that was sent afterwards.
+
+use crate::{
+    errors::MessagingError,
+    publisher::{PublishMessage, Publisher},
+};
+use async_trait::async_trait;
+use opentelemetry::Context;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// A publisher that stores published messages in memory.
+///
+/// Every successful `publish` records the message in call order. When a failure is armed
+/// with [`RecordingPublisher::arm_failure`], `publish` returns that error instead and the
+/// message is not recorded, until [`RecordingPublisher::disarm`] is called.
+#[derive(Default)]
+pub struct RecordingPublisher {
+    published: Mutex<Vec<PublishMessage>>,
+    failure: Mutex<Option<MessagingError>>,
+}
+
+impl RecordingPublisher {
+    /// Creates a new recording publisher.
+    ///
+    /// # Returns
+    ///
+    /// A new `RecordingPublisher` instance with no recorded message.
+    pub fn new() -> Self {
+        RecordingPublisher::default()
+    }
+
+    /// Returns the messages published so far, in the order they were published.
+    pub fn published(&self) -> Vec<PublishMessage> {
+        lock(&self.published).clone()
+    }
+
+    /// Makes every following `publish` call fail with the given error.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The error to return from `publish`.
+    pub fn arm_failure(&self, error: MessagingError) {
+        *lock(&self.failure) = Some(error);
+    }
+
+    /// Makes `publish` succeed again after a failure was armed.
+    pub fn disarm(&self) {
+        *lock(&self.failure) = None;
+    }
+
+    /// Removes all recorded messages.
+    pub fn clear(&self) {
+        lock(&self.published).clear();
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[async_trait]
+impl Publisher for RecordingPublisher {
+    async fn publish(&self, _ctx: &Context, msg: &PublishMessage) -> Result<(), MessagingError> {
+        if let Some(error) = lock(&self.failure).clone() {
+            return Err(error);
+        }
+
+        lock(&self.published).push(msg.clone());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{block_on, message};
+
+    fn publish(publisher: &RecordingPublisher, to: &str) -> Result<(), MessagingError> {
+        block_on(publisher.publish(&Context::new(), &message(to, "data")))
+    }
+
+    #[test]
+    fn records_messages_in_publish_order() {
+        let publisher = RecordingPublisher::new();
+
+        publish(&publisher, "orders").unwrap();
+        publish(&publisher, "invoices").unwrap();
+
+        let to: Vec<_> = publisher
+            .published()
+            .into_iter()
+            .map(|msg| msg.to)
+            .collect();
+        assert_eq!(to, ["orders", "invoices"]);
+    }
+
+    #[test]
+    fn armed_failures_are_returned_and_not_recorded() {
+        let publisher = RecordingPublisher::new();
+        publisher.arm_failure(MessagingError::ConnectionError);
+
+        assert_eq!(
+            publish(&publisher, "orders"),
+            Err(MessagingError::ConnectionError)
+        );
+        assert!(publisher.published().is_empty());
+
+        publisher.disarm();
+        assert_eq!(publish(&publisher, "orders"), Ok(()));
+        assert_eq!(publisher.published().len(), 1);
+    }
+
+    #[test]
+    fn clear_removes_recorded_messages() {
+        let publisher = RecordingPublisher::new();
+        publish(&publisher, "orders").unwrap();
+
+        publisher.clear();
+
+        assert!(publisher.published().is_empty());
+    }
+}
@@ -0,0 +1,135 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Timer
+//!
+//! This module provides the timer abstraction used by time-based wrappers.
+//!
+//! The crate does not depend on a specific async runtime. Components that need to wait,
+//! such as timeouts or backoffs, take a [`Timer`] which applications can implement on top
+//! of their runtime, e.g. by delegating to `tokio::time::sleep`. [`ThreadTimer`] is a
+//! runtime-independent fallback.
+
+use async_trait::async_trait;
+use std::{
+    future::{Future, poll_fn},
+    pin::pin,
+    sync::{Arc, Mutex, PoisonError},
+    task::{Poll, Waker},
+    thread,
+    time::Duration,
+};
+
+#[cfg(feature = "mocks")]
+use mockall::*;
+
+/// Defines the interface for waiting for a duration.
+#[cfg_attr(feature = "mocks", automock)]
+#[async_trait]
+pub trait Timer: Send + Sync {
+    /// Completes once the given duration has elapsed.
+    ///
+    /// # Arguments
+    ///
+    /// * `duration` - How long to wait.
+    async fn sleep(&self, duration: Duration);
+}
+
+/// A timer that waits on a dedicated OS thread.
+///
+/// Each `sleep` spawns a thread, which works with any executor but is more expensive than
+/// a runtime timer. Prefer a runtime-backed `Timer` for high volumes.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ThreadTimer;
+
+#[async_trait]
+impl Timer for ThreadTimer {
+    async fn sleep(&self, duration: Duration) {
+        let state = Arc::new(Mutex::new((false, None::<Waker>)));
+
+        let shared = state.clone();
+        thread::spawn(move || {
+            thread::sleep(duration);
+            let mut state = shared.lock().unwrap_or_else(PoisonError::into_inner);
+            state.0 = true;
+            if let Some(waker) = state.1.take() {
+                waker.wake();
+            }
+        });
+
+        poll_fn(|cx| {
+            let mut state = state.lock().unwrap_or_else(PoisonError::into_inner);
+            if state.0 {
+                return Poll::Ready(());
+            }
+            state.1 = Some(cx.waker().clone());
+            Poll::Pending
+        })
+        .await
+    }
+}
+
+/// Runs a future until it completes or the duration elapses.
+///
+/// When the duration elapses first, the future is dropped. Work it already handed off to
+/// other tasks or threads is not cancelled.
+///
+/// # Arguments
+///
+/// * `timer` - The timer used to measure the duration.
+/// * `duration` - The maximum time to wait for the future.
+/// * `future` - The future to run.
+///
+/// # Returns
+///
+/// The output of the future, or `None` if the duration elapsed first.
+pub async fn timeout<F>(timer: &dyn Timer, duration: Duration, future: F) -> Option<F::Output>
+where
+    F: Future,
+{
+    let mut future = pin!(future);
+    let mut sleep = timer.sleep(duration);
+
+    poll_fn(|cx| {
+        if let Poll::Ready(output) = future.as_mut().poll(cx) {
+            return Poll::Ready(Some(output));
+        }
+        sleep.as_mut().poll(cx).map(|_| None)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::block_on;
+    use std::{future, time::Instant};
+
+    #[test]
+    fn thread_timer_sleeps_for_the_duration() {
+        let start = Instant::now();
+
+        block_on(ThreadTimer.sleep(Duration::from_millis(20)));
+
+        assert!(start.elapsed() >= Duration::from_millis(20));
+    }
+
+    #[test]
+    fn timeout_returns_the_output_of_a_future_completing_in_time() {
+        let output = block_on(timeout(&ThreadTimer, Duration::from_secs(10), async { 42 }));
+
+        assert_eq!(output, Some(42));
+    }
+
+    #[test]
+    fn timeout_drops_a_future_exceeding_the_duration() {
+        let output = block_on(timeout(
+            &ThreadTimer,
+            Duration::from_millis(10),
+            future::pending::<()>(),
+        ));
+
+        assert_eq!(output, None);
+    }
+}
@@ -0,0 +1,309 @@
+// Copyright (c) 2025, The Ruskit Authors
+// MIT License
+// All rights reserved.
+
+//! # Typed
+//!
+//! This module provides typed helpers on top of the byte-oriented messaging traits.
+//!
+//! The helpers use the [`Encode`] and [`Decode`] traits from the [`codec`](crate::codec)
+//! module, so handlers and producers can work with their own types instead of raw data,
+//! optionally wrapped in a [`MessageEnvelope`] carrying the message metadata.
+
+use crate::{
+    codec::{Decode, Encode},
+    errors::MessagingError,
+    handler::{ConsumerHandler, ConsumerMessage},
+    headers,
+    publisher::{HeaderValues, PublishMessage, Publisher},
+};
+use async_trait::async_trait;
+use opentelemetry::Context;
+use std::{collections::HashMap, future::Future, marker::PhantomData, sync::Arc};
+
+/// A publisher bound to a single destination and message type.
+///
+/// Every value sent is encoded with its [`Encode`] implementation and published with the
+/// configured destination, message type and the matching `content-type` header.
+pub struct TypedPublisher<T> {
+    publisher: Arc<dyn Publisher>,
+    to: String,
+    msg_type: String,
+    _marker: PhantomData<fn(&T)>,
+}
+
+impl<T> TypedPublisher<T>
+where
+    T: Encode,
+{
+    /// Creates a new typed publisher.
+    ///
+    /// # Arguments
+    ///
+    /// * `publisher` - The publisher used to send messages.
+    /// * `to` - The destination of every message.
+    /// * `msg_type` - The message type of every message.
+    ///
+    /// # Returns
+    ///
+    /// A new `TypedPublisher` instance.
+    pub fn new<S>(publisher: Arc<dyn Publisher>, to: S, msg_type: S) -> Self
+    where
+        S: Into<String>,
+    {
+        TypedPublisher {
+            publisher,
+            to: to.into(),
+            msg_type: msg_type.into(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Encodes and publishes a value.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The OpenTelemetry context for tracing and monitoring.
+    /// * `value` - The value to publish.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or containing an error if encoding or publishing fails.
+    pub async fn send(&self, ctx: &Context, value: &T) -> Result<(), MessagingError> {
+        let data = value.encode()?;
+        let headers = HashMap::from([(
+            headers::CONTENT_TYPE.to_owned(),
+            HeaderValues::ShortString(T::CONTENT_TYPE.to_owned()),
+        )]);
+
+        let msg = PublishMessage::new(
+            None,
+            self.to.as_str(),
+            None,
+            Some(self.msg_type.as_str()),
+            &data,
+            Some(headers),
+        );
+
+        self.publisher.publish(ctx, &msg).await
+    }
+}
+
+/// A consumed message with its data decoded into a typed payload.
+///
+/// Handlers can work with a `MessageEnvelope<T>` instead of the raw `ConsumerMessage`
+/// while keeping access to the message metadata.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageEnvelope<T> {
+    /// The source of the message.
+    pub from: String,
+
+    /// The message type identifier.
+    pub msg_type: String,
+
+    /// The headers of the message.
+    pub headers: Option<HashMap<String, String>>,
+
+    /// The unique identifier of the message, if known.
+    pub message_id: Option<String>,
+
+    /// The decoded message data.
+    pub payload: T,
+}
+
+impl<T> TryFrom<&ConsumerMessage> for MessageEnvelope<T>
+where
+    T: Decode,
+{
+    type Error = MessagingError;
+
+    /// Decodes the data of a consumed message into an envelope.
+    ///
+    /// The message id is taken from [`ConsumerMessage::message_id`], so it falls back to
+    /// the `message-id` header.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The consumed message.
+    ///
+    /// # Returns
+    ///
+    /// The envelope, or `MessagingError::DeserializingError` if the data cannot be decoded.
+    fn try_from(msg: &ConsumerMessage) -> Result<Self, Self::Error> {
+        let payload = T::decode(&msg.data).map_err(|_| MessagingError::DeserializingError)?;
+
+        Ok(MessageEnvelope {
+            from: msg.from.clone(),
+            msg_type: msg.msg_type.clone(),
+            headers: msg.headers.clone(),
+            message_id: msg.message_id().map(str::to_owned),
+            payload,
+        })
+    }
+}
+
+struct TypedHandler<T, F, Fut> {
+    f: F,
+    _marker: PhantomData<fn(T) -> Fut>,
+}
+
+#[async_trait]
+impl<T, F, Fut> ConsumerHandler for TypedHandler<T, F, Fut>
+where
+    T: Decode,
+    F: Fn(&Context, T) -> Fut + Send + Sync,
+    Fut: Future<Output = Result<(), MessagingError>> + Send,
+{
+    async fn exec(&self, ctx: &Context, msg: &ConsumerMessage) -> Result<(), MessagingError> {
+        let value = T::decode(&msg.data)?;
+        (self.f)(ctx, value).await
+    }
+}
+
+/// Creates a handler from a closure receiving the decoded message data.
+///
+/// Messages whose data cannot be decoded fail with the error of the [`Decode`]
+/// implementation and the closure is not invoked.
+///
+/// # Arguments
+///
+/// * `f` - The closure invoked with every decoded value.
+///
+/// # Returns
+///
+/// The closure as a `ConsumerHandler`, ready to be registered with a dispatcher.
+pub fn typed_handler<T, F, Fut>(f: F) -> Arc<dyn ConsumerHandler>
+where
+    T: Decode + 'static,
+    F: Fn(&Context, T) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<(), MessagingError>> + Send + 'static,
+{
+    Arc::new(TypedHandler {
+        f,
+        _marker: PhantomData,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        dispatcher::{DispatcherDefinition, HandlerRegistry},
+        test_support::{TestPublisher, block_on},
+    };
+    use std::sync::Mutex;
+
+    struct Unencodable;
+
+    impl Encode for Unencodable {
+        const CONTENT_TYPE: &'static str = "application/x-unencodable";
+
+        fn encode(&self) -> Result<Vec<u8>, MessagingError> {
+            Err(MessagingError::serializing("cannot encode"))
+        }
+    }
+
+    #[test]
+    fn send_publishes_the_encoded_value() {
+        let inner = Arc::new(TestPublisher::default());
+        let publisher = TypedPublisher::<String>::new(inner.clone(), "orders", "created");
+
+        block_on(publisher.send(&Context::new(), &"data".to_owned())).unwrap();
+
+        let published = inner.published();
+        assert_eq!(published.len(), 1);
+        assert_eq!(published[0].to, "orders");
+        assert_eq!(published[0].msg_type.as_deref(), Some("created"));
+        assert_eq!(&*published[0].data, b"data");
+        assert_eq!(
+            published[0].headers.as_ref().unwrap()[headers::CONTENT_TYPE],
+            HeaderValues::ShortString(String::CONTENT_TYPE.to_owned())
+        );
+    }
+
+    #[test]
+    fn send_does_not_publish_values_failing_to_encode() {
+        let inner = Arc::new(TestPublisher::default());
+        let publisher = TypedPublisher::new(inner.clone(), "orders", "created");
+
+        let result = block_on(publisher.send(&Context::new(), &Unencodable));
+
+        assert_eq!(result, Err(MessagingError::serializing("cannot encode")));
+        assert!(inner.published().is_empty());
+    }
+
+    #[test]
+    fn registered_typed_handler_runs_with_the_decoded_value() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let recorded = received.clone();
+        let handler = typed_handler(move |_, value: String| {
+            recorded.lock().unwrap().push(value);
+            async { Ok(()) }
+        });
+        let mut registry = HandlerRegistry::new();
+        registry.register(&DispatcherDefinition::new("orders", None), handler);
+
+        let msg = ConsumerMessage::new("orders", "created", b"data", None);
+        block_on(registry.dispatch(&Context::new(), &msg)).unwrap();
+
+        assert_eq!(*received.lock().unwrap(), ["data"]);
+    }
+
+    #[test]
+    fn typed_handler_fails_undecodable_messages_without_running() {
+        let runs = Arc::new(Mutex::new(0));
+        let counted = runs.clone();
+        let handler = typed_handler(move |_, _: String| {
+            *counted.lock().unwrap() += 1;
+            async { Ok(()) }
+        });
+
+        let msg = ConsumerMessage::new("orders", "created", &[0xff], None);
+        let result = block_on(handler.exec(&Context::new(), &msg));
+
+        assert_eq!(result, Err(MessagingError::DeserializingError));
+        assert_eq!(*runs.lock().unwrap(), 0);
+    }
+
+    #[test]
+    fn typed_handler_returns_the_closure_error() {
+        let handler = typed_handler(|_, _: Vec<u8>| async { Err(MessagingError::HandlerError) });
+
+        let msg = ConsumerMessage::new("orders", "created", b"data", None);
+
+        assert_eq!(
+            block_on(handler.exec(&Context::new(), &msg)),
+            Err(MessagingError::HandlerError)
+        );
+    }
+
+    #[test]
+    fn envelope_carries_the_metadata_and_decoded_payload() {
+        let json = r#"{"id":42}"#;
+        let headers = HashMap::from([(headers::MESSAGE_ID.to_owned(), "m-1".to_owned())]);
+        let msg = ConsumerMessage::new("orders", "created", json.as_bytes(), Some(headers));
+
+        let envelope = MessageEnvelope::<String>::try_from(&msg).unwrap();
+
+        assert_eq!(
+            envelope,
+            MessageEnvelope {
+                from: "orders".to_owned(),
+                msg_type: "created".to_owned(),
+                headers: msg.headers.clone(),
+                message_id: Some("m-1".to_owned()),
+                payload: json.to_owned(),
+            }
+        );
+    }
+
+    #[test]
+    fn envelope_rejects_undecodable_data() {
+        let msg = ConsumerMessage::new("orders", "created", &[0xff], None);
+
+        assert_eq!(
+            MessageEnvelope::<String>::try_from(&msg),
+            Err(MessagingError::DeserializingError)
+        );
+    }
+}